@@ -0,0 +1,301 @@
+// ============================================================================
+// ASSET PROTOCOL
+// ============================================================================
+//
+// `inkdown-asset://localhost/<percent-encoded absolute path>` serves files
+// inside an open workspace straight to the webview, so images, PDFs, audio
+// and video don't have to be base64-encoded through `read_file_binary`. The
+// frontend builds these URLs with `convertFileSrc(path, "inkdown-asset")`,
+// which also picks the `http://inkdown-asset.localhost/` form on Windows.
+//
+// Supports single `Range` requests so media can seek, and answers
+// `If-None-Match` with 304 using an ETag derived from size and mtime.
+// Images can be requested downscaled with `?thumbnail=<max size>`. Every
+// asset but PDFs is served with a sandboxing Content-Security-Policy, so an
+// SVG or HTML file opened on its own can't run scripts.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use percent_encoding::percent_decode_str;
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::UriSchemeResponder;
+
+use crate::{sandbox, thumbnail};
+
+/// URI scheme registered in `run()`
+pub const SCHEME: &str = "inkdown-asset";
+
+/// Origins of the bundled frontend: `tauri://localhost` on macOS and Linux,
+/// `http(s)://tauri.localhost` on Windows
+const APP_ORIGINS: [&str; 3] = ["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"];
+
+/// Largest body returned for an open-ended range like `bytes=0-`; the
+/// webview asks for the rest as playback reaches it
+const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Keeps an asset opened as a document, e.g. an SVG, from running scripts
+/// or loading anything; inline styles are still needed to render SVGs
+const ASSET_CSP: &str = "default-src 'none'; style-src 'unsafe-inline'; sandbox";
+
+/// Answer a request off the main thread
+pub fn handle(app: &tauri::AppHandle, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let response = respond(&app, &request)
+            .unwrap_or_else(|status| empty(status).body(Vec::new()).unwrap_or_default());
+        responder.respond(response);
+    });
+}
+
+fn respond(app: &tauri::AppHandle, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, StatusCode> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return Err(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let path = request_path(request.uri().path()).ok_or(StatusCode::BAD_REQUEST)?;
+
+    // Only the open workspaces, not the app directories or picked files
+    sandbox::check_workspace(app, &path).map_err(|_| StatusCode::FORBIDDEN)?;
+
+    // `?thumbnail=<max size>` serves a cached, downscaled copy instead; the
+    // original is served if it can't be made
+    let path = match thumbnail_size(request.uri().query()) {
+        Some(max_size) if thumbnail::is_supported(&path) => {
+            thumbnail::thumbnail_path(app, &path, max_size).unwrap_or(path)
+        }
+        _ => path,
+    };
+
+    let metadata = fs::metadata(&path).map_err(|_| StatusCode::NOT_FOUND)?;
+    if !metadata.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let len = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let etag = format!("\"{:x}-{:x}\"", len, modified);
+
+    let content_type = mime_type(&path);
+    let mut builder = allow_app_origin(app, request, empty(StatusCode::OK))
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ETAG, &etag);
+
+    // The webview's PDF viewer is a plugin, which a sandboxed document can't load
+    if content_type != "application/pdf" {
+        builder = builder.header(header::CONTENT_SECURITY_POLICY, ASSET_CSP);
+    }
+
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+
+    if not_modified {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Vec::new())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let range = match request.headers().get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(value) => match parse_range(value, len) {
+            Some(range) => Some(range),
+            None => {
+                return builder
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                    .body(Vec::new())
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+        None => None,
+    };
+
+    let (status, start, end) = match range {
+        Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end),
+        None => (StatusCode::OK, 0, len.saturating_sub(1)),
+    };
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+
+    let mut builder = builder
+        .status(status)
+        .header(header::CONTENT_LENGTH, body_len);
+    if range.is_some() {
+        builder = builder.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len));
+    }
+
+    let body = if request.method() == Method::HEAD {
+        Vec::new()
+    } else {
+        read_range(&path, start, body_len).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    };
+
+    builder.body(body).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Response builder with the headers every answer carries
+fn empty(status: StatusCode) -> tauri::http::response::Builder {
+    // nosniff keeps unknown files from being rendered as HTML
+    Response::builder()
+        .status(status)
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+}
+
+/// Let the app's own pages `fetch` assets as well as embed them
+///
+/// The origin is echoed only if it is the app's, so a remote page loaded in
+/// the webview can't read vault files.
+fn allow_app_origin(
+    app: &tauri::AppHandle,
+    request: &Request<Vec<u8>>,
+    builder: tauri::http::response::Builder,
+) -> tauri::http::response::Builder {
+    let builder = builder.header(header::VARY, "Origin");
+
+    let Some(origin) = request.headers().get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return builder;
+    };
+
+    if is_app_origin(app, origin) {
+        builder.header(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin)
+    } else {
+        builder
+    }
+}
+
+/// Whether an origin serves the bundled frontend, or the dev server in
+/// debug builds
+fn is_app_origin(app: &tauri::AppHandle, origin: &str) -> bool {
+    if APP_ORIGINS.contains(&origin) {
+        return true;
+    }
+
+    cfg!(debug_assertions)
+        && app
+            .config()
+            .build
+            .dev_url
+            .as_ref()
+            .is_some_and(|url| url.origin().ascii_serialization() == origin)
+}
+
+/// Absolute file path from the URI path, `/<percent-encoded path>`
+fn request_path(uri_path: &str) -> Option<PathBuf> {
+    let encoded = uri_path.strip_prefix('/').unwrap_or(uri_path);
+    let decoded = percent_decode_str(encoded).decode_utf8().ok()?;
+    let path = PathBuf::from(decoded.as_ref());
+
+    path.is_absolute().then_some(path)
+}
+
+/// Requested thumbnail size from the query string
+fn thumbnail_size(query: Option<&str>) -> Option<u32> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("thumbnail="))
+        .and_then(|size| size.parse().ok())
+}
+
+/// Inclusive byte range for a single-range `Range` header; `None` if it
+/// can't be satisfied. Multiple ranges are answered with the first one.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+
+    if len == 0 {
+        return None;
+    }
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // `bytes=-500`: the last 500 bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return None;
+            }
+            (len.saturating_sub(suffix), len - 1)
+        }
+        // `bytes=500-`: from 500 on, capped so large media streams in chunks
+        (start, "") => {
+            let start: u64 = start.parse().ok()?;
+            (start, (start.saturating_add(MAX_RANGE_LEN) - 1).min(len - 1))
+        }
+        (start, end) => {
+            let start: u64 = start.parse().ok()?;
+            let end: u64 = end.parse().ok()?;
+            (start, end.min(len - 1))
+        }
+    };
+
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn read_range(path: &Path, start: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut body = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut body)?;
+    Ok(body)
+}
+
+/// MIME type from the file extension
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        // Images
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "tif" | "tiff" => "image/tiff",
+        // Audio
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" | "oga" => "audio/ogg",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        "aac" => "audio/aac",
+        "opus" => "audio/opus",
+        // Video
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "ogv" => "video/ogg",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        // Documents and text
+        "pdf" => "application/pdf",
+        "md" | "markdown" => "text/markdown; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "json" => "application/json",
+        // Fonts
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
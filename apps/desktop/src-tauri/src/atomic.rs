@@ -0,0 +1,124 @@
+// ============================================================================
+// ATOMIC FILE WRITES
+// ============================================================================
+//
+// Every backend write goes through `write_atomic`: the new content is written
+// to a temp file next to the target, fsynced, then renamed over the target.
+// A crash or a full disk mid-save leaves either the old or the new file on
+// disk, never a truncated one.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Marker used in temp file names so other parts of the backend can skip them
+const TEMP_MARKER: &str = ".inkdown-";
+
+/// Upper bound on symlink hops, same as Linux's MAXSYMLINKS
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Atomically replace the contents of `path`
+///
+/// Symlinks are written through (the link keeps pointing at its target) and
+/// the permissions of an existing file are carried over to the new one.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let target = resolve_symlinks(path)?;
+
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let permissions = fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let temp_path = dir.join(format!(
+        ".{}{}{}-{}.tmp",
+        file_name.to_string_lossy(),
+        TEMP_MARKER,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = write_temp_and_rename(&temp_path, &target, contents.as_ref(), permissions);
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    } else {
+        sync_dir(&dir);
+    }
+
+    result
+}
+
+/// Whether a path is one of our in-flight temp files
+pub fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.') && n.contains(TEMP_MARKER) && n.ends_with(".tmp"))
+        .unwrap_or(false)
+}
+
+fn write_temp_and_rename(
+    temp_path: &Path,
+    target: &Path,
+    contents: &[u8],
+    permissions: Option<fs::Permissions>,
+) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+
+    file.write_all(contents)?;
+
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)?;
+    }
+
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(temp_path, target)
+}
+
+/// Follow a chain of symlinks to the final path, which may not exist yet
+fn resolve_symlinks(path: &Path) -> io::Result<PathBuf> {
+    let mut current = path.to_path_buf();
+
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        match fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let link = fs::read_link(&current)?;
+                current = match current.parent() {
+                    Some(parent) if link.is_relative() => parent.join(link),
+                    _ => link,
+                };
+            }
+            // Regular file, or nothing there yet
+            _ => return Ok(current),
+        }
+    }
+
+    Err(io::Error::other(format!(
+        "Too many levels of symbolic links: {}",
+        path.display()
+    )))
+}
+
+/// Persist the rename itself by syncing the containing directory
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Windows can't open directories as files; NTFS journals the rename
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
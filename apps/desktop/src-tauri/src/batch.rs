@@ -0,0 +1,623 @@
+// ============================================================================
+// BATCH FILE OPERATIONS
+// ============================================================================
+//
+// `apply_file_operations` runs an ordered list of create, write, rename,
+// move, copy and delete operations inside one workspace as a unit, so a
+// multi-select action in the file explorer either happens completely or not
+// at all.
+//
+// The whole list is validated first against the state the workspace will be
+// in when each operation runs (an operation may use a folder an earlier one
+// created, or a name an earlier one freed), so most mistakes are caught
+// before anything is touched. Operations then run in order, each recording
+// how to undo it; if one fails, the completed ones are undone in reverse.
+// Deleted items are only moved aside while the batch runs - into the trash,
+// or next to themselves for permanent deletes - and permanent deletes are
+// finished once every operation succeeded.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CommandError, ErrorCode, IoContext};
+use crate::{atomic, history, sandbox, transfer, trash};
+
+static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// One operation of a batch, tagged by `type`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FileOperation {
+    /// Create an empty file, or a directory with `directory`
+    Create {
+        path: String,
+        #[serde(default)]
+        directory: bool,
+    },
+    /// Write text, keeping the encoding of a file being replaced
+    Write { path: String, content: String },
+    /// Give an item a new path
+    Rename { from: String, to: String },
+    /// Move an item, into `to` if that is a directory, like `move_path`
+    Move { from: String, to: String },
+    /// Copy an item, into `to` if that is a directory; an existing item is
+    /// kept and the copy named "name (copy)", like `copy_file`
+    Copy { from: String, to: String },
+    /// Move an item to the workspace trash, or delete it with `permanent`
+    Delete {
+        path: String,
+        #[serde(default)]
+        permanent: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationStatus {
+    /// Done and kept, since every operation succeeded
+    Applied,
+    /// Failed to validate or to run; see `error`
+    Failed,
+    /// Done, then undone because a later operation failed
+    RolledBack,
+    /// Done, but undoing it failed; see `error`
+    RollbackFailed,
+    /// Not run because another operation failed
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationResult {
+    status: OperationStatus,
+    /// Where the created, written, moved or copied item is
+    path: Option<String>,
+    error: Option<CommandError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    /// Whether every operation was applied
+    committed: bool,
+    /// One result per operation, in order
+    results: Vec<OperationResult>,
+}
+
+/// An operation with its paths resolved against the planned state
+enum Step {
+    Create { path: PathBuf, directory: bool },
+    Write { path: PathBuf, content: String },
+    /// Rename or move
+    Move { from: PathBuf, to: PathBuf },
+    Copy { from: PathBuf, to: PathBuf },
+    Delete { path: PathBuf, permanent: bool },
+}
+
+/// How to undo a completed step
+enum Undo {
+    /// Remove a created file or directory, or a copy
+    Remove(PathBuf),
+    /// Put back the previous content of a written file, or remove it if it
+    /// didn't exist
+    Restore { path: PathBuf, previous: Option<Vec<u8>> },
+    MoveBack { from: PathBuf, to: PathBuf },
+    TakeFromTrash { id: String, path: PathBuf },
+    /// Move back an item set aside for a permanent delete
+    Unstage { staged: PathBuf, path: PathBuf },
+}
+
+struct Done {
+    undo: Undo,
+    /// Parent directories the step created, deepest first
+    created_dirs: Vec<PathBuf>,
+}
+
+/// Apply file operations in order, all or nothing
+///
+/// Every path must be inside `workspace_root`, which must be an open
+/// workspace. Operations that fail validation leave the workspace untouched;
+/// a failure while running rolls back the operations already done. Links
+/// are not rewritten for renamed or moved items.
+#[tauri::command]
+pub fn apply_file_operations(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    operations: Vec<FileOperation>,
+) -> Result<BatchReport, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+
+    let count = operations.len();
+    let mut plan = Plan::default();
+    let mut steps = Vec::with_capacity(count);
+
+    for (index, operation) in operations.into_iter().enumerate() {
+        match validate(&app, &root, &mut plan, operation) {
+            Ok(step) => steps.push(step),
+            Err(error) => return Ok(failed_at(count, index, error)),
+        }
+    }
+
+    let mut done: Vec<Done> = Vec::with_capacity(steps.len());
+
+    for (index, step) in steps.iter().enumerate() {
+        match execute(&app, &root, step) {
+            Ok(step_done) => done.push(step_done),
+            Err(error) => {
+                let mut results = rollback(&app, &root, done);
+                results.push(OperationResult {
+                    status: OperationStatus::Failed,
+                    path: None,
+                    error: Some(error),
+                });
+                results.extend((index + 1..steps.len()).map(|_| skipped()));
+
+                return Ok(BatchReport {
+                    committed: false,
+                    results,
+                });
+            }
+        }
+    }
+
+    commit(&app, &steps, &done);
+
+    Ok(BatchReport {
+        committed: true,
+        results: steps
+            .iter()
+            .map(|step| OperationResult {
+                status: OperationStatus::Applied,
+                path: step.result_path().map(|path| path.to_string_lossy().to_string()),
+                error: None,
+            })
+            .collect(),
+    })
+}
+
+/// Report for a batch where operation `index` of `count` didn't validate
+fn failed_at(count: usize, index: usize, error: CommandError) -> BatchReport {
+    let mut results: Vec<OperationResult> = (0..count).map(|_| skipped()).collect();
+    results[index] = OperationResult {
+        status: OperationStatus::Failed,
+        path: None,
+        error: Some(error),
+    };
+
+    BatchReport {
+        committed: false,
+        results,
+    }
+}
+
+fn skipped() -> OperationResult {
+    OperationResult {
+        status: OperationStatus::Skipped,
+        path: None,
+        error: None,
+    }
+}
+
+/// Check an operation against the planned state, resolve its paths and
+/// record its effect on the plan
+fn validate(
+    app: &tauri::AppHandle,
+    root: &Path,
+    plan: &mut Plan,
+    operation: FileOperation,
+) -> Result<Step, CommandError> {
+    let step = match operation {
+        FileOperation::Create { path, directory } => {
+            let path = checked_path(app, root, &path)?;
+            if plan.kind(&path).is_some() {
+                return Err(CommandError::already_exists(&path));
+            }
+
+            let mut layer = plan.missing_parents(&path)?;
+            layer.push((path.clone(), if directory { Entry::Dir } else { Entry::File }));
+            plan.push(layer);
+
+            Step::Create { path, directory }
+        }
+        FileOperation::Write { path, content } => {
+            let path = checked_path(app, root, &path)?;
+            if plan.kind(&path) == Some(Kind::Dir) {
+                return Err(CommandError::not_a_file(&path));
+            }
+
+            let mut layer = plan.missing_parents(&path)?;
+            layer.push((path.clone(), Entry::File));
+            plan.push(layer);
+
+            Step::Write { path, content }
+        }
+        FileOperation::Rename { from, to } => {
+            let from = checked_path(app, root, &from)?;
+            let to = checked_path(app, root, &to)?;
+            plan_move(plan, from, to)?
+        }
+        FileOperation::Move { from, to } => {
+            let from = checked_path(app, root, &from)?;
+            let to = checked_path(app, root, &to)?;
+            let to = if plan.kind(&to) == Some(Kind::Dir) {
+                to.join(file_name(&from)?)
+            } else {
+                to
+            };
+            plan_move(plan, from, to)?
+        }
+        FileOperation::Copy { from, to } => {
+            let from = checked_path(app, root, &from)?;
+            let to = checked_path(app, root, &to)?;
+            if plan.kind(&from).is_none() {
+                return Err(CommandError::not_found(&from));
+            }
+
+            let to = if plan.kind(&to) == Some(Kind::Dir) {
+                to.join(file_name(&from)?)
+            } else {
+                to
+            };
+            let is_dir = plan.kind(&from) == Some(Kind::Dir);
+            let to = transfer::unique_path(&to, is_dir, |candidate| plan.kind(candidate).is_some())?;
+
+            let mut layer = plan.missing_parents(&to)?;
+            layer.push((to.clone(), Entry::From(from.clone())));
+            plan.push(layer);
+
+            Step::Copy { from, to }
+        }
+        FileOperation::Delete { path, permanent } => {
+            let path = checked_path(app, root, &path)?;
+            if plan.kind(&path).is_none() {
+                return Err(CommandError::not_found(&path));
+            }
+
+            plan.push(vec![(path.clone(), Entry::Absent)]);
+
+            // Like `delete_path`, items already in the trash are deleted
+            let permanent = permanent || trash::is_in_trash(root, &path);
+            Step::Delete { path, permanent }
+        }
+    };
+
+    Ok(step)
+}
+
+fn plan_move(plan: &mut Plan, from: PathBuf, to: PathBuf) -> Result<Step, CommandError> {
+    if plan.kind(&from).is_none() {
+        return Err(CommandError::not_found(&from));
+    }
+    if plan.kind(&to).is_some() {
+        return Err(CommandError::already_exists(&to));
+    }
+    if to.starts_with(&from) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Can't move a folder into itself: {}", from.display()),
+        )
+        .with_path(&from));
+    }
+
+    let mut layer = plan.missing_parents(&to)?;
+    layer.push((to.clone(), Entry::From(from.clone())));
+    layer.push((from.clone(), Entry::Absent));
+    plan.push(layer);
+
+    Ok(Step::Move { from, to })
+}
+
+/// A path from the frontend, checked to be inside the workspace but not
+/// the workspace root itself
+fn checked_path(app: &tauri::AppHandle, root: &Path, path: &str) -> Result<PathBuf, CommandError> {
+    let path = PathBuf::from(path);
+    sandbox::check_workspace(app, &path)?;
+
+    if !path.starts_with(root) || path == root {
+        return Err(CommandError::access_denied(&path));
+    }
+    Ok(path)
+}
+
+fn file_name(path: &Path) -> Result<&std::ffi::OsStr, CommandError> {
+    path.file_name().ok_or_else(|| CommandError::invalid_name(&path.to_string_lossy()))
+}
+
+fn execute(app: &tauri::AppHandle, root: &Path, step: &Step) -> Result<Done, CommandError> {
+    let created_dirs = match step {
+        Step::Create { path, .. } | Step::Write { path, .. } => create_parents(path)?,
+        Step::Move { to, .. } | Step::Copy { to, .. } => create_parents(to)?,
+        Step::Delete { .. } => Vec::new(),
+    };
+
+    let result = execute_step(app, root, step);
+
+    match result {
+        Ok(undo) => Ok(Done { undo, created_dirs }),
+        Err(error) => {
+            remove_dirs(&created_dirs);
+            Err(error)
+        }
+    }
+}
+
+fn execute_step(app: &tauri::AppHandle, root: &Path, step: &Step) -> Result<Undo, CommandError> {
+    match step {
+        Step::Create { path, directory } => {
+            if *directory {
+                fs::create_dir(path).context("Failed to create directory", path)?;
+            } else {
+                fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(path)
+                    .context("Failed to create file", path)?;
+            }
+            Ok(Undo::Remove(path.clone()))
+        }
+        Step::Write { path, content } => {
+            let previous = fs::read(path).ok();
+            let bytes = crate::encode_for_write(path, content, Default::default())?;
+
+            history::snapshot(app, path);
+            atomic::write_atomic(path, bytes).context("Failed to write file", path)?;
+
+            Ok(Undo::Restore {
+                path: path.clone(),
+                previous,
+            })
+        }
+        Step::Move { from, to } => {
+            if to.exists() {
+                return Err(CommandError::already_exists(to));
+            }
+
+            transfer::move_item(app, from, to)?;
+            history::rename(app, from, to);
+
+            Ok(Undo::MoveBack {
+                from: from.clone(),
+                to: to.clone(),
+            })
+        }
+        Step::Copy { from, to } => {
+            if to.exists() {
+                return Err(CommandError::already_exists(to));
+            }
+
+            if let Err(error) = transfer::copy_item(app, from, to) {
+                let _ = transfer::remove_item(to);
+                return Err(error);
+            }
+            Ok(Undo::Remove(to.clone()))
+        }
+        Step::Delete { path, permanent: false } => {
+            let id = trash::move_to_trash(root, path)?;
+            Ok(Undo::TakeFromTrash { id, path: path.clone() })
+        }
+        Step::Delete { path, permanent: true } => {
+            let staged = staging_path(path)?;
+            fs::rename(path, &staged).context("Failed to delete", path)?;
+
+            Ok(Undo::Unstage {
+                staged,
+                path: path.clone(),
+            })
+        }
+    }
+}
+
+/// Undo completed steps, latest first; returns their results in order
+fn rollback(app: &tauri::AppHandle, root: &Path, done: Vec<Done>) -> Vec<OperationResult> {
+    let mut results: Vec<OperationResult> = done
+        .into_iter()
+        .rev()
+        .map(|done| {
+            let result = undo(app, root, done.undo);
+            remove_dirs(&done.created_dirs);
+
+            match result {
+                Ok(()) => OperationResult {
+                    status: OperationStatus::RolledBack,
+                    path: None,
+                    error: None,
+                },
+                Err(error) => {
+                    eprintln!("[batch] Failed to roll back an operation: {}", error);
+                    OperationResult {
+                        status: OperationStatus::RollbackFailed,
+                        path: None,
+                        error: Some(error),
+                    }
+                }
+            }
+        })
+        .collect();
+
+    results.reverse();
+    results
+}
+
+fn undo(app: &tauri::AppHandle, root: &Path, undo: Undo) -> Result<(), CommandError> {
+    match undo {
+        Undo::Remove(path) => transfer::remove_item(&path),
+        Undo::Restore { path, previous: Some(bytes) } => {
+            atomic::write_atomic(&path, bytes).context("Failed to restore", &path)
+        }
+        Undo::Restore { path, previous: None } => transfer::remove_item(&path),
+        Undo::MoveBack { from, to } => {
+            transfer::move_item(app, &to, &from)?;
+            history::rename(app, &to, &from);
+            Ok(())
+        }
+        Undo::TakeFromTrash { id, path } => Ok(trash::take_back(root, &id, &path)?),
+        Undo::Unstage { staged, path } => {
+            fs::rename(&staged, &path).context("Failed to restore", &path)
+        }
+    }
+}
+
+/// Finish permanent deletes and let the indexes know what changed
+fn commit(app: &tauri::AppHandle, steps: &[Step], done: &[Done]) {
+    for done in done {
+        if let Undo::Unstage { staged, path } = &done.undo {
+            if let Err(e) = transfer::remove_item(staged) {
+                eprintln!("[batch] Failed to delete {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    let mut updated = Vec::new();
+    let mut removed = Vec::new();
+
+    for step in steps {
+        match step {
+            Step::Create { path, .. } | Step::Write { path, .. } => updated.push(path.clone()),
+            Step::Move { from, to } => {
+                updated.push(to.clone());
+                removed.push(from.clone());
+            }
+            Step::Copy { to, .. } => updated.push(to.clone()),
+            Step::Delete { path, .. } => removed.push(path.clone()),
+        }
+    }
+
+    crate::refresh_indexes(app, updated, removed);
+}
+
+impl Step {
+    fn result_path(&self) -> Option<&Path> {
+        match self {
+            Step::Create { path, .. } | Step::Write { path, .. } => Some(path),
+            Step::Move { to, .. } | Step::Copy { to, .. } => Some(to),
+            Step::Delete { .. } => None,
+        }
+    }
+}
+
+/// Create the missing parents of `path`; returns them deepest first
+fn create_parents(path: &Path) -> Result<Vec<PathBuf>, CommandError> {
+    let missing: Vec<PathBuf> = path
+        .ancestors()
+        .skip(1)
+        .take_while(|ancestor| !ancestor.exists())
+        .map(Path::to_path_buf)
+        .collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create parent directories", parent)?;
+    }
+    Ok(missing)
+}
+
+/// Remove directories created for a step, if nothing else ended up in them
+fn remove_dirs(dirs: &[PathBuf]) {
+    for dir in dirs {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+/// Hidden sibling an item is moved to until a permanent delete is final
+fn staging_path(path: &Path) -> Result<PathBuf, CommandError> {
+    let name = file_name(path)?.to_string_lossy();
+
+    loop {
+        let staged = path.with_file_name(format!(
+            ".{}.{}-{}.deleting",
+            name,
+            std::process::id(),
+            STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if !staged.exists() {
+            return Ok(staged);
+        }
+    }
+}
+
+// ============================================================================
+// Planned state
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    File,
+    Dir,
+}
+
+/// What an operation left at a path
+enum Entry {
+    Absent,
+    /// A new file
+    File,
+    /// A new, empty directory
+    Dir,
+    /// What was at the given path before the operation, moved or copied
+    From(PathBuf),
+}
+
+/// The workspace as it will be after the validated operations: one layer
+/// of changed paths per operation, on top of what is on disk
+#[derive(Default)]
+struct Plan {
+    layers: Vec<Vec<(PathBuf, Entry)>>,
+}
+
+impl Plan {
+    fn push(&mut self, layer: Vec<(PathBuf, Entry)>) {
+        self.layers.push(layer);
+    }
+
+    fn kind(&self, path: &Path) -> Option<Kind> {
+        self.kind_at(path, self.layers.len())
+    }
+
+    /// Kind of item at `path` after the first `depth` operations
+    fn kind_at(&self, path: &Path, depth: usize) -> Option<Kind> {
+        for (i, layer) in self.layers[..depth].iter().enumerate().rev() {
+            // The deepest changed path at or above `path` decides
+            let Some((at, entry)) = layer
+                .iter()
+                .filter(|(at, _)| path.starts_with(at))
+                .max_by_key(|(at, _)| at.components().count())
+            else {
+                continue;
+            };
+
+            return match entry {
+                Entry::Absent => None,
+                Entry::File => (path == at).then_some(Kind::File),
+                Entry::Dir => (path == at).then_some(Kind::Dir),
+                Entry::From(source) => {
+                    let suffix = path.strip_prefix(at).ok()?;
+                    self.kind_at(&source.join(suffix), i)
+                }
+            };
+        }
+
+        match fs::metadata(path).or_else(|_| fs::symlink_metadata(path)) {
+            Ok(metadata) if metadata.is_dir() => Some(Kind::Dir),
+            Ok(_) => Some(Kind::File),
+            Err(_) => None,
+        }
+    }
+
+    /// Entries for the parents of `path` that don't exist yet, which the
+    /// operation creates; fails if the closest existing one is a file
+    fn missing_parents(&self, path: &Path) -> Result<Vec<(PathBuf, Entry)>, CommandError> {
+        let mut missing = Vec::new();
+
+        for ancestor in path.ancestors().skip(1) {
+            match self.kind(ancestor) {
+                None => missing.push((ancestor.to_path_buf(), Entry::Dir)),
+                Some(Kind::Dir) => return Ok(missing),
+                Some(Kind::File) => return Err(CommandError::not_a_directory(ancestor)),
+            }
+        }
+
+        Ok(missing)
+    }
+}
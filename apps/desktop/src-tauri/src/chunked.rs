@@ -0,0 +1,281 @@
+// ============================================================================
+// CHUNKED READS
+// ============================================================================
+//
+// Reading a file of hundreds of megabytes with `read_file` would hold all of
+// it in memory and push it through IPC in one message, so `read_file`
+// refuses files above a size limit with a `TooLarge` error. Such files can
+// still be shown read-only, either a window at a time with `read_file_range`
+// or streamed start to end as `file-stream-chunk` events with `stream_file`,
+// which `cancel_file_stream` stops early.
+//
+// Offsets are in bytes. The encoding is detected from the start of the file,
+// and chunk boundaries are moved so no character is split between chunks;
+// each result carries the offset to continue from.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::encoding::{self, TextEncoding};
+use crate::error::{CommandError, IoContext};
+use crate::sandbox;
+
+/// Name of the Tauri event carrying one chunk of a streamed file
+pub const FILE_CHUNK_EVENT: &str = "file-stream-chunk";
+
+/// Files larger than this are refused by `read_file` unless the caller
+/// passes its own limit
+pub const DEFAULT_MAX_READ_SIZE: u64 = 20 * 1024 * 1024;
+
+/// Largest range returned by one `read_file_range` call
+const MAX_RANGE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Smallest range; always enough for one whole character
+const MIN_RANGE_SIZE: u64 = 4;
+
+const DEFAULT_CHUNK_SIZE: u64 = 256 * 1024;
+
+/// Bytes read from the start of a file to detect its encoding
+const SNIFF_LENGTH: u64 = 4096;
+
+/// Cancellation flags of running streams keyed by stream id
+#[derive(Default)]
+pub struct StreamState {
+    streams: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// Decoded text of a byte range of a file
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRange {
+    content: String,
+    /// Offset of the first byte decoded; later than the requested offset if
+    /// that fell inside a character or the byte order mark
+    offset: u64,
+    /// Offset to read the next range from
+    next_offset: u64,
+    total_size: u64,
+    encoding: TextEncoding,
+    /// Whether the range reaches the end of the file
+    eof: bool,
+}
+
+/// One chunk of a streamed file, the payload of a `file-stream-chunk` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChunk {
+    stream_id: String,
+    content: String,
+    offset: u64,
+    total_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSummary {
+    bytes_read: u64,
+    total_size: u64,
+    encoding: TextEncoding,
+    /// Stopped by `cancel_file_stream`
+    cancelled: bool,
+}
+
+/// An open text file and how it is encoded
+struct TextFile {
+    path: PathBuf,
+    file: File,
+    total_size: u64,
+    encoding: TextEncoding,
+    bom_len: u64,
+}
+
+/// Decode up to `length` bytes of a file starting at `offset`
+#[tauri::command]
+pub fn read_file_range(
+    app: tauri::AppHandle,
+    path: String,
+    offset: u64,
+    length: u64,
+) -> Result<FileRange, CommandError> {
+    let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
+
+    let mut file = TextFile::open(&file_path)?;
+    file.read_range(offset, length.clamp(MIN_RANGE_SIZE, MAX_RANGE_SIZE))
+}
+
+/// Read a whole file in chunks of about `chunk_size` bytes
+///
+/// Chunks are emitted in order as `file-stream-chunk` events tagged with
+/// `stream_id`; the command resolves with a summary once the file has been
+/// read or the stream was cancelled.
+#[tauri::command]
+pub async fn stream_file(
+    app: tauri::AppHandle,
+    stream_id: String,
+    path: String,
+    chunk_size: Option<u64>,
+) -> Result<StreamSummary, CommandError> {
+    let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
+
+    let chunk_size = chunk_size
+        .unwrap_or(DEFAULT_CHUNK_SIZE)
+        .clamp(MIN_RANGE_SIZE, MAX_RANGE_SIZE);
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let state = app.state::<StreamState>();
+        let mut streams = state
+            .streams
+            .lock()
+            .map_err(|_| "Stream state is poisoned".to_string())?;
+
+        if streams.contains_key(&stream_id) {
+            return Err(format!("Stream is already running: {}", stream_id).into());
+        }
+        streams.insert(stream_id.clone(), cancelled.clone());
+    }
+
+    let task_app = app.clone();
+    let task_id = stream_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        run_stream(&task_app, &task_id, &file_path, chunk_size, &cancelled)
+    })
+    .await
+    .map_err(|e| format!("Stream task failed: {}", e));
+
+    if let Ok(mut streams) = app.state::<StreamState>().streams.lock() {
+        streams.remove(&stream_id);
+    }
+
+    result?
+}
+
+/// Cancel a running stream; returns `false` if no stream has that id
+#[tauri::command]
+pub fn cancel_file_stream(state: tauri::State<'_, StreamState>, stream_id: String) -> Result<bool, CommandError> {
+    let streams = state
+        .streams
+        .lock()
+        .map_err(|_| "Stream state is poisoned".to_string())?;
+
+    match streams.get(&stream_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn run_stream(
+    app: &tauri::AppHandle,
+    stream_id: &str,
+    path: &Path,
+    chunk_size: u64,
+    cancelled: &AtomicBool,
+) -> Result<StreamSummary, CommandError> {
+    let mut file = TextFile::open(path)?;
+    let mut offset = 0;
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let range = file.read_range(offset, chunk_size)?;
+        offset = range.next_offset;
+
+        let _ = app.emit(
+            FILE_CHUNK_EVENT,
+            FileChunk {
+                stream_id: stream_id.to_string(),
+                content: range.content,
+                offset: range.offset,
+                total_size: range.total_size,
+            },
+        );
+
+        if range.eof {
+            break;
+        }
+    }
+
+    Ok(StreamSummary {
+        bytes_read: offset,
+        total_size: file.total_size,
+        encoding: file.encoding,
+        cancelled: cancelled.load(Ordering::Relaxed),
+    })
+}
+
+impl TextFile {
+    fn open(path: &Path) -> Result<Self, CommandError> {
+        if path.exists() && !path.is_file() {
+            return Err(CommandError::not_a_file(path));
+        }
+
+        let mut file = File::open(path).context("Failed to open file", path)?;
+        let total_size = file.metadata().context("Failed to read metadata", path)?.len();
+
+        let mut head = Vec::new();
+        (&mut file)
+            .take(SNIFF_LENGTH)
+            .read_to_end(&mut head)
+            .context("Failed to read file", path)?;
+        let (encoding, bom_len) = encoding::sniff(&head);
+
+        Ok(TextFile {
+            path: path.to_path_buf(),
+            file,
+            total_size,
+            encoding,
+            bom_len: bom_len as u64,
+        })
+    }
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<FileRange, CommandError> {
+        let mut start = offset.max(self.bom_len).min(self.total_size);
+        // UTF-16 code units start at even offsets, with or without a BOM
+        if matches!(self.encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be) {
+            start &= !1;
+        }
+
+        self.file
+            .seek(SeekFrom::Start(start))
+            .context("Failed to read file", &self.path)?;
+
+        let mut bytes = Vec::with_capacity(length.min(self.total_size - start) as usize);
+        (&mut self.file)
+            .take(length)
+            .read_to_end(&mut bytes)
+            .context("Failed to read file", &self.path)?;
+
+        // A short read means the file now ends here, even if it shrank since it was opened
+        let end = start + bytes.len() as u64;
+        if (bytes.len() as u64) < length {
+            self.total_size = end;
+        }
+
+        let is_last = end >= self.total_size;
+        let (content, decoded) = encoding::decode_slice(&bytes, self.encoding, is_last);
+        let next_offset = start + decoded.end as u64;
+
+        Ok(FileRange {
+            content,
+            offset: start + decoded.start as u64,
+            next_offset,
+            total_size: self.total_size,
+            encoding: self.encoding,
+            eof: bytes.is_empty() || next_offset >= self.total_size,
+        })
+    }
+}
@@ -0,0 +1,178 @@
+// ============================================================================
+// LINE DIFF
+// ============================================================================
+//
+// Myers' O((N+M)D) diff over lines, used to compare a history version with
+// the current file. The common prefix and suffix are trimmed first, so the
+// usual case of a few edited lines stays cheap; when the files differ in more
+// than `MAX_EDIT_DISTANCE` lines the middle is reported as removed and
+// re-added instead of searching for the shortest edit script.
+
+use serde::Serialize;
+
+/// Largest edit distance searched for; the trace grows with its square
+const MAX_EDIT_DISTANCE: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// One line of a diff from `old` to `new`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    kind: DiffKind,
+    /// 1-based line number in `old`, `None` for inserted lines
+    old_line: Option<usize>,
+    /// 1-based line number in `new`, `None` for deleted lines
+    new_line: Option<usize>,
+    text: String,
+}
+
+/// Line diff turning `old` into `new`
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut edits: Vec<(DiffKind, usize, usize)> = (0..prefix).map(|i| (DiffKind::Equal, i, i)).collect();
+
+    let middle = shortest_edit(old_middle, new_middle).unwrap_or_else(|| {
+        (0..old_middle.len())
+            .map(|i| (DiffKind::Delete, i, 0))
+            .chain((0..new_middle.len()).map(|j| (DiffKind::Insert, 0, j)))
+            .collect()
+    });
+    edits.extend(middle.into_iter().map(|(kind, i, j)| (kind, i + prefix, j + prefix)));
+
+    let old_tail = old.len() - suffix;
+    let new_tail = new.len() - suffix;
+    edits.extend((0..suffix).map(|i| (DiffKind::Equal, old_tail + i, new_tail + i)));
+
+    edits
+        .into_iter()
+        .map(|(kind, i, j)| match kind {
+            DiffKind::Equal => DiffLine {
+                kind,
+                old_line: Some(i + 1),
+                new_line: Some(j + 1),
+                text: old[i].to_string(),
+            },
+            DiffKind::Delete => DiffLine {
+                kind,
+                old_line: Some(i + 1),
+                new_line: None,
+                text: old[i].to_string(),
+            },
+            DiffKind::Insert => DiffLine {
+                kind,
+                old_line: None,
+                new_line: Some(j + 1),
+                text: new[j].to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Myers' greedy search followed by a walk back through the saved
+/// frontiers; `None` if the edit distance exceeds `MAX_EDIT_DISTANCE`.
+/// Indices of `Insert` refer to `new`, of `Delete` to `old`.
+fn shortest_edit(old: &[&str], new: &[&str]) -> Option<Vec<(DiffKind, usize, usize)>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize;
+
+    // Furthest x reached on each diagonal k = x - y, indexed by k + offset
+    let mut v = vec![0isize; 2 * max + 2];
+    // Frontier after each round d, covering diagonals -d..=d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max.min(MAX_EDIT_DISTANCE) as isize {
+        for k in (-d..=d).step_by(2) {
+            let down = k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]);
+            let mut x = if down {
+                v[(k + 1 + offset) as usize]
+            } else {
+                v[(k - 1 + offset) as usize] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[(k + offset) as usize] = x;
+
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                break 'search;
+            }
+        }
+
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+
+    let reached = trace.last().is_some_and(|last| {
+        let d = (last.len() / 2) as isize;
+        last.get((n - m + d) as usize) == Some(&n)
+    });
+    if !reached {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[(d - 1) as usize];
+        let at = |k: isize| previous[(k + d - 1) as usize];
+
+        let k = x - y;
+        let down = k == -d || (k != d && at(k - 1) < at(k + 1));
+        let prev_k = if down { k + 1 } else { k - 1 };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+
+        // Start of the snake that follows the edit
+        let (mid_x, mid_y) = if down { (prev_x, prev_y + 1) } else { (prev_x + 1, prev_y) };
+
+        while x > mid_x && y > mid_y {
+            x -= 1;
+            y -= 1;
+            edits.push((DiffKind::Equal, x as usize, y as usize));
+        }
+
+        if down {
+            edits.push((DiffKind::Insert, prev_x as usize, prev_y as usize));
+        } else {
+            edits.push((DiffKind::Delete, prev_x as usize, prev_y.max(0) as usize));
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push((DiffKind::Equal, x as usize, y as usize));
+    }
+
+    edits.reverse();
+    Some(edits)
+}
@@ -0,0 +1,465 @@
+// ============================================================================
+// DUPLICATE FILES
+// ============================================================================
+//
+// Finds files with identical content, left behind by imports and sync
+// conflicts: files are grouped by size first, and only files sharing a size
+// are hashed, through the hash cache. Optionally also finds notes that are
+// nearly the same, by the Jaccard similarity of their sets of word
+// shingles (runs of `SHINGLE_WORDS` words) outside the frontmatter.
+//
+// `merge_duplicates` keeps one file of a group: links to the others are
+// rewritten to point at it, then the others go to the workspace trash.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CommandError, ErrorCode};
+use crate::hashing::{self, HashAlgorithm, HashContent};
+use crate::ignore::IgnoreRules;
+use crate::{encoding, links, markdown, sandbox, trash};
+
+const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Words per shingle when comparing notes
+const SHINGLE_WORDS: usize = 3;
+
+/// Notes larger than this aren't compared for similarity
+const MAX_SIMILAR_NOTE_BYTES: u64 = 1024 * 1024;
+
+/// Notes compared for similarity at most, as every pair may be compared
+const MAX_SIMILAR_NOTES: usize = 20_000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateOptions {
+    /// Extra gitignore-style patterns on top of the workspace's ignore files
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    /// Also group notes that are nearly the same
+    #[serde(default)]
+    include_similar: bool,
+    /// Lowest similarity, from 0 to 1, for notes to count as near
+    /// duplicates (default 0.8)
+    similarity_threshold: Option<f64>,
+    /// Empty files are identical to each other, so they are skipped unless set
+    #[serde(default)]
+    include_empty: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateKind {
+    /// Byte-for-byte identical
+    Exact,
+    /// Notes above the similarity threshold
+    Similar,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    kind: DuplicateKind,
+    /// Hex-encoded SHA-256 of the content; `None` for similar notes
+    hash: Option<String>,
+    /// 1 for exact duplicates; for similar notes the lowest similarity that
+    /// joined two notes of the group
+    similarity: f64,
+    /// Oldest first, so the first file is usually the original
+    files: Vec<DuplicateFile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFile {
+    path: String,
+    size: u64,
+    /// Milliseconds since the Unix epoch
+    modified: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    /// Exact groups, most wasted bytes first, then similar ones, most
+    /// similar first
+    groups: Vec<DuplicateGroup>,
+    files_scanned: usize,
+    /// Bytes taken by all but one file of each exact group
+    wasted_bytes: u64,
+}
+
+/// A duplicate moved to the trash by `merge_duplicates`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedDuplicate {
+    path: String,
+    /// Id of the trash entry, to restore the file with `restore_from_trash`
+    trash_id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    removed: Vec<RemovedDuplicate>,
+    #[serde(flatten)]
+    links: links::LinkRewriteReport,
+}
+
+/// A note reduced to what similarity is measured on
+struct Shingled {
+    file: DuplicateFile,
+    /// Sorted, without repeats
+    shingles: Vec<u64>,
+}
+
+/// Group the workspace's files with identical content and, with
+/// `include_similar`, notes that are nearly the same
+#[tauri::command]
+pub async fn find_duplicates(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    options: Option<DuplicateOptions>,
+) -> Result<DuplicateReport, CommandError> {
+    let options = options.unwrap_or_default();
+
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    if !root.is_dir() {
+        return Err(CommandError::not_a_directory(&root));
+    }
+
+    let threshold = options.similarity_threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Similarity threshold must be between 0 and 1: {}", threshold),
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || find(&app, &root, &options, threshold))
+        .await
+        .map_err(|e| format!("Duplicate search failed: {}", e))?
+}
+
+/// Keep `keep` and remove `duplicates`: links to each duplicate are
+/// rewritten to point at `keep`, then the duplicate is moved to the
+/// workspace trash
+///
+/// Nothing checks that the files really are duplicates; for similar notes,
+/// whatever only the removed notes contain is left in the trash. If one
+/// duplicate fails, the error's details hold the report of the duplicates
+/// merged before it.
+#[tauri::command]
+pub fn merge_duplicates(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    keep: String,
+    duplicates: Vec<String>,
+) -> Result<MergeReport, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    let keep = PathBuf::from(&keep);
+    sandbox::check_workspace(&app, &root)?;
+    sandbox::check(&app, &keep)?;
+
+    if !keep.starts_with(&root) {
+        return Err(CommandError::access_denied(&keep));
+    }
+    if !keep.is_file() {
+        return Err(CommandError::not_a_file(&keep));
+    }
+
+    let duplicates: Vec<PathBuf> = duplicates.into_iter().map(PathBuf::from).collect();
+    for (index, duplicate) in duplicates.iter().enumerate() {
+        sandbox::check(&app, duplicate)?;
+
+        if !duplicate.starts_with(&root) {
+            return Err(CommandError::access_denied(duplicate));
+        }
+        if !duplicate.is_file() {
+            return Err(CommandError::not_a_file(duplicate));
+        }
+        if same_file::is_same_file(duplicate, &keep).unwrap_or(false) {
+            return Err(CommandError::new(
+                ErrorCode::InvalidInput,
+                format!("Can't merge a file into itself: {}", duplicate.display()),
+            )
+            .with_path(duplicate));
+        }
+        // Every file is validated before anything is changed, so a repeat
+        // can't fail halfway through the merge
+        if duplicates[..index]
+            .iter()
+            .any(|earlier| same_file::is_same_file(earlier, duplicate).unwrap_or(false))
+        {
+            return Err(CommandError::new(
+                ErrorCode::InvalidInput,
+                format!("File is listed more than once: {}", duplicate.display()),
+            )
+            .with_path(duplicate));
+        }
+    }
+
+    let mut report = MergeReport {
+        removed: Vec::new(),
+        links: links::LinkRewriteReport::default(),
+    };
+
+    for duplicate in duplicates {
+        // Each duplicate is merged completely or not at all; on failure the
+        // error carries the report of the ones merged before it
+        let (rewritten, trash_id) = merge_one(&app, &root, &keep, &duplicate).map_err(|error| {
+            error.with_details(serde_json::to_value(&report).unwrap_or_default())
+        })?;
+
+        crate::refresh_indexes(&app, rewritten.paths().collect(), vec![duplicate.clone()]);
+
+        report.links.append(rewritten);
+        report.removed.push(RemovedDuplicate {
+            path: duplicate.to_string_lossy().to_string(),
+            trash_id,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Retarget the links to one duplicate and move it to the trash, restoring
+/// the links if it can't be moved
+fn merge_one(
+    app: &tauri::AppHandle,
+    root: &Path,
+    keep: &Path,
+    duplicate: &Path,
+) -> Result<(links::LinkRewriteReport, String), CommandError> {
+    let plan = links::plan_retarget(app, root, duplicate, keep)?;
+    let rewritten = links::apply_move(plan)?;
+
+    match trash::move_to_trash(root, duplicate) {
+        Ok(trash_id) => Ok((rewritten, trash_id)),
+        Err(error) => {
+            rewritten.revert();
+            Err(error)
+        }
+    }
+}
+
+fn find(
+    app: &tauri::AppHandle,
+    root: &Path,
+    options: &DuplicateOptions,
+    threshold: f64,
+) -> Result<DuplicateReport, CommandError> {
+    let files: Vec<DuplicateFile> = IgnoreRules::load(root, &options.ignore_patterns)
+        .collect_files(root)
+        .par_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            Some(DuplicateFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: crate::modified_millis(&metadata),
+            })
+        })
+        .collect();
+    let files_scanned = files.len();
+
+    // Only files sharing a size can be identical
+    let mut by_size: HashMap<u64, Vec<DuplicateFile>> = HashMap::new();
+    for file in &files {
+        if file.size > 0 || options.include_empty {
+            by_size.entry(file.size).or_default().push(file.clone());
+        }
+    }
+    let candidates: Vec<DuplicateFile> = by_size.into_values().filter(|group| group.len() > 1).flatten().collect();
+
+    let paths = candidates.iter().map(|file| file.path.clone()).collect();
+    let hashes = hashing::hash_all(app, paths, HashAlgorithm::Sha256, HashContent::Bytes)?;
+
+    let mut by_hash: HashMap<String, Vec<DuplicateFile>> = HashMap::new();
+    for (file, hash) in candidates.into_iter().zip(hashes) {
+        if let Some(hash) = hash.hash() {
+            by_hash.entry(hash.to_string()).or_default().push(file);
+        }
+    }
+
+    let mut exact: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(hash, files)| DuplicateGroup {
+            kind: DuplicateKind::Exact,
+            hash: Some(hash),
+            similarity: 1.0,
+            files: sorted(files),
+        })
+        .collect();
+    exact.sort_by(|a, b| wasted(b).cmp(&wasted(a)).then_with(|| a.files[0].path.cmp(&b.files[0].path)));
+
+    let wasted_bytes = exact.iter().map(wasted).sum();
+
+    let mut groups = exact;
+    if options.include_similar {
+        // One file of each exact group stands for the others
+        let copies: HashSet<&str> = groups
+            .iter()
+            .flat_map(|group| group.files[1..].iter().map(|file| file.path.as_str()))
+            .collect();
+        let notes: Vec<DuplicateFile> = files
+            .iter()
+            .filter(|file| markdown::is_note(Path::new(&file.path)) && !copies.contains(file.path.as_str()))
+            .cloned()
+            .collect();
+
+        groups.extend(similar_groups(notes, threshold));
+    }
+
+    Ok(DuplicateReport {
+        groups,
+        files_scanned,
+        wasted_bytes,
+    })
+}
+
+/// Group notes whose similarity to another note of the group reaches the
+/// threshold
+fn similar_groups(notes: Vec<DuplicateFile>, threshold: f64) -> Vec<DuplicateGroup> {
+    let mut shingled: Vec<Shingled> = notes
+        .into_par_iter()
+        .filter(|note| note.size <= MAX_SIMILAR_NOTE_BYTES)
+        .filter_map(|file| {
+            let bytes = fs::read(&file.path).ok()?;
+            let shingles = shingles(&encoding::decode(&bytes).0);
+            (!shingles.is_empty()).then_some(Shingled { file, shingles })
+        })
+        .collect();
+
+    shingled.sort_by_key(|note| note.shingles.len());
+    shingled.truncate(MAX_SIMILAR_NOTES);
+
+    // Similarity is at most the ratio of the set sizes, so each note is
+    // only compared with the larger notes still within reach
+    let pairs: Vec<(usize, usize, f64)> = (0..shingled.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let shingled = &shingled;
+            let smaller = shingled[i].shingles.len() as f64;
+
+            (i + 1..shingled.len())
+                .take_while(move |&j| smaller / shingled[j].shingles.len() as f64 >= threshold)
+                .filter_map(move |j| {
+                    let similarity = jaccard(&shingled[i].shingles, &shingled[j].shingles);
+                    (similarity >= threshold).then_some((i, j, similarity))
+                })
+        })
+        .collect();
+
+    // Union-find over the matching pairs
+    let mut parent: Vec<usize> = (0..shingled.len()).collect();
+    let mut lowest: HashMap<usize, f64> = HashMap::new();
+
+    for &(i, j, _) in &pairs {
+        let (a, b) = (find_root(&mut parent, i), find_root(&mut parent, j));
+        if a != b {
+            parent[b] = a;
+        }
+    }
+    for &(i, _, similarity) in &pairs {
+        let group = find_root(&mut parent, i);
+        let entry = lowest.entry(group).or_insert(similarity);
+        *entry = entry.min(similarity);
+    }
+
+    let mut members: HashMap<usize, Vec<DuplicateFile>> = HashMap::new();
+    for (i, note) in shingled.iter().enumerate() {
+        let group = find_root(&mut parent, i);
+        if lowest.contains_key(&group) {
+            members.entry(group).or_default().push(note.file.clone());
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = members
+        .into_iter()
+        .map(|(group, files)| DuplicateGroup {
+            kind: DuplicateKind::Similar,
+            hash: None,
+            similarity: lowest[&group],
+            files: sorted(files),
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+    groups
+}
+
+/// Representative of the union-find set containing `i`
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Hashes of the runs of `SHINGLE_WORDS` lowercase words in a note's body;
+/// a shorter note is a single shingle
+fn shingles(content: &str) -> Vec<u64> {
+    let (_, body) = markdown::split_frontmatter(content);
+    let words: Vec<String> = body
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut shingles: Vec<u64> = words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    shingles.sort_unstable();
+    shingles.dedup();
+    shingles
+}
+
+/// Size of the intersection over the size of the union of two sorted sets
+fn jaccard(a: &[u64], b: &[u64]) -> f64 {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn sorted(mut files: Vec<DuplicateFile>) -> Vec<DuplicateFile> {
+    files.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path)));
+    files
+}
+
+/// Bytes taken by all but one file of a group
+fn wasted(group: &DuplicateGroup) -> u64 {
+    group.files.iter().skip(1).map(|file| file.size).sum()
+}
@@ -0,0 +1,395 @@
+// ============================================================================
+// TEXT ENCODINGS
+// ============================================================================
+//
+// Detects how a text file is stored so `read_file` can decode it and
+// `write_file` can store it back the same way. Recognized encodings are
+// UTF-8 and UTF-16 (with or without a byte order mark) and Windows-1252,
+// the usual "Latin-1" of files from Windows, which also serves as the
+// fallback for any other 8-bit encoding since every byte decodes to some
+// character.
+//
+// Line endings are reported as they occur in the file; content is passed
+// through unchanged on read, and on write every line break is converted to
+// the requested style unless the file mixes styles.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Bytes looked at when guessing UTF-16 without a byte order mark
+const UTF16_SAMPLE_BYTES: usize = 4096;
+
+/// Characters for bytes 0x80-0x9F in Windows-1252; the five unassigned
+/// bytes map to the C1 control with the same value, so they round-trip
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "windows-1252")]
+    Windows1252,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    /// More than one style; content is written back as given
+    Mixed,
+}
+
+/// How a file is stored on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    pub bom: bool,
+    pub line_ending: LineEnding,
+}
+
+/// Format requested for a write; unset fields keep the existing file's
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFormatOptions {
+    pub encoding: Option<TextEncoding>,
+    pub bom: Option<bool>,
+    pub line_ending: Option<LineEnding>,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        TextFormat {
+            encoding: TextEncoding::Utf8,
+            bom: false,
+            line_ending: LineEnding::Lf,
+        }
+    }
+}
+
+/// Decode file content, returning the text without its byte order mark and
+/// the format it was stored in
+pub fn decode(bytes: &[u8]) -> (String, TextFormat) {
+    let (encoding, bom, body) = if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        (TextEncoding::Utf8, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16LE_BOM) {
+        (TextEncoding::Utf16Le, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16BE_BOM) {
+        (TextEncoding::Utf16Be, true, body)
+    } else {
+        (guess_encoding(bytes), false, bytes)
+    };
+
+    let content = match encoding {
+        TextEncoding::Utf8 => match std::str::from_utf8(body) {
+            Ok(text) => text.to_string(),
+            // A UTF-8 BOM followed by invalid UTF-8: keep every byte
+            Err(_) => return decode_windows_1252(bytes),
+        },
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => match decode_utf16(body, encoding == TextEncoding::Utf16Le) {
+            Some(text) => text,
+            None => return decode_windows_1252(bytes),
+        },
+        TextEncoding::Windows1252 => return decode_windows_1252(bytes),
+    };
+
+    let line_ending = detect_line_ending(&content);
+    (
+        content,
+        TextFormat {
+            encoding,
+            bom,
+            line_ending,
+        },
+    )
+}
+
+/// Encode text in the given format, converting line breaks to its line
+/// ending; fails if a character can't be represented in the encoding
+pub fn encode(content: &str, format: TextFormat) -> Result<Vec<u8>, String> {
+    let content = convert_line_endings(content, format.line_ending);
+
+    let mut bytes = Vec::with_capacity(content.len() + 3);
+
+    match format.encoding {
+        TextEncoding::Utf8 => {
+            if format.bom {
+                bytes.extend_from_slice(UTF8_BOM);
+            }
+            bytes.extend_from_slice(content.as_bytes());
+        }
+        TextEncoding::Utf16Le => {
+            if format.bom {
+                bytes.extend_from_slice(UTF16LE_BOM);
+            }
+            bytes.extend(content.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        }
+        TextEncoding::Utf16Be => {
+            if format.bom {
+                bytes.extend_from_slice(UTF16BE_BOM);
+            }
+            bytes.extend(content.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+        }
+        TextEncoding::Windows1252 => {
+            for c in content.chars() {
+                let byte = encode_windows_1252(c)
+                    .ok_or_else(|| format!("Character {:?} can't be saved as windows-1252", c))?;
+                bytes.push(byte);
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Encoding of a file from its first bytes, and the length of its byte
+/// order mark; used when only part of a file is read
+pub fn sniff(head: &[u8]) -> (TextEncoding, usize) {
+    if head.starts_with(UTF8_BOM) {
+        return (TextEncoding::Utf8, UTF8_BOM.len());
+    }
+    if head.starts_with(UTF16LE_BOM) {
+        return (TextEncoding::Utf16Le, UTF16LE_BOM.len());
+    }
+    if head.starts_with(UTF16BE_BOM) {
+        return (TextEncoding::Utf16Be, UTF16BE_BOM.len());
+    }
+
+    if let Some(encoding) = guess_utf16(head) {
+        return (encoding, 0);
+    }
+
+    // The head may end partway through a character
+    match std::str::from_utf8(head) {
+        Ok(_) => (TextEncoding::Utf8, 0),
+        Err(e) if e.error_len().is_none() => (TextEncoding::Utf8, 0),
+        Err(_) => (TextEncoding::Windows1252, 0),
+    }
+}
+
+/// Decode a slice cut out of the middle of a file stored in `encoding`
+///
+/// Bytes of a character cut off at the start are skipped, and those of one
+/// cut off at the end are left for the next slice unless `is_last`. Returns
+/// the text and the range of `bytes` it was decoded from; invalid sequences
+/// become U+FFFD. UTF-16 slices must start at an even file offset.
+pub fn decode_slice(bytes: &[u8], encoding: TextEncoding, is_last: bool) -> (String, Range<usize>) {
+    match encoding {
+        TextEncoding::Utf8 => {
+            let is_continuation = |b: &u8| (0x80..=0xBF).contains(b);
+            let start = bytes.iter().take(3).take_while(|b| is_continuation(b)).count();
+
+            let mut end = bytes.len();
+            if !is_last {
+                // Back up to the last lead byte and keep its character only
+                // if all of its bytes are here
+                if let Some(lead) = bytes[start..]
+                    .iter()
+                    .rev()
+                    .take(4)
+                    .position(|b| !is_continuation(b))
+                    .map(|i| bytes.len() - 1 - i)
+                {
+                    let width = match bytes[lead] {
+                        0xC0..=0xDF => 2,
+                        0xE0..=0xEF => 3,
+                        0xF0..=0xF7 => 4,
+                        _ => 1,
+                    };
+                    if lead + width > bytes.len() {
+                        end = lead;
+                    }
+                }
+            }
+
+            (String::from_utf8_lossy(&bytes[start..end]).into_owned(), start..end)
+        }
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let little_endian = encoding == TextEncoding::Utf16Le;
+            let unit = |i: usize| {
+                let pair = [bytes[i], bytes[i + 1]];
+                if little_endian {
+                    u16::from_le_bytes(pair)
+                } else {
+                    u16::from_be_bytes(pair)
+                }
+            };
+
+            let mut start = 0;
+            let mut end = bytes.len() & !1;
+
+            if end >= 2 && (0xDC00..=0xDFFF).contains(&unit(0)) {
+                start = 2;
+            }
+            if !is_last && end >= start + 2 && (0xD800..=0xDBFF).contains(&unit(end - 2)) {
+                end -= 2;
+            }
+
+            let text = char::decode_utf16((start..end).step_by(2).map(unit))
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect();
+
+            // A stray final byte can't be decoded; count it as read
+            if is_last {
+                end = bytes.len();
+            }
+            (text, start..end)
+        }
+        TextEncoding::Windows1252 => (decode_windows_1252(bytes).0, 0..bytes.len()),
+    }
+}
+
+/// UTF-16 if NUL bytes fall consistently on one side of the code units, as
+/// they do for mostly-ASCII text; otherwise UTF-8 if valid, else Windows-1252
+fn guess_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.len().is_multiple_of(2) {
+        if let Some(encoding) = guess_utf16(bytes) {
+            return encoding;
+        }
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Windows1252
+    }
+}
+
+fn guess_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+    let sample = &bytes[..bytes.len().min(UTF16_SAMPLE_BYTES) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let units = sample.len() / 2;
+    let even_zeros = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+
+    if odd_zeros * 3 > units && even_zeros * 20 < units {
+        Some(TextEncoding::Utf16Le)
+    } else if even_zeros * 3 > units && odd_zeros * 20 < units {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let units = bytes.chunks_exact(2).map(|pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    });
+
+    char::decode_utf16(units).collect::<Result<String, _>>().ok()
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> (String, TextFormat) {
+    let content: String = bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect();
+
+    let line_ending = detect_line_ending(&content);
+    (
+        content,
+        TextFormat {
+            encoding: TextEncoding::Windows1252,
+            bom: false,
+            line_ending,
+        },
+    )
+}
+
+fn encode_windows_1252(c: char) -> Option<u8> {
+    match c as u32 {
+        0x00..=0x7F | 0xA0..=0xFF => Some(c as u8),
+        _ => WINDOWS_1252_HIGH
+            .iter()
+            .position(|&high| high == c)
+            .map(|i| 0x80 + i as u8),
+    }
+}
+
+/// The line ending used in `content`; `Lf` if it has no line breaks
+pub fn detect_line_ending(content: &str) -> LineEnding {
+    let bytes = content.as_bytes();
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    match (lf > 0, crlf > 0, cr > 0) {
+        (_, false, false) => LineEnding::Lf,
+        (false, true, false) => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    }
+}
+
+/// Replace every line break with `line_ending`; `Mixed` leaves them as is
+fn convert_line_endings(content: &str, line_ending: LineEnding) -> std::borrow::Cow<'_, str> {
+    let separator = match line_ending {
+        LineEnding::Lf => "\n",
+        LineEnding::Crlf => "\r\n",
+        LineEnding::Cr => "\r",
+        LineEnding::Mixed => return content.into(),
+    };
+
+    if detect_line_ending(content) == line_ending {
+        return content.into();
+    }
+
+    let mut converted = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                converted.push_str(separator);
+            }
+            '\n' => converted.push_str(separator),
+            c => converted.push(c),
+        }
+    }
+
+    converted.into()
+}
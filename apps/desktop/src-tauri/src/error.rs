@@ -0,0 +1,169 @@
+// ============================================================================
+// COMMAND ERRORS
+// ============================================================================
+//
+// Error returned by the commands, serialized as `{ code, message, path,
+// details }`. `code` tells the frontend what went wrong without parsing the
+// message: failed I/O takes its code from the `io::ErrorKind`, and the
+// commands' own checks (sandbox, names, size limits, write conflicts) have
+// codes of their own. `path` is the file the error is about, if any, and
+// `details` carries the extra data some codes have, e.g. `size` and `limit`
+// for `tooLarge`. Plain `String` errors convert into `io`, so `?` keeps
+// working on helpers that still return them.
+
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    NotFound,
+    AlreadyExists,
+    /// The operating system refused access to the file
+    PermissionDenied,
+    NotADirectory,
+    IsADirectory,
+    DirectoryNotEmpty,
+    ReadOnlyFilesystem,
+    StorageFull,
+    /// The file is locked or in use by another program
+    Busy,
+    InvalidInput,
+    /// The path is outside every opened workspace, the app's directories
+    /// and the files the user picked in a dialog
+    AccessDenied,
+    /// A theme, plugin or file name that would leave its directory;
+    /// `details.name` is the name
+    InvalidName,
+    /// The file is larger than the caller allows reading at once; it can
+    /// still be read in ranges. `details` has `size` and `limit`.
+    TooLarge,
+    /// The file changed on disk since the caller read it; `details.current`
+    /// is its metadata now, `null` if it has been deleted
+    Conflict,
+    /// The content has characters the requested encoding can't represent
+    Encoding,
+    /// Any other failure
+    Io,
+}
+
+impl From<io::ErrorKind> for ErrorCode {
+    fn from(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => ErrorCode::NotFound,
+            io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+            io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            io::ErrorKind::NotADirectory => ErrorCode::NotADirectory,
+            io::ErrorKind::IsADirectory => ErrorCode::IsADirectory,
+            io::ErrorKind::DirectoryNotEmpty => ErrorCode::DirectoryNotEmpty,
+            io::ErrorKind::ReadOnlyFilesystem => ErrorCode::ReadOnlyFilesystem,
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => ErrorCode::StorageFull,
+            io::ErrorKind::ResourceBusy | io::ErrorKind::ExecutableFileBusy => ErrorCode::Busy,
+            io::ErrorKind::FileTooLarge => ErrorCode::TooLarge,
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidFilename => ErrorCode::InvalidInput,
+            _ => ErrorCode::Io,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub path: Option<String>,
+    pub details: Option<Value>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        CommandError {
+            code,
+            message: message.into(),
+            path: None,
+            details: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// An I/O failure on `path`, coded by its kind; the message is
+    /// `"<message>: <error>"`
+    pub fn io(message: impl std::fmt::Display, path: &Path, error: io::Error) -> Self {
+        CommandError::new(error.kind().into(), format!("{}: {}", message, error)).with_path(path)
+    }
+
+    pub fn not_found(path: &Path) -> Self {
+        CommandError::new(ErrorCode::NotFound, format!("Path does not exist: {}", path.display())).with_path(path)
+    }
+
+    pub fn already_exists(path: &Path) -> Self {
+        CommandError::new(ErrorCode::AlreadyExists, format!("Path already exists: {}", path.display())).with_path(path)
+    }
+
+    pub fn not_a_directory(path: &Path) -> Self {
+        CommandError::new(ErrorCode::NotADirectory, format!("Path is not a directory: {}", path.display()))
+            .with_path(path)
+    }
+
+    /// A directory, or anything else that isn't a regular file, where a
+    /// file is expected
+    pub fn not_a_file(path: &Path) -> Self {
+        CommandError::new(ErrorCode::IsADirectory, format!("Path is not a file: {}", path.display())).with_path(path)
+    }
+
+    pub fn access_denied(path: &Path) -> Self {
+        CommandError::new(
+            ErrorCode::AccessDenied,
+            format!("Access denied outside the workspace: {}", path.display()),
+        )
+        .with_path(path)
+    }
+
+    pub fn too_large(path: &Path, size: u64, limit: u64) -> Self {
+        CommandError::new(
+            ErrorCode::TooLarge,
+            format!("File is too large to open ({} bytes): {}", size, path.display()),
+        )
+        .with_path(path)
+        .with_details(json!({ "size": size, "limit": limit }))
+    }
+
+    pub fn invalid_name(name: &str) -> Self {
+        CommandError::new(ErrorCode::InvalidName, format!("Invalid name: {}", name))
+            .with_details(json!({ "name": name }))
+    }
+}
+
+/// Turns the `io::Error` of a failed file operation into a `CommandError`
+pub trait IoContext<T> {
+    fn context(self, message: impl std::fmt::Display, path: &Path) -> Result<T, CommandError>;
+}
+
+impl<T> IoContext<T> for io::Result<T> {
+    fn context(self, message: impl std::fmt::Display, path: &Path) -> Result<T, CommandError> {
+        self.map_err(|e| CommandError::io(message, path, e))
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::new(ErrorCode::Io, message)
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
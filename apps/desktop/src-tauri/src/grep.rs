@@ -0,0 +1,308 @@
+// ============================================================================
+// WORKSPACE GREP
+// ============================================================================
+//
+// Literal or regex search over every text file in a workspace, run in
+// parallel in the backend. Matches are streamed to the webview per file as
+// `workspace-grep-results` events while the search runs; a search can be
+// stopped early with `cancel_grep` using the id it was started with.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::error::{CommandError, ErrorCode};
+use crate::ignore::IgnoreRules;
+use crate::sandbox;
+use crate::search::{floor_char_boundary, utf16_len, MatchRange};
+
+/// Name of the Tauri event carrying the matches of one file
+pub const GREP_RESULTS_EVENT: &str = "workspace-grep-results";
+
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+
+/// Files larger than this are skipped
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Bytes checked for a NUL byte to detect binary files
+const BINARY_CHECK_LENGTH: usize = 8 * 1024;
+
+/// Longer lines are cut down to a preview around the first match
+const MAX_LINE_LENGTH: usize = 1000;
+
+/// Bytes kept before the first match when a line is cut down
+const LINE_CONTEXT: usize = 100;
+
+/// Cancellation flags of running searches keyed by search id
+#[derive(Default)]
+pub struct GrepState {
+    searches: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepOptions {
+    /// Treat the query as a regular expression instead of literal text
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    whole_word: bool,
+    /// Extra gitignore-style patterns on top of the workspace's ignore files
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    /// Stop once this many matching lines were found
+    max_results: Option<usize>,
+}
+
+/// Matches in one file, the payload of a `workspace-grep-results` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepFileResult {
+    search_id: String,
+    path: String,
+    matches: Vec<GrepMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    /// 1-based line number
+    line_number: usize,
+    /// The line, or a preview of it when it is very long
+    text: String,
+    /// Match ranges within `text`, in UTF-16 code units
+    ranges: Vec<MatchRange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepSummary {
+    files_searched: usize,
+    files_matched: usize,
+    matches: usize,
+    /// Stopped by `cancel_grep`
+    cancelled: bool,
+    /// Stopped because `max_results` was reached
+    truncated: bool,
+}
+
+/// Search every file in a workspace
+///
+/// Results are emitted as `workspace-grep-results` events tagged with
+/// `search_id`; the command resolves with a summary once the search has
+/// finished or was cancelled.
+#[tauri::command]
+pub async fn grep_workspace(
+    app: tauri::AppHandle,
+    search_id: String,
+    workspace_root: String,
+    query: String,
+    options: Option<GrepOptions>,
+) -> Result<GrepSummary, CommandError> {
+    let options = options.unwrap_or_default();
+    let regex = build_regex(&query, &options)?;
+
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", workspace_root).into());
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let state = app.state::<GrepState>();
+        let mut searches = state
+            .searches
+            .lock()
+            .map_err(|_| "Grep state is poisoned".to_string())?;
+
+        if searches.contains_key(&search_id) {
+            return Err(format!("Search is already running: {}", search_id).into());
+        }
+        searches.insert(search_id.clone(), cancelled.clone());
+    }
+
+    let task_app = app.clone();
+    let task_id = search_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        run_search(&task_app, &task_id, &root, &regex, &options, &cancelled)
+    })
+    .await
+    .map_err(|e| format!("Search task failed: {}", e));
+
+    if let Ok(mut searches) = app.state::<GrepState>().searches.lock() {
+        searches.remove(&search_id);
+    }
+
+    Ok(result?)
+}
+
+/// Cancel a running search; returns `false` if no search has that id
+#[tauri::command]
+pub fn cancel_grep(state: tauri::State<'_, GrepState>, search_id: String) -> Result<bool, CommandError> {
+    let searches = state
+        .searches
+        .lock()
+        .map_err(|_| "Grep state is poisoned".to_string())?;
+
+    match searches.get(&search_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn build_regex(query: &str, options: &GrepOptions) -> Result<Regex, CommandError> {
+    if query.is_empty() {
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Search query is empty"));
+    }
+
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+
+    let pattern = if options.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| CommandError::new(ErrorCode::InvalidInput, format!("Invalid search pattern: {}", e)))
+}
+
+fn run_search(
+    app: &tauri::AppHandle,
+    search_id: &str,
+    root: &std::path::Path,
+    regex: &Regex,
+    options: &GrepOptions,
+    cancelled: &AtomicBool,
+) -> GrepSummary {
+    let files = IgnoreRules::load(root, &options.ignore_patterns).collect_files(root);
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+    let files_searched = AtomicUsize::new(0);
+    let files_matched = AtomicUsize::new(0);
+    let total_matches = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+
+    files.par_iter().for_each(|path| {
+        if cancelled.load(Ordering::Relaxed) || truncated.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Some(content) = read_text(path) else {
+            return;
+        };
+        files_searched.fetch_add(1, Ordering::Relaxed);
+
+        let mut matches = search_text(&content, regex);
+        if matches.is_empty() {
+            return;
+        }
+
+        // Claim a share of the remaining result budget
+        let before = total_matches.fetch_add(matches.len(), Ordering::Relaxed);
+        if before >= max_results {
+            truncated.store(true, Ordering::Relaxed);
+            return;
+        }
+        if before + matches.len() > max_results {
+            matches.truncate(max_results - before);
+            truncated.store(true, Ordering::Relaxed);
+        }
+
+        files_matched.fetch_add(1, Ordering::Relaxed);
+
+        let result = GrepFileResult {
+            search_id: search_id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            matches,
+        };
+        if let Err(e) = app.emit(GREP_RESULTS_EVENT, result) {
+            eprintln!("[grep] Failed to emit results: {}", e);
+        }
+    });
+
+    GrepSummary {
+        files_searched: files_searched.into_inner(),
+        files_matched: files_matched.into_inner(),
+        matches: total_matches.into_inner().min(max_results),
+        cancelled: cancelled.load(Ordering::Relaxed),
+        truncated: truncated.into_inner(),
+    }
+}
+
+/// File content as text, `None` for large, binary or non-UTF-8 files
+fn read_text(path: &std::path::Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_FILE_SIZE {
+        return None;
+    }
+
+    let bytes = fs::read(path).ok()?;
+    if bytes[..bytes.len().min(BINARY_CHECK_LENGTH)].contains(&0) {
+        return None;
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn search_text(content: &str, regex: &Regex) -> Vec<GrepMatch> {
+    // Most files don't match at all; rule them out before splitting lines
+    if !regex.is_match(content) {
+        return Vec::new();
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let ranges: Vec<_> = regex
+                .find_iter(line)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect();
+            let first = ranges.first()?;
+
+            let (start, end) = if line.len() <= MAX_LINE_LENGTH {
+                (0, line.len())
+            } else {
+                let start = floor_char_boundary(line, first.start.saturating_sub(LINE_CONTEXT));
+                let end = floor_char_boundary(line, (start + MAX_LINE_LENGTH).min(line.len()));
+                (start, end)
+            };
+
+            let ranges = ranges
+                .iter()
+                .filter(|r| r.start >= start && r.end <= end)
+                .map(|r| MatchRange {
+                    start: utf16_len(&line[start..r.start]),
+                    end: utf16_len(&line[start..r.end]),
+                })
+                .collect();
+
+            Some(GrepMatch {
+                line_number: index + 1,
+                text: line[start..end].to_string(),
+                ranges,
+            })
+        })
+        .collect()
+}
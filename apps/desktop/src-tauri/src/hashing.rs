@@ -0,0 +1,467 @@
+// ============================================================================
+// CONTENT HASHING
+// ============================================================================
+//
+// Hashes files in Rust so the frontend can compare a whole vault (e.g. for
+// sync change detection) without reading every file over IPC. Files are
+// hashed in parallel, and hashes are cached in `<app data>/hash-cache.json`
+// keyed by path, size and modification time, so a file is only read again
+// once it changes. The least recently used entries are dropped once the
+// cache grows past `MAX_CACHE_ENTRIES`. Changes to the cache are written
+// `SAVE_DELAY` after the first one, and on exit, rather than on every call.
+//
+// Hashes are hex-encoded, of either the bytes on disk or the text
+// `read_file` decodes them to, which differ for files with a byte order
+// mark or in an encoding other than UTF-8.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::atomic;
+use crate::encoding;
+use crate::error::{CommandError, IoContext};
+use crate::sandbox;
+
+const CACHE_FILE: &str = "hash-cache.json";
+
+const MAX_CACHE_ENTRIES: usize = 100_000;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// How long changes to the cache wait before being written, so a burst of
+/// `hash_file` calls writes it once
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+/// What is hashed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashContent {
+    /// The bytes on disk
+    #[default]
+    Bytes,
+    /// The UTF-8 text `read_file` returns, without a byte order mark
+    Text,
+}
+
+/// The hash cache, loaded on first use
+#[derive(Default)]
+pub struct HashState {
+    cache: Mutex<Option<HashCache>>,
+    /// Whether a delayed save is already scheduled
+    save_scheduled: AtomicBool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HashCache {
+    entries: HashMap<String, CacheEntry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    /// Modification time in milliseconds since the Unix epoch
+    modified: u64,
+    hashes: HashMap<HashAlgorithm, String>,
+    /// Hashes of the decoded text
+    #[serde(default)]
+    text_hashes: HashMap<HashAlgorithm, String>,
+    /// When the entry was last used, in milliseconds since the Unix epoch
+    used: u64,
+}
+
+/// Hash of one file, as returned by `hash_files`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHash {
+    path: String,
+    /// `None` if the file couldn't be hashed; see `error`
+    hash: Option<String>,
+    size: Option<u64>,
+    /// Milliseconds since the Unix epoch
+    modified: Option<u64>,
+    error: Option<CommandError>,
+}
+
+impl FileHash {
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+}
+
+/// A file to hash, with the metadata its cache entry is keyed by
+struct Target {
+    path: PathBuf,
+    key: String,
+    size: u64,
+    modified: u64,
+}
+
+/// Hash one file (default: SHA-256 of the bytes on disk)
+#[tauri::command]
+pub async fn hash_file(
+    app: tauri::AppHandle,
+    path: String,
+    algorithm: Option<HashAlgorithm>,
+    content: Option<HashContent>,
+) -> Result<String, CommandError> {
+    let mut results = hash_files(app, vec![path], algorithm, content).await?;
+
+    match results.pop() {
+        Some(FileHash { hash: Some(hash), .. }) => Ok(hash),
+        Some(FileHash { error: Some(error), .. }) => Err(error),
+        _ => Err("Failed to hash file".to_string().into()),
+    }
+}
+
+/// Hash many files in parallel (default: SHA-256 of the bytes on disk);
+/// results are in the order of `paths`, and a file that can't be read
+/// doesn't fail the others
+#[tauri::command]
+pub async fn hash_files(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    algorithm: Option<HashAlgorithm>,
+    content: Option<HashContent>,
+) -> Result<Vec<FileHash>, CommandError> {
+    let algorithm = algorithm.unwrap_or_default();
+    let content = content.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || hash_all(&app, paths, algorithm, content))
+        .await
+        .map_err(|e| format!("Hash task failed: {}", e))?
+}
+
+/// Hash files in parallel through the cache; see `hash_files`
+pub fn hash_all(
+    app: &tauri::AppHandle,
+    paths: Vec<String>,
+    algorithm: HashAlgorithm,
+    content: HashContent,
+) -> Result<Vec<FileHash>, CommandError> {
+    let targets: Vec<Result<Target, CommandError>> = paths
+        .par_iter()
+        .map(|path| target(app, Path::new(path)))
+        .collect();
+
+    let now = crate::now_millis();
+    let keys: Vec<Option<String>> = targets
+        .iter()
+        .map(|target| target.as_ref().ok().map(|target| target.key.clone()))
+        .collect();
+
+    // Look up cached hashes without holding the lock while hashing
+    let cached: Vec<Option<String>> = {
+        let mut guard = lock(app)?;
+        let cache = loaded(app, &mut guard);
+
+        targets
+            .iter()
+            .map(|target| {
+                let target = target.as_ref().ok()?;
+                let entry = cache.entries.get_mut(&target.key)?;
+                if entry.size != target.size || entry.modified != target.modified {
+                    return None;
+                }
+
+                let hash = entry.hashes_mut(content).get(&algorithm)?.clone();
+                entry.used = now;
+                Some(hash)
+            })
+            .collect()
+    };
+
+    let results: Vec<FileHash> = targets
+        .into_par_iter()
+        .zip(paths.into_par_iter())
+        .zip(cached.into_par_iter())
+        .map(|((target, path), cached)| {
+            let target = match target {
+                Ok(target) => target,
+                Err(error) => {
+                    return FileHash {
+                        path,
+                        hash: None,
+                        size: None,
+                        modified: None,
+                        error: Some(error),
+                    }
+                }
+            };
+
+            let (hash, error) = match cached.map_or_else(|| hash_path(&target.path, algorithm, content), Ok) {
+                Ok(hash) => (Some(hash), None),
+                Err(error) => (None, Some(error)),
+            };
+
+            FileHash {
+                path,
+                hash,
+                size: Some(target.size),
+                modified: Some(target.modified),
+                error,
+            }
+        })
+        .collect();
+
+    let dirty = {
+        let mut guard = lock(app)?;
+        let cache = loaded(app, &mut guard);
+
+        for (result, key) in results.iter().zip(keys) {
+            if let (Some(key), Some(hash), Some(size), Some(modified)) = (key, &result.hash, result.size, result.modified) {
+                cache.insert(key, size, modified, algorithm, content, hash);
+            }
+        }
+        cache.dirty
+    };
+
+    if dirty {
+        schedule_save(app);
+    }
+
+    Ok(results)
+}
+
+/// Write the cache `SAVE_DELAY` from now, unless a save is already pending
+fn schedule_save(app: &tauri::AppHandle) {
+    if app.state::<HashState>().save_scheduled.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(SAVE_DELAY);
+        app.state::<HashState>().save_scheduled.store(false, Ordering::Release);
+        flush(&app);
+    });
+}
+
+/// Write pending changes to the cache, e.g. on exit
+pub fn flush(app: &tauri::AppHandle) {
+    // Serialize under the lock, but write without holding it
+    let content = {
+        let Ok(mut guard) = lock(app) else {
+            return;
+        };
+        let Some(cache) = guard.as_mut() else {
+            return;
+        };
+
+        cache.prune();
+        if !cache.dirty {
+            return;
+        }
+
+        match serde_json::to_string(cache) {
+            Ok(content) => {
+                cache.dirty = false;
+                content
+            }
+            Err(e) => {
+                eprintln!("[hashing] Failed to save the hash cache: {}", e);
+                return;
+            }
+        }
+    };
+
+    let Some(path) = cache_path(app) else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| atomic::write_atomic(&path, content));
+
+    if let Err(e) = result {
+        eprintln!("[hashing] Failed to save the hash cache: {}", e);
+        // Try again with the next save
+        if let Ok(mut guard) = lock(app) {
+            if let Some(cache) = guard.as_mut() {
+                cache.dirty = true;
+            }
+        }
+    }
+}
+
+/// Check `path` is an allowed file and read the metadata it is cached by
+fn target(app: &tauri::AppHandle, path: &Path) -> Result<Target, CommandError> {
+    sandbox::check(app, path)?;
+
+    let metadata = fs::metadata(path).context("Failed to read metadata", path)?;
+    if !metadata.is_file() {
+        return Err(CommandError::not_a_file(path));
+    }
+
+    Ok(Target {
+        path: path.to_path_buf(),
+        key: key(path),
+        size: metadata.len(),
+        modified: crate::modified_millis(&metadata),
+    })
+}
+
+/// Hash a file's bytes, reading them in chunks, or its decoded text
+fn hash_path(path: &Path, algorithm: HashAlgorithm, content: HashContent) -> Result<String, CommandError> {
+    if content == HashContent::Text {
+        let bytes = fs::read(path).context("Failed to read file", path)?;
+        let (text, _) = encoding::decode(&bytes);
+        return Ok(hash_bytes(text.as_bytes(), algorithm));
+    }
+
+    let mut file = File::open(path).context("Failed to open file", path)?;
+
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            use sha2::{Digest, Sha256};
+
+            let mut hasher = Sha256::new();
+            read_chunks(&mut file, path, |bytes| hasher.update(bytes))?;
+            Ok(hex(&hasher.finalize()))
+        }
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            read_chunks(&mut file, path, |bytes| {
+                hasher.update(bytes);
+            })?;
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+    }
+}
+
+/// Hex digest of `bytes`
+pub fn hash_bytes(bytes: &[u8], algorithm: HashAlgorithm) -> String {
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            use sha2::{Digest, Sha256};
+
+            hex(&Sha256::digest(bytes))
+        }
+        HashAlgorithm::Blake3 => blake3::hash(bytes).to_hex().to_string(),
+    }
+}
+
+fn read_chunks(file: &mut File, path: &Path, mut consume: impl FnMut(&[u8])) -> Result<(), CommandError> {
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer).context("Failed to read file", path)?;
+        if read == 0 {
+            return Ok(());
+        }
+        consume(&buffer[..read]);
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl HashCache {
+    fn load(app: &tauri::AppHandle) -> Self {
+        cache_path(app)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn insert(
+        &mut self,
+        key: String,
+        size: u64,
+        modified: u64,
+        algorithm: HashAlgorithm,
+        content: HashContent,
+        hash: &str,
+    ) {
+        let now = crate::now_millis();
+        let entry = self.entries.entry(key).or_insert_with(|| CacheEntry {
+            size,
+            modified,
+            hashes: HashMap::new(),
+            text_hashes: HashMap::new(),
+            used: now,
+        });
+
+        // Hashes of an older version of the file are stale
+        if entry.size != size || entry.modified != modified {
+            entry.size = size;
+            entry.modified = modified;
+            entry.hashes.clear();
+            entry.text_hashes.clear();
+        }
+
+        let hashes = entry.hashes_mut(content);
+        if hashes.get(&algorithm).map(String::as_str) != Some(hash) {
+            hashes.insert(algorithm, hash.to_string());
+            self.dirty = true;
+        }
+        entry.used = now;
+    }
+
+    /// Drop the least recently used entries beyond the limit
+    fn prune(&mut self) {
+        if self.entries.len() > MAX_CACHE_ENTRIES {
+            let mut used: Vec<u64> = self.entries.values().map(|entry| entry.used).collect();
+            used.sort_unstable_by(|a, b| b.cmp(a));
+            let cutoff = used[MAX_CACHE_ENTRIES - 1];
+
+            self.entries.retain(|_, entry| entry.used >= cutoff);
+            self.dirty = true;
+        }
+    }
+}
+
+impl CacheEntry {
+    fn hashes_mut(&mut self, content: HashContent) -> &mut HashMap<HashAlgorithm, String> {
+        match content {
+            HashContent::Bytes => &mut self.hashes,
+            HashContent::Text => &mut self.text_hashes,
+        }
+    }
+}
+
+fn cache_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(CACHE_FILE))
+}
+
+/// Cache key of a path; the canonical path where it can be resolved, so
+/// different spellings of a path share one entry
+fn key(path: &Path) -> String {
+    sandbox::resolve(path)
+        .unwrap_or_else(|| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn lock(app: &tauri::AppHandle) -> Result<MutexGuard<'_, Option<HashCache>>, CommandError> {
+    app.state::<HashState>()
+        .inner()
+        .cache
+        .lock()
+        .map_err(|_| "Hash cache is poisoned".to_string().into())
+}
+
+fn loaded<'a>(app: &tauri::AppHandle, guard: &'a mut MutexGuard<'_, Option<HashCache>>) -> &'a mut HashCache {
+    guard.get_or_insert_with(|| HashCache::load(app))
+}
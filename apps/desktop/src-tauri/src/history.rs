@@ -0,0 +1,512 @@
+// ============================================================================
+// VERSION HISTORY
+// ============================================================================
+//
+// Before `write_file` overwrites a file inside an open workspace, its current
+// content is saved as a version, so earlier states of a note can be listed,
+// compared with the current file and restored.
+//
+// Each workspace has a store in `<app data>/history/<workspace key>/`:
+// contents live in `objects/<sha-256>`, shared by every version with the same
+// content, and `index.json` lists the versions of each file by its
+// workspace-relative path. A file gets at most one version per
+// `SNAPSHOT_INTERVAL_MILLIS`, and none when its content equals the latest
+// version. Versions older than `MAX_AGE_DAYS` are pruned, as are the oldest
+// versions once the store grows past `MAX_STORE_BYTES`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::diff::{self, DiffLine};
+use crate::error::{CommandError, ErrorCode, IoContext};
+use crate::ignore::to_slash_path;
+use crate::{atomic, encoding, sandbox, trash};
+
+/// Minimum time between two versions of the same file
+const SNAPSHOT_INTERVAL_MILLIS: u64 = 10 * 60 * 1000;
+
+/// Files larger than this are not versioned
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Default limits applied after each snapshot
+const MAX_AGE_DAYS: u64 = 30;
+const MAX_STORE_BYTES: u64 = 200 * 1024 * 1024;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+const INDEX_FILE: &str = "index.json";
+const OBJECTS_DIR: &str = "objects";
+
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Open history stores keyed by canonical workspace root
+#[derive(Default)]
+pub struct HistoryState {
+    stores: Mutex<HashMap<PathBuf, Store>>,
+}
+
+/// A saved version of a file, as listed to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersion {
+    id: String,
+    /// Milliseconds since the Unix epoch
+    created_at: u64,
+    size: u64,
+    /// Hex-encoded SHA-256 of the content
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    /// Versions by workspace-relative, `/`-separated path, oldest first
+    files: BTreeMap<String, Vec<FileVersion>>,
+}
+
+struct Store {
+    dir: PathBuf,
+    index: HistoryIndex,
+}
+
+/// Limits for `prune_history`; unset limits use the defaults
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneHistoryOptions {
+    max_age_days: Option<u64>,
+    max_total_bytes: Option<u64>,
+}
+
+/// The versions in a workspace's store, copied so their contents can be
+/// read without holding the history lock
+pub struct HistoryView {
+    root: PathBuf,
+    objects: PathBuf,
+    files: BTreeMap<String, Vec<FileVersion>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    removed_versions: usize,
+    freed_bytes: u64,
+}
+
+/// Save the current content of `path` as a version before it is
+/// overwritten; does nothing outside an open workspace
+///
+/// Failures are logged rather than returned so they never block a save.
+pub fn snapshot(app: &tauri::AppHandle, path: &Path) {
+    if let Err(e) = try_snapshot(app, path, false) {
+        eprintln!("[history] Failed to save a version of {}: {}", path.display(), e);
+    }
+}
+
+/// Carry the versions of a renamed or moved file (or of every file in a
+/// folder) over to its new path
+pub fn rename(app: &tauri::AppHandle, from: &Path, to: &Path) {
+    let (Some(root), Some(to_root)) = (sandbox::workspace_of(app, from), sandbox::workspace_of(app, to)) else {
+        return;
+    };
+    if root != to_root {
+        return;
+    }
+
+    let (Some(from), Some(to)) = (relative_key(&root, from), relative_key(&root, to)) else {
+        return;
+    };
+
+    let result = with_store(app, &root, |store| {
+        let moved: Vec<String> = store
+            .index
+            .files
+            .keys()
+            .filter(|key| **key == from || key.starts_with(&format!("{}/", from)))
+            .cloned()
+            .collect();
+
+        if moved.is_empty() {
+            return Ok(());
+        }
+
+        for key in moved {
+            if let Some(versions) = store.index.files.remove(&key) {
+                let new_key = format!("{}{}", to, &key[from.len()..]);
+                store.index.files.entry(new_key).or_default().extend(versions);
+            }
+        }
+
+        store.save_index()
+    });
+
+    if let Err(e) = result {
+        eprintln!("[history] Failed to move versions of {}: {}", from, e);
+    }
+}
+
+/// Versions of a file, newest first
+#[tauri::command]
+pub fn list_versions(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+) -> Result<Vec<FileVersion>, CommandError> {
+    let (root, key) = locate(&app, &workspace_root, &path)?;
+
+    with_store(&app, &root, |store| -> Result<_, CommandError> {
+        let mut versions = store.index.files.get(&key).cloned().unwrap_or_default();
+        versions.reverse();
+        Ok(versions)
+    })
+}
+
+/// Content of a version
+#[tauri::command]
+pub fn get_version(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+    id: String,
+) -> Result<String, CommandError> {
+    let (root, key) = locate(&app, &workspace_root, &path)?;
+
+    let bytes = with_store(&app, &root, |store| store.read_version(&key, &id))?;
+    Ok(encoding::decode(&bytes).0)
+}
+
+/// Line diff from a version to the current content of the file; a deleted
+/// file diffs against empty content
+#[tauri::command]
+pub fn diff_version(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+    id: String,
+) -> Result<Vec<DiffLine>, CommandError> {
+    let (root, key) = locate(&app, &workspace_root, &path)?;
+    let version = with_store(&app, &root, |store| store.read_version(&key, &id))?;
+    let (version, _) = encoding::decode(&version);
+
+    let file_path = Path::new(&path);
+    let current = match fs::read(file_path) {
+        Ok(bytes) => encoding::decode(&bytes).0,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(CommandError::io("Failed to read file", file_path, e)),
+    };
+
+    Ok(diff::diff_lines(&version, &current))
+}
+
+/// Replace the file with a version
+///
+/// The current content is saved as a version first, regardless of the
+/// snapshot interval, so the restore can itself be undone.
+#[tauri::command]
+pub fn restore_version(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+    id: String,
+) -> Result<(), CommandError> {
+    let (root, key) = locate(&app, &workspace_root, &path)?;
+    let content = with_store(&app, &root, |store| store.read_version(&key, &id))?;
+
+    let file_path = PathBuf::from(&path);
+    try_snapshot(&app, &file_path, true)?;
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create parent directories", parent)?;
+    }
+    atomic::write_atomic(&file_path, content).context("Failed to restore version", &file_path)?;
+
+    crate::refresh_indexes(&app, vec![file_path], Vec::new());
+    Ok(())
+}
+
+/// Remove versions older than `max_age_days` and then the oldest versions
+/// until the store is under `max_total_bytes`
+#[tauri::command]
+pub fn prune_history(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    options: Option<PruneHistoryOptions>,
+) -> Result<PruneReport, CommandError> {
+    let root = sandbox::resolve(Path::new(&workspace_root))
+        .ok_or_else(|| CommandError::access_denied(Path::new(&workspace_root)))?;
+    sandbox::check_workspace(&app, &root)?;
+
+    let options = options.unwrap_or_default();
+    let max_age = options.max_age_days.unwrap_or(MAX_AGE_DAYS) * MILLIS_PER_DAY;
+    let max_bytes = options.max_total_bytes.unwrap_or(MAX_STORE_BYTES);
+
+    Ok(with_store(&app, &root, |store| store.prune(max_age, max_bytes))?)
+}
+
+/// The versions saved for a workspace; `root` must be canonical
+pub fn view(app: &tauri::AppHandle, root: &Path) -> Option<HistoryView> {
+    with_store(app, root, |store| -> Result<_, String> {
+        Ok(HistoryView {
+            root: root.to_path_buf(),
+            objects: store.dir.join(OBJECTS_DIR),
+            files: store.index.files.clone(),
+        })
+    })
+    .ok()
+}
+
+impl HistoryView {
+    /// Versions of a file, oldest first
+    pub fn versions(&self, path: &Path) -> &[FileVersion] {
+        relative_key(&self.root, path)
+            .and_then(|key| self.files.get(&key))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Content of a version; `None` if it was pruned in the meantime
+    pub fn read(&self, version: &FileVersion) -> Option<Vec<u8>> {
+        fs::read(self.objects.join(&version.hash)).ok()
+    }
+}
+
+impl FileVersion {
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+}
+
+fn try_snapshot(app: &tauri::AppHandle, path: &Path, force: bool) -> Result<(), String> {
+    let Some(root) = sandbox::workspace_of(app, path) else {
+        return Ok(());
+    };
+    let Some(key) = relative_key(&root, path) else {
+        return Ok(());
+    };
+    if trash::is_in_trash(&root, &root.join(&key)) {
+        return Ok(());
+    }
+
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_FILE_BYTES => metadata,
+        _ => return Ok(()),
+    };
+
+    with_store(app, &root, |store| {
+        let now = crate::now_millis();
+        let latest = store.index.files.get(&key).and_then(|versions| versions.last());
+
+        if !force && latest.is_some_and(|latest| now.saturating_sub(latest.created_at) < SNAPSHOT_INTERVAL_MILLIS) {
+            return Ok(());
+        }
+
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let hash = crate::hash_bytes(&bytes);
+
+        if latest.is_some_and(|latest| latest.hash == hash) {
+            return Ok(());
+        }
+
+        let object = store.dir.join(OBJECTS_DIR).join(&hash);
+        if !object.exists() {
+            fs::create_dir_all(store.dir.join(OBJECTS_DIR)).map_err(|e| e.to_string())?;
+            atomic::write_atomic(&object, &bytes).map_err(|e| e.to_string())?;
+        }
+
+        store.index.files.entry(key.clone()).or_default().push(FileVersion {
+            id: format!("{}-{}", now, ID_COUNTER.fetch_add(1, Ordering::Relaxed)),
+            created_at: now,
+            size: metadata.len(),
+            hash,
+        });
+
+        store.prune(MAX_AGE_DAYS * MILLIS_PER_DAY, MAX_STORE_BYTES)?;
+        Ok(())
+    })
+}
+
+impl Store {
+    fn open(dir: PathBuf) -> Self {
+        let index = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Store { dir, index }
+    }
+
+    fn save_index(&self) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create history store: {}", e))?;
+
+        let content = serde_json::to_string(&self.index).map_err(|e| e.to_string())?;
+        atomic::write_atomic(&self.dir.join(INDEX_FILE), content).map_err(|e| format!("Failed to save history: {}", e))
+    }
+
+    fn read_version(&self, key: &str, id: &str) -> Result<Vec<u8>, CommandError> {
+        let version = self
+            .index
+            .files
+            .get(key)
+            .and_then(|versions| versions.iter().find(|version| version.id == id))
+            .ok_or_else(|| CommandError::new(ErrorCode::NotFound, format!("Version not found: {}", id)))?;
+
+        let object = self.dir.join(OBJECTS_DIR).join(&version.hash);
+        fs::read(&object).context("Failed to read version", &object)
+    }
+
+    /// Apply the age and size limits, delete contents no version refers to
+    /// any more and save the index
+    fn prune(&mut self, max_age: u64, max_bytes: u64) -> Result<PruneReport, String> {
+        let cutoff = crate::now_millis().saturating_sub(max_age);
+        let mut removed_versions = 0;
+
+        for versions in self.index.files.values_mut() {
+            let before = versions.len();
+            versions.retain(|version| version.created_at >= cutoff);
+            removed_versions += before - versions.len();
+        }
+
+        // Oldest first across all files; each content counts once
+        let mut all: Vec<(u64, String, String)> = self
+            .index
+            .files
+            .iter()
+            .flat_map(|(key, versions)| versions.iter().map(move |v| (v.created_at, key.clone(), v.id.clone())))
+            .collect();
+        all.sort();
+
+        let mut total = self.stored_bytes();
+        let mut oldest = all.into_iter();
+
+        while total > max_bytes {
+            let Some((_, key, id)) = oldest.next() else {
+                break;
+            };
+
+            if let Some(versions) = self.index.files.get_mut(&key) {
+                versions.retain(|version| version.id != id);
+                removed_versions += 1;
+            }
+            total = self.stored_bytes();
+        }
+
+        self.index.files.retain(|_, versions| !versions.is_empty());
+
+        let freed_bytes = self.remove_unreferenced();
+        self.save_index()?;
+
+        Ok(PruneReport {
+            removed_versions,
+            freed_bytes,
+        })
+    }
+
+    /// Size of the distinct contents the versions refer to
+    fn stored_bytes(&self) -> u64 {
+        let mut seen = HashSet::new();
+
+        self.index
+            .files
+            .values()
+            .flatten()
+            .filter(|version| seen.insert(&version.hash))
+            .map(|version| version.size)
+            .sum()
+    }
+
+    /// Delete objects no version refers to; returns the bytes freed
+    fn remove_unreferenced(&self) -> u64 {
+        let referenced: HashSet<&str> = self
+            .index
+            .files
+            .values()
+            .flatten()
+            .map(|version| version.hash.as_str())
+            .collect();
+
+        let Ok(entries) = fs::read_dir(self.dir.join(OBJECTS_DIR)) else {
+            return 0;
+        };
+
+        let mut freed = 0;
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if referenced.contains(name.to_string_lossy().as_ref()) {
+                continue;
+            }
+
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if fs::remove_file(entry.path()).is_ok() {
+                freed += size;
+            }
+        }
+        freed
+    }
+}
+
+/// Canonical workspace root and the file's key in its store, after
+/// checking both are inside the sandbox
+fn locate(app: &tauri::AppHandle, workspace_root: &str, path: &str) -> Result<(PathBuf, String), CommandError> {
+    let root = sandbox::resolve(Path::new(workspace_root))
+        .ok_or_else(|| CommandError::access_denied(Path::new(workspace_root)))?;
+    sandbox::check_workspace(app, &root)?;
+    sandbox::check_workspace(app, Path::new(path))?;
+
+    let key = relative_key(&root, Path::new(path)).ok_or_else(|| {
+        CommandError::new(ErrorCode::InvalidInput, format!("Path is not inside the workspace: {}", path))
+            .with_path(Path::new(path))
+    })?;
+
+    Ok((root, key))
+}
+
+/// `/`-separated path of `path` relative to the canonical `root`
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let resolved = sandbox::resolve(path)?;
+    let relative = resolved.strip_prefix(root).ok()?;
+
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(to_slash_path(relative))
+}
+
+/// Run `f` on the workspace's store, opening it on first use
+fn with_store<T, E: From<String>>(
+    app: &tauri::AppHandle,
+    root: &Path,
+    f: impl FnOnce(&mut Store) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut stores = lock(app)?;
+
+    if !stores.contains_key(root) {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("history")
+            .join(crate::search::workspace_key(root));
+        stores.insert(root.to_path_buf(), Store::open(dir));
+    }
+
+    let store = stores
+        .get_mut(root)
+        .ok_or_else(|| "History store is unavailable".to_string())?;
+    f(store)
+}
+
+fn lock(app: &tauri::AppHandle) -> Result<MutexGuard<'_, HashMap<PathBuf, Store>>, String> {
+    app.state::<HistoryState>()
+        .inner()
+        .stores
+        .lock()
+        .map_err(|_| "History state is poisoned".to_string())
+}
@@ -0,0 +1,283 @@
+// ============================================================================
+// IGNORE RULES
+// ============================================================================
+//
+// gitignore-style rules read from `.gitignore` and `.inkdownignore` at the
+// workspace root, plus patterns passed in by the caller (e.g. SelectiveSync's
+// `ignorePatterns`). Supports `*`, `?`, `[...]`, `**`, `!negation`, a leading
+// `/` to anchor to the root and a trailing `/` to match directories only.
+//
+// Shared by `read_directory`, the watcher, search, grep and the link index so
+// they all agree on what is part of the workspace.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::error::CommandError;
+use crate::{sandbox, trash};
+
+/// Ignore files read from the workspace root, in order of precedence
+pub const IGNORE_FILES: &[&str] = &[".gitignore", ".inkdownignore"];
+
+/// Entries that are never part of the workspace content
+const ALWAYS_IGNORED: &[&str] = &[".git", trash::TRASH_DIR];
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+    include_hidden: bool,
+}
+
+impl IgnoreRules {
+    /// Rules from the workspace's ignore files followed by `extra_patterns`
+    pub fn load(root: &Path, extra_patterns: &[String]) -> Self {
+        let mut rules = IgnoreRules::from_patterns(&read_patterns(root));
+
+        for pattern in extra_patterns {
+            rules.add(pattern);
+        }
+
+        rules
+    }
+
+    /// Rules from the given patterns only, ignoring the workspace's ignore files
+    pub fn from_patterns(patterns: &[String]) -> Self {
+        let mut rules = IgnoreRules::default();
+
+        for pattern in patterns {
+            rules.add(pattern);
+        }
+
+        rules
+    }
+
+    /// Whether dot-files and dot-folders are kept (`.git` and the trash
+    /// are skipped regardless)
+    pub fn include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
+    /// Add a single gitignore-style pattern; blank lines and comments are skipped
+    pub fn add(&mut self, pattern: &str) {
+        if let Some(rule) = parse_rule(pattern) {
+            self.rules.push(rule);
+        }
+    }
+
+    /// Whether an entry is ignored, judged on the entry alone; walks prune
+    /// ignored directories so their contents are never visited
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let Some(name) = relative.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+
+        if ALWAYS_IGNORED.contains(&name) || (!self.include_hidden && name.starts_with('.')) {
+            return true;
+        }
+
+        let path = to_slash_path(relative);
+        let mut ignored = false;
+
+        // Like git, the last matching rule wins
+        for rule in &self.rules {
+            if (!rule.dir_only || is_dir) && rule.regex.is_match(&path) {
+                ignored = !rule.negated;
+            }
+        }
+
+        ignored
+    }
+
+    /// Whether a path inside the workspace is ignored, either itself or
+    /// through one of its parent folders
+    pub fn is_path_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        relative
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .enumerate()
+            .any(|(i, ancestor)| self.is_ignored(ancestor, i > 0 || is_dir))
+    }
+
+    /// Every non-ignored file under `root`, without following symlinks
+    pub fn collect_files(&self, root: &Path) -> Vec<PathBuf> {
+        self.collect_files_in(root, root)
+    }
+
+    /// Every non-ignored file under `dir`, a folder inside the workspace `root`
+    pub fn collect_files_in(&self, root: &Path, dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        self.collect_into(root, dir, &mut files);
+        files
+    }
+
+    fn collect_into(&self, root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+
+            if self.is_ignored(relative, file_type.is_dir()) {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                self.collect_into(root, &path, files);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+}
+
+/// Patterns from the workspace's ignore files, without blank lines and comments
+pub fn read_patterns(root: &Path) -> Vec<String> {
+    IGNORE_FILES
+        .iter()
+        .filter_map(|file| fs::read_to_string(root.join(file)).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .map(|line| line.trim_end().to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Whether a path is one of the workspace's ignore files
+pub fn is_ignore_file(root: &Path, path: &Path) -> bool {
+    path.parent() == Some(root)
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| IGNORE_FILES.contains(&name))
+}
+
+/// Patterns from the workspace's ignore files, so the frontend (e.g.
+/// SelectiveSync) can apply the same rules
+#[tauri::command]
+pub fn read_ignore_patterns(app: tauri::AppHandle, workspace_root: String) -> Result<Vec<String>, CommandError> {
+    let root = Path::new(&workspace_root);
+    sandbox::check_workspace(&app, root)?;
+
+    Ok(read_patterns(root))
+}
+
+fn parse_rule(line: &str) -> Option<Rule> {
+    let mut pattern = line.trim_end();
+
+    if pattern.is_empty() || pattern.starts_with('#') {
+        return None;
+    }
+
+    let negated = pattern.starts_with('!');
+    if negated {
+        pattern = &pattern[1..];
+    }
+
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+
+    // A slash anywhere but the end anchors the pattern to the root;
+    // otherwise it matches at any depth
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from("^");
+    if !anchored {
+        regex.push_str("(?:.*/)?");
+    }
+    regex.push_str(&glob_to_regex(pattern));
+    regex.push('$');
+
+    Regex::new(&regex).ok().map(|regex| Rule {
+        regex,
+        negated,
+        dir_only,
+    })
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // `**/` matches zero or more directories
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let mut class = String::from("[");
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                if closed {
+                    class.push(']');
+                    regex.push_str(&class);
+                } else {
+                    // Unterminated class: treat the bracket literally
+                    regex.push_str(&regex::escape("["));
+                    regex.push_str(&regex::escape(&class[1..]));
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+/// Relative path with `/` separators, as ignore patterns expect
+pub fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,71 +1,102 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 use font_kit::source::SystemSource;
 
+use error::{CommandError, ErrorCode, IoContext};
+
+mod asset;
+mod atomic;
+mod batch;
+mod chunked;
+mod diff;
+mod duplicates;
+mod encoding;
+mod error;
+mod grep;
+mod hashing;
+mod history;
+mod ignore;
+mod links;
+mod markdown;
+mod orphans;
+mod sandbox;
+mod search;
+mod stat;
+mod stats;
+mod templates;
+mod thumbnail;
+mod transfer;
+mod trash;
+mod watcher;
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// The app config directory, holding config files, themes and plugins
+fn config_dir(app: &tauri::AppHandle) -> Result<PathBuf, CommandError> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to get config directory: {}", e)))
+}
+
 /// Get the configuration directory path based on the OS
 #[tauri::command]
-fn get_config_dir(app: tauri::AppHandle) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn get_config_dir(app: tauri::AppHandle) -> Result<String, CommandError> {
+    let config_dir = config_dir(&app)?;
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        .context("Failed to create config directory", &config_dir)?;
     
     config_dir
         .to_str()
-        .ok_or_else(|| "Invalid path".to_string())
         .map(|s| s.to_string())
+        .ok_or_else(|| CommandError::new(ErrorCode::InvalidInput, "Invalid path").with_path(&config_dir))
 }
 
 /// Read a configuration file
 #[tauri::command]
-fn read_config_file(app: tauri::AppHandle, file_name: String) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn read_config_file(app: tauri::AppHandle, file_name: String) -> Result<String, CommandError> {
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let file_path = config_dir.join(&file_name);
     
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file {}: {}", file_name, e))
+    let content = fs::read_to_string(&file_path)
+        .context(format!("Failed to read file {}", file_name), &file_path)?;
+    
+    Ok(content)
 }
 
 /// Write a configuration file
 #[tauri::command]
-fn write_config_file(app: tauri::AppHandle, file_name: String, content: String) -> Result<(), String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn write_config_file(app: tauri::AppHandle, file_name: String, content: String) -> Result<(), CommandError> {
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        .context("Failed to create config directory", &config_dir)?;
     
     let file_path = config_dir.join(&file_name);
+    sandbox::check_config_file(&app, &file_path)?;
+    
+    atomic::write_atomic(&file_path, content)
+        .context(format!("Failed to write file {}", file_name), &file_path)?;
     
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write file {}: {}", file_name, e))
+    Ok(())
 }
 
 /// List custom themes in the themes directory
 #[tauri::command]
-fn list_custom_themes(app: tauri::AppHandle) -> Result<Vec<String>, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn list_custom_themes(app: tauri::AppHandle) -> Result<Vec<String>, CommandError> {
+    let config_dir = config_dir(&app)?;
     
     let themes_dir = config_dir.join("themes");
     
@@ -77,10 +108,10 @@ fn list_custom_themes(app: tauri::AppHandle) -> Result<Vec<String>, String> {
     let mut themes = Vec::new();
     
     let entries = fs::read_dir(&themes_dir)
-        .map_err(|e| format!("Failed to read themes directory: {}", e))?;
+        .context("Failed to read themes directory", &themes_dir)?;
     
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let entry = entry.context("Failed to read entry", &themes_dir)?;
         let path = entry.path();
         
         if path.is_dir() {
@@ -95,36 +126,39 @@ fn list_custom_themes(app: tauri::AppHandle) -> Result<Vec<String>, String> {
 
 /// Read a theme manifest file (reads manifest.json)
 #[tauri::command]
-fn read_theme_manifest(app: tauri::AppHandle, theme_name: String) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn read_theme_manifest(app: tauri::AppHandle, theme_name: String) -> Result<String, CommandError> {
+    sandbox::check_name(&theme_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let manifest_path = config_dir
         .join("themes")
         .join(&theme_name)
         .join("manifest.json");
     
-    fs::read_to_string(&manifest_path)
-        .map_err(|e| format!("Failed to read theme manifest for {}: {}", theme_name, e))
+    let manifest = fs::read_to_string(&manifest_path)
+        .context(format!("Failed to read theme manifest for {}", theme_name), &manifest_path)?;
+    
+    Ok(manifest)
 }
 
 /// Read a theme CSS file
 #[tauri::command]
-fn read_theme_css(app: tauri::AppHandle, theme_name: String, css_file: String) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn read_theme_css(app: tauri::AppHandle, theme_name: String, css_file: String) -> Result<String, CommandError> {
+    sandbox::check_name(&theme_name)?;
+    sandbox::check_relative_path(&css_file)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let css_path = config_dir
         .join("themes")
         .join(&theme_name)
         .join(&css_file);
     
-    fs::read_to_string(&css_path)
-        .map_err(|e| format!("Failed to read theme CSS for {} ({}): {}", theme_name, css_file, e))
+    let css = fs::read_to_string(&css_path)
+        .context(format!("Failed to read theme CSS for {} ({})", theme_name, css_file), &css_path)?;
+    
+    Ok(css)
 }
 
 /// Install a community theme file (saves file to themes directory)
@@ -134,38 +168,39 @@ fn install_community_theme_file(
     theme_name: String,
     file_name: String,
     content: String,
-) -> Result<(), String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+) -> Result<(), CommandError> {
+    sandbox::check_name(&theme_name)?;
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let theme_dir = config_dir.join("themes").join(&theme_name);
     
     // Create theme directory if it doesn't exist
     fs::create_dir_all(&theme_dir)
-        .map_err(|e| format!("Failed to create theme directory: {}", e))?;
+        .context("Failed to create theme directory", &theme_dir)?;
     
     let file_path = theme_dir.join(&file_name);
     
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write theme file {}: {}", file_name, e))
+    atomic::write_atomic(&file_path, content)
+        .context(format!("Failed to write theme file {}", file_name), &file_path)?;
+    
+    Ok(())
 }
 
 /// Uninstall a community theme (removes entire theme directory)
 #[tauri::command]
-fn uninstall_community_theme(app: tauri::AppHandle, theme_name: String) -> Result<(), String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn uninstall_community_theme(app: tauri::AppHandle, theme_name: String) -> Result<(), CommandError> {
+    sandbox::check_name(&theme_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let theme_dir = config_dir.join("themes").join(&theme_name);
     
     // Remove theme directory if it exists
     if theme_dir.exists() {
         fs::remove_dir_all(&theme_dir)
-            .map_err(|e| format!("Failed to remove theme directory: {}", e))?;
+            .context("Failed to remove theme directory", &theme_dir)?;
     }
     
     Ok(())
@@ -177,19 +212,21 @@ fn read_theme_file(
     app: tauri::AppHandle,
     theme_name: String,
     file_name: String,
-) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+) -> Result<String, CommandError> {
+    sandbox::check_name(&theme_name)?;
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let file_path = config_dir
         .join("themes")
         .join(&theme_name)
         .join(&file_name);
     
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read theme file {}/{}: {}", theme_name, file_name, e))
+    let content = fs::read_to_string(&file_path)
+        .context(format!("Failed to read theme file {}/{}", theme_name, file_name), &file_path)?;
+    
+    Ok(content)
 }
 
 // ============================================================================
@@ -198,9 +235,13 @@ fn read_theme_file(
 
 /// Ensure a directory exists (create if it doesn't)
 #[tauri::command]
-fn ensure_dir(path: String) -> Result<(), String> {
+fn ensure_dir(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
+    sandbox::check(&app, Path::new(&path))?;
+    
     fs::create_dir_all(&path)
-        .map_err(|e| format!("Failed to create directory {}: {}", path, e))
+        .context("Failed to create directory", Path::new(&path))?;
+    
+    Ok(())
 }
 
 /// Read a file from a community plugin directory
@@ -209,19 +250,21 @@ fn read_plugin_file(
     app: tauri::AppHandle,
     plugin_id: String,
     file_name: String,
-) -> Result<String, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+) -> Result<String, CommandError> {
+    sandbox::check_name(&plugin_id)?;
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let file_path = config_dir
         .join("plugins")
         .join(&plugin_id)
         .join(&file_name);
     
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read plugin file {}/{}: {}", plugin_id, file_name, e))
+    let content = fs::read_to_string(&file_path)
+        .context(format!("Failed to read plugin file {}/{}", plugin_id, file_name), &file_path)?;
+    
+    Ok(content)
 }
 
 /// Write a file to a community plugin directory
@@ -231,38 +274,39 @@ fn write_plugin_file(
     plugin_id: String,
     file_name: String,
     content: String,
-) -> Result<(), String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+) -> Result<(), CommandError> {
+    sandbox::check_name(&plugin_id)?;
+    sandbox::check_relative_path(&file_name)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let plugin_dir = config_dir.join("plugins").join(&plugin_id);
     
     // Create plugin directory if it doesn't exist
     fs::create_dir_all(&plugin_dir)
-        .map_err(|e| format!("Failed to create plugin directory: {}", e))?;
+        .context("Failed to create plugin directory", &plugin_dir)?;
     
     let file_path = plugin_dir.join(&file_name);
     
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write plugin file {}: {}", file_name, e))
+    atomic::write_atomic(&file_path, content)
+        .context(format!("Failed to write plugin file {}", file_name), &file_path)?;
+    
+    Ok(())
 }
 
 /// Delete a community plugin directory
 #[tauri::command]
-fn delete_plugin_dir(app: tauri::AppHandle, plugin_id: String) -> Result<(), String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn delete_plugin_dir(app: tauri::AppHandle, plugin_id: String) -> Result<(), CommandError> {
+    sandbox::check_name(&plugin_id)?;
+    
+    let config_dir = config_dir(&app)?;
     
     let plugin_dir = config_dir.join("plugins").join(&plugin_id);
     
     // Remove plugin directory if it exists
     if plugin_dir.exists() {
         fs::remove_dir_all(&plugin_dir)
-            .map_err(|e| format!("Failed to remove plugin directory: {}", e))?;
+            .context("Failed to remove plugin directory", &plugin_dir)?;
     }
     
     Ok(())
@@ -270,11 +314,8 @@ fn delete_plugin_dir(app: tauri::AppHandle, plugin_id: String) -> Result<(), Str
 
 /// List all installed community plugins (returns directory names)
 #[tauri::command]
-fn list_community_plugins(app: tauri::AppHandle) -> Result<Vec<String>, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+fn list_community_plugins(app: tauri::AppHandle) -> Result<Vec<String>, CommandError> {
+    let config_dir = config_dir(&app)?;
     
     let plugins_dir = config_dir.join("plugins");
     
@@ -286,10 +327,10 @@ fn list_community_plugins(app: tauri::AppHandle) -> Result<Vec<String>, String>
     let mut plugins = Vec::new();
     
     let entries = fs::read_dir(&plugins_dir)
-        .map_err(|e| format!("Failed to read plugins directory: {}", e))?;
+        .context("Failed to read plugins directory", &plugins_dir)?;
     
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let entry = entry.context("Failed to read entry", &plugins_dir)?;
         let path = entry.path();
         
         if path.is_dir() {
@@ -308,7 +349,7 @@ fn list_community_plugins(app: tauri::AppHandle) -> Result<Vec<String>, String>
 
 /// List all available system fonts
 #[tauri::command]
-fn list_system_fonts() -> Result<Vec<String>, String> {
+fn list_system_fonts() -> Result<Vec<String>, CommandError> {
     let source = SystemSource::new();
     
     // Get all font families from the system
@@ -338,49 +379,259 @@ struct FileNode {
     children: Option<Vec<FileNode>>,
     size: Option<u64>,
     modified: Option<u64>,
+    /// The entry is a symbolic link; the other fields describe what it
+    /// points to (or the link itself when it is broken)
+    #[serde(default)]
+    is_symlink: bool,
+    /// Where the link points, as stored in the link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symlink_target: Option<String>,
+    /// Extra properties, listed with `include_details`
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    details: Option<stat::FileDetails>,
 }
 
-/// Read directory structure recursively
-#[tauri::command]
-fn read_directory(path: String, recursive: bool) -> Result<Vec<FileNode>, String> {
-    let dir_path = PathBuf::from(&path);
-    
-    if !dir_path.exists() {
-        return Err(format!("Path does not exist: {}", path));
+/// Directories on the current traversal path, identified by device and
+/// inode (volume and file index on Windows) so symlink loops are detected
+/// however the loop is reached
+struct DirectoryStack {
+    app: tauri::AppHandle,
+    handles: Vec<same_file::Handle>,
+}
+
+impl DirectoryStack {
+    fn new(app: &tauri::AppHandle) -> Self {
+        DirectoryStack {
+            app: app.clone(),
+            handles: Vec::new(),
+        }
     }
     
-    if !dir_path.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
+    /// Enter a directory; `false` if it is already on the path, in which
+    /// case descending into it would loop forever, or if it is a symlink
+    /// to a folder outside the sandbox
+    fn enter(&mut self, dir: &Path) -> Result<bool, CommandError> {
+        if !self.allows(dir) {
+            return Ok(false);
+        }
+        
+        let handle = same_file::Handle::from_path(dir)
+            .context("Failed to read directory", dir)?;
+
+        if self.handles.contains(&handle) {
+            return Ok(false);
+        }
+
+        self.handles.push(handle);
+        Ok(true)
+    }
+
+    fn leave(&mut self) {
+        self.handles.pop();
     }
     
-    read_directory_recursive(&dir_path, recursive)
+    /// Whether an entry met on the way may be followed: anything but a
+    /// symlink pointing outside the sandbox
+    fn allows(&self, path: &Path) -> bool {
+        !path.is_symlink() || sandbox::check(&self.app, path).is_ok()
+    }
 }
 
-fn read_directory_recursive(dir_path: &PathBuf, recursive: bool) -> Result<Vec<FileNode>, String> {
-    let mut nodes = Vec::new();
+/// Options for `read_directory` and `read_directory_page`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadDirectoryOptions {
+    /// Root whose ignore files apply and that patterns are relative to;
+    /// defaults to the directory being read
+    workspace_root: Option<String>,
+    /// Skip entries matched by `.gitignore` / `.inkdownignore`
+    #[serde(default)]
+    respect_ignore_files: bool,
+    /// Extra gitignore-style patterns to skip
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    /// Include dot-files and dot-folders (default: true)
+    include_hidden: Option<bool>,
+    /// Only list files with these extensions; folders are always listed
+    extensions: Option<Vec<String>>,
+    /// Levels to descend when recursive; 1 lists only direct children.
+    /// Folders at the limit are returned without `children`.
+    max_depth: Option<usize>,
+    /// Descend into symlinked folders when recursive (default: true).
+    /// Symlinks are always listed; a link back to a folder that is already
+    /// being read is returned without `children`.
+    follow_symlinks: Option<bool>,
+    /// Add `stat::FileDetails` to every entry; notes are read to count
+    /// their lines and words
+    #[serde(default)]
+    include_details: bool,
+}
+
+/// One page of a folder listing
+#[derive(Debug, Serialize)]
+struct DirectoryPage {
+    entries: Vec<FileNode>,
+    /// Number of entries in the folder after filtering
+    total: usize,
+    has_more: bool,
+}
+
+/// An entry that passed the filters, before its metadata is read
+struct ListedEntry {
+    name: String,
+    path: PathBuf,
+    is_directory: bool,
+    is_symlink: bool,
+}
+
+/// Applies `ReadDirectoryOptions` while listing folders
+struct DirectoryReader {
+    root: PathBuf,
+    rules: ignore::IgnoreRules,
+    extensions: Option<Vec<String>>,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    include_details: bool,
+}
+
+impl DirectoryReader {
+    fn new(path: &Path, options: ReadDirectoryOptions) -> Self {
+        let root = options
+            .workspace_root
+            .map(PathBuf::from)
+            .filter(|root| path.starts_with(root))
+            .unwrap_or_else(|| path.to_path_buf());
+        
+        let rules = if options.respect_ignore_files {
+            ignore::IgnoreRules::load(&root, &options.ignore_patterns)
+        } else {
+            ignore::IgnoreRules::from_patterns(&options.ignore_patterns)
+        };
+        
+        DirectoryReader {
+            root,
+            rules: rules.include_hidden(options.include_hidden.unwrap_or(true)),
+            extensions: options
+                .extensions
+                .map(|extensions| extensions.iter().map(|e| e.trim_start_matches('.').to_lowercase()).collect()),
+            max_depth: options.max_depth,
+            follow_symlinks: options.follow_symlinks.unwrap_or(true),
+            include_details: options.include_details,
+        }
+    }
     
-    let entries = fs::read_dir(dir_path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+    /// List `dir_path`, which must already be on `stack`
+    fn read(
+        &self,
+        dir_path: &Path,
+        recursive: bool,
+        depth: usize,
+        stack: &mut DirectoryStack,
+    ) -> Result<Vec<FileNode>, CommandError> {
+        self.list(dir_path)?
+            .into_iter()
+            .map(|entry| self.node(entry, recursive, depth, stack))
+            .collect()
+    }
     
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
-        let metadata = entry.metadata()
-            .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    /// Names of the entries in `dir_path` that pass the filters, sorted
+    /// with folders first and then by name
+    ///
+    /// Only file types are read here, plus the target of each symlink, so
+    /// a page of a large folder can be picked before anything is stat-ed.
+    fn list(&self, dir_path: &Path) -> Result<Vec<ListedEntry>, CommandError> {
+        let mut listed = Vec::new();
         
-        let name = path.file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| "Invalid file name".to_string())?
-            .to_string();
+        let entries = fs::read_dir(dir_path)
+            .context("Failed to read directory", dir_path)?;
+        
+        for entry in entries {
+            let entry = entry.context("Failed to read entry", dir_path)?;
+            
+            let path = entry.path();
+            let file_type = entry.file_type()
+                .context("Failed to read metadata", &path)?;
+            let is_symlink = file_type.is_symlink();
+            
+            // A symlink lists as what it points to; a broken one as a file
+            let is_directory = if is_symlink {
+                fs::metadata(&path).is_ok_and(|metadata| metadata.is_dir())
+            } else {
+                file_type.is_dir()
+            };
+            
+            // Besides ignored entries, this skips `.git` and the workspace
+            // trash, which is managed through the trash commands
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+            if self.rules.is_ignored(relative, is_directory) {
+                continue;
+            }
+            
+            if !is_directory && !self.has_allowed_extension(&path) {
+                continue;
+            }
+            
+            let name = path.file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| "Invalid file name".to_string())?
+                .to_string();
+            
+            listed.push(ListedEntry {
+                name,
+                path,
+                is_directory,
+                is_symlink,
+            });
+        }
+        
+        // Sort: directories first, then by name
+        listed.sort_by(|a, b| {
+            match (a.is_directory, b.is_directory) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            }
+        });
+        
+        Ok(listed)
+    }
+    
+    /// Read the metadata, details and children of a listed entry
+    fn node(
+        &self,
+        entry: ListedEntry,
+        recursive: bool,
+        depth: usize,
+        stack: &mut DirectoryStack,
+    ) -> Result<FileNode, CommandError> {
+        let ListedEntry { name, path, is_directory, is_symlink } = entry;
+        
+        // Describe what a symlink points to, or the link itself if it is broken
+        let metadata = if is_symlink {
+            fs::metadata(&path).or_else(|_| fs::symlink_metadata(&path))
+        } else {
+            fs::metadata(&path)
+        }
+        .context("Failed to read metadata", &path)?;
         
         let path_str = path.to_str()
             .ok_or_else(|| "Invalid path".to_string())?
             .to_string();
         
-        let is_directory = metadata.is_dir();
+        let symlink_target = if is_symlink {
+            fs::read_link(&path).ok().map(|target| target.to_string_lossy().to_string())
+        } else {
+            None
+        };
         
-        let children = if is_directory && recursive {
-            Some(read_directory_recursive(&path, recursive)?)
+        let descend = is_directory
+            && recursive
+            && self.max_depth.is_none_or(|max| depth < max)
+            && (!is_symlink || self.follow_symlinks);
+        let children = if descend && stack.enter(&path)? {
+            let children = self.read(&path, recursive, depth + 1, stack);
+            stack.leave();
+            Some(children?)
         } else {
             None
         };
@@ -396,179 +647,519 @@ fn read_directory_recursive(dir_path: &PathBuf, recursive: bool) -> Result<Vec<F
             .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
             .map(|d| d.as_secs());
         
-        nodes.push(FileNode {
+        Ok(FileNode {
             name,
             path: path_str,
             is_directory,
             children,
             size,
             modified,
-        });
+            is_symlink,
+            symlink_target,
+            details: self.include_details.then(|| stat::details(&path, &metadata)),
+        })
     }
     
-    // Sort: directories first, then by name
-    nodes.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
+    fn has_allowed_extension(&self, path: &Path) -> bool {
+        let Some(extensions) = &self.extensions else {
+            return true;
+        };
+        
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| extensions.iter().any(|allowed| e.eq_ignore_ascii_case(allowed)))
+    }
+}
+
+fn check_directory(dir_path: &Path) -> Result<(), CommandError> {
+    if !dir_path.exists() {
+        return Err(CommandError::not_found(dir_path));
+    }
+    
+    if !dir_path.is_dir() {
+        return Err(CommandError::not_a_directory(dir_path));
+    }
+    
+    Ok(())
+}
+
+/// Read directory structure recursively
+#[tauri::command]
+fn read_directory(
+    app: tauri::AppHandle,
+    path: String,
+    recursive: bool,
+    options: Option<ReadDirectoryOptions>,
+) -> Result<Vec<FileNode>, CommandError> {
+    let dir_path = PathBuf::from(&path);
+    sandbox::check(&app, &dir_path)?;
+    check_directory(&dir_path)?;
+    
+    let mut stack = DirectoryStack::new(&app);
+    stack.enter(&dir_path)?;
+    
+    let nodes = DirectoryReader::new(&dir_path, options.unwrap_or_default())
+        .read(&dir_path, recursive, 1, &mut stack)?;
     
     Ok(nodes)
 }
 
-/// Read file content
+/// Read one page of a folder's direct children, for lazily loading large
+/// folders; folders in the page are returned without `children`
+#[tauri::command]
+fn read_directory_page(
+    app: tauri::AppHandle,
+    path: String,
+    offset: usize,
+    limit: usize,
+    options: Option<ReadDirectoryOptions>,
+) -> Result<DirectoryPage, CommandError> {
+    let dir_path = PathBuf::from(&path);
+    sandbox::check(&app, &dir_path)?;
+    check_directory(&dir_path)?;
+    
+    let mut stack = DirectoryStack::new(&app);
+    stack.enter(&dir_path)?;
+    
+    // Only the entries in the page are stat-ed and, with
+    // `include_details`, read
+    let reader = DirectoryReader::new(&dir_path, options.unwrap_or_default());
+    let listed = reader.list(&dir_path)?;
+    let total = listed.len();
+    let entries = listed
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|entry| reader.node(entry, false, 1, &mut stack))
+        .collect::<Result<Vec<FileNode>, CommandError>>()?;
+    
+    Ok(DirectoryPage {
+        has_more: offset + entries.len() < total,
+        entries,
+        total,
+    })
+}
+
+/// Let the backend indexes know files changed on disk, so searches and
+/// link queries reflect the change right away
+fn refresh_indexes(app: &tauri::AppHandle, updated: Vec<PathBuf>, removed: Vec<PathBuf>) {
+    links::refresh(app, &updated, &removed);
+    search::refresh(app, updated, removed);
+}
+
+/// On-disk state of a file, used as the baseline for conflict checks
+#[derive(Debug, Clone, Serialize)]
+struct FileMetadata {
+    size: u64,
+    /// Milliseconds since the Unix epoch
+    modified: u64,
+    /// Hex-encoded SHA-256 of the file content
+    hash: String,
+}
+
+/// File content together with the metadata it was read at and the way it
+/// is stored, so it can be written back unchanged
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadFileResult {
+    /// Decoded content, without the byte order mark
+    content: String,
+    metadata: FileMetadata,
+    encoding: encoding::TextEncoding,
+    bom: bool,
+    line_ending: encoding::LineEnding,
+}
+
+/// Hex SHA-256 of file content, as used for conflict checks
+fn hash_bytes(bytes: &[u8]) -> String {
+    hashing::hash_bytes(bytes, hashing::HashAlgorithm::Sha256)
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> u64 {
+    metadata.modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Current time in milliseconds since the Unix epoch
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Read the current metadata of a file, `None` if it doesn't exist
+fn read_file_metadata(path: &Path) -> Result<Option<FileMetadata>, CommandError> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(CommandError::io("Failed to read metadata", path, e)),
+    };
+
+    let bytes = fs::read(path)
+        .context("Failed to read file", path)?;
+
+    Ok(Some(FileMetadata {
+        size: bytes.len() as u64,
+        modified: modified_millis(&metadata),
+        hash: hash_bytes(&bytes),
+    }))
+}
+
+/// Read file content along with its metadata
+///
+/// UTF-8, UTF-16 and Windows-1252 files are decoded; see `encoding`.
+/// Files larger than `max_size` (default `chunked::DEFAULT_MAX_READ_SIZE`)
+/// are refused with `TooLarge`; read those with `read_file_range`.
 #[tauri::command]
-fn read_file(path: String) -> Result<String, String> {
+fn read_file(app: tauri::AppHandle, path: String, max_size: Option<u64>) -> Result<ReadFileResult, CommandError> {
     let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
     
     if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(CommandError::not_found(&file_path));
     }
     
     if !file_path.is_file() {
-        return Err(format!("Path is not a file: {}", path));
+        return Err(CommandError::not_a_file(&file_path));
     }
     
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+    let metadata = fs::metadata(&file_path)
+        .context("Failed to read metadata", &file_path)?;
+    
+    let limit = max_size.unwrap_or(chunked::DEFAULT_MAX_READ_SIZE);
+    if metadata.len() > limit {
+        return Err(CommandError::too_large(&file_path, metadata.len(), limit));
+    }
+    
+    let bytes = fs::read(&file_path)
+        .context("Failed to read file", &file_path)?;
+    
+    let metadata = FileMetadata {
+        size: bytes.len() as u64,
+        modified: modified_millis(&metadata),
+        hash: hash_bytes(&bytes),
+    };
+    
+    let (content, format) = encoding::decode(&bytes);
+    
+    Ok(ReadFileResult {
+        content,
+        metadata,
+        encoding: format.encoding,
+        bom: format.bom,
+        line_ending: format.line_ending,
+    })
 }
 
 /// Read binary file content (returns base64 encoded)
 #[tauri::command]
-fn read_file_binary(path: String) -> Result<String, String> {
+fn read_file_binary(app: tauri::AppHandle, path: String) -> Result<String, CommandError> {
     use base64::{Engine as _, engine::general_purpose};
     
     let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
     
     if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(CommandError::not_found(&file_path));
     }
     
     if !file_path.is_file() {
-        return Err(format!("Path is not a file: {}", path));
+        return Err(CommandError::not_a_file(&file_path));
     }
     
     let bytes = fs::read(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .context("Failed to read file", &file_path)?;
     
     Ok(general_purpose::STANDARD.encode(&bytes))
 }
 
 /// Write file content
+///
+/// When `expected_modified` or `expected_hash` is given, the write only goes
+/// through if the file on disk still matches; otherwise a `conflict` error
+/// carrying the current metadata is returned. Returns the metadata of the
+/// written file so the caller can use it as the next baseline.
+///
+/// Unset fields of `format` default to the way the existing file is stored,
+/// so saving a file never changes bytes the user didn't edit; new files are
+/// UTF-8 with LF line endings.
 #[tauri::command]
-fn write_file(path: String, content: String) -> Result<(), String> {
+fn write_file(
+    app: tauri::AppHandle,
+    path: String,
+    content: String,
+    expected_modified: Option<u64>,
+    expected_hash: Option<String>,
+    format: Option<encoding::TextFormatOptions>,
+) -> Result<FileMetadata, CommandError> {
     let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
+    
+    if expected_modified.is_some() || expected_hash.is_some() {
+        let current = read_file_metadata(&file_path)?;
+        
+        let unchanged = current.as_ref().is_some_and(|current| {
+            expected_modified.is_none_or(|modified| modified == current.modified)
+                && expected_hash.as_ref().is_none_or(|hash| hash.eq_ignore_ascii_case(&current.hash))
+        });
+        
+        if !unchanged {
+            return Err(CommandError::new(
+                ErrorCode::Conflict,
+                format!("File changed on disk since it was read: {}", path),
+            )
+            .with_path(&file_path)
+            .with_details(serde_json::json!({ "current": current })));
+        }
+    }
+    
+    let bytes = encode_for_write(&file_path, &content, format.unwrap_or_default())?;
+    
+    // Keep the content being replaced in the version history
+    history::snapshot(&app, &file_path);
     
     // Create parent directories if they don't exist
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+            .context("Failed to create parent directories", parent)?;
     }
     
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+    atomic::write_atomic(&file_path, &bytes)
+        .context("Failed to write file", &file_path)?;
+    
+    let metadata = fs::metadata(&file_path)
+        .context("Failed to read metadata", &file_path)?;
+    
+    refresh_indexes(&app, vec![file_path], Vec::new());
+    
+    Ok(FileMetadata {
+        size: bytes.len() as u64,
+        modified: modified_millis(&metadata),
+        hash: hash_bytes(&bytes),
+    })
+}
+
+/// Encode content for `write_file`, filling in whatever wasn't requested
+/// from the format of the file being replaced
+///
+/// If the existing file is Windows-1252 and the new content has characters
+/// it can't hold, the file is saved as UTF-8 rather than failing the save;
+/// an explicitly requested encoding is never changed.
+fn encode_for_write(
+    path: &Path,
+    content: &str,
+    options: encoding::TextFormatOptions,
+) -> Result<Vec<u8>, CommandError> {
+    use encoding::{TextEncoding, TextFormat};
+    
+    let encoding::TextFormatOptions { encoding, bom, line_ending } = options;
+    let existing = if encoding.is_none() || bom.is_none() || line_ending.is_none() {
+        fs::read(path).ok().map(|bytes| encoding::decode(&bytes).1)
+    } else {
+        None
+    };
+    let existing = existing.unwrap_or_default();
+    
+    let target_encoding = encoding.unwrap_or(existing.encoding);
+    let format = TextFormat {
+        encoding: target_encoding,
+        bom: bom.unwrap_or(if target_encoding == existing.encoding {
+            existing.bom
+        } else {
+            matches!(target_encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be)
+        }),
+        line_ending: line_ending.unwrap_or(existing.line_ending),
+    };
+    
+    match encoding::encode(content, format) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) if encoding.is_none() => Ok(encoding::encode(
+            content,
+            TextFormat {
+                encoding: TextEncoding::Utf8,
+                bom: false,
+                ..format
+            },
+        )?),
+        Err(message) => Err(CommandError::new(ErrorCode::Encoding, message).with_path(path)),
+    }
 }
 
 /// Write binary file content (base64 encoded)
 #[tauri::command]
-fn write_file_binary(path: String, data: String) -> Result<(), String> {
+fn write_file_binary(app: tauri::AppHandle, path: String, data: String) -> Result<(), CommandError> {
     use base64::{Engine as _, engine::general_purpose};
     
     let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
     
     // Create parent directories if they don't exist
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+            .context("Failed to create parent directories", parent)?;
     }
     
     // Decode base64 data
     let bytes = general_purpose::STANDARD
         .decode(&data)
-        .map_err(|e| format!("Failed to decode base64 data: {}", e))?;
+        .map_err(|e| {
+            CommandError::new(ErrorCode::InvalidInput, format!("Failed to decode base64 data: {}", e))
+        })?;
+    
+    atomic::write_atomic(&file_path, bytes)
+        .context("Failed to write binary file", &file_path)?;
     
-    fs::write(&file_path, bytes)
-        .map_err(|e| format!("Failed to write binary file: {}", e))
+    refresh_indexes(&app, vec![file_path], Vec::new());
+    Ok(())
 }
 
 /// Create a new file
 #[tauri::command]
-fn create_file(path: String) -> Result<(), String> {
+fn create_file(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
     let file_path = PathBuf::from(&path);
+    sandbox::check(&app, &file_path)?;
     
     if file_path.exists() {
-        return Err(format!("File already exists: {}", path));
+        return Err(CommandError::already_exists(&file_path));
     }
     
     // Create parent directories if they don't exist
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+            .context("Failed to create parent directories", parent)?;
     }
     
     fs::write(&file_path, "")
-        .map_err(|e| format!("Failed to create file: {}", e))
+        .context("Failed to create file", &file_path)?;
+    
+    refresh_indexes(&app, vec![file_path], Vec::new());
+    Ok(())
 }
 
 /// Create a new directory
 #[tauri::command]
-fn create_directory(path: String) -> Result<(), String> {
+fn create_directory(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
     let dir_path = PathBuf::from(&path);
+    sandbox::check(&app, &dir_path)?;
     
     if dir_path.exists() {
-        return Err(format!("Directory already exists: {}", path));
+        return Err(CommandError::already_exists(&dir_path));
     }
     
     fs::create_dir_all(&dir_path)
-        .map_err(|e| format!("Failed to create directory: {}", e))
+        .context("Failed to create directory", &dir_path)?;
+    
+    Ok(())
 }
 
 /// Rename a file or directory
+///
+/// With `update_links` and a `workspace_root`, links to the renamed item
+/// across the workspace are rewritten; see `rename_with_links`.
 #[tauri::command]
-fn rename_path(old_path: String, new_path: String) -> Result<(), String> {
+fn rename_path(
+    app: tauri::AppHandle,
+    old_path: String,
+    new_path: String,
+    workspace_root: Option<String>,
+    update_links: Option<bool>,
+) -> Result<links::LinkRewriteReport, CommandError> {
     let old = PathBuf::from(&old_path);
     let new = PathBuf::from(&new_path);
+    sandbox::check_link(&app, &old)?;
+    sandbox::check_link(&app, &new)?;
+    if let Some(root) = &workspace_root {
+        sandbox::check_workspace(&app, Path::new(root))?;
+    }
     
-    if !old.exists() {
-        return Err(format!("Source path does not exist: {}", old_path));
+    if !transfer::exists(&old) {
+        return Err(CommandError::not_found(&old));
     }
     
-    if new.exists() {
-        return Err(format!("Destination path already exists: {}", new_path));
+    if transfer::exists(&new) {
+        return Err(CommandError::already_exists(&new));
     }
     
-    fs::rename(&old, &new)
-        .map_err(|e| format!("Failed to rename: {}", e))
+    rename_with_links(&app, &old, &new, workspace_root, update_links, false)
 }
 
 /// Delete a file or directory
+///
+/// When `workspace_root` is given, the item is moved to the workspace trash
+/// instead, unless `permanent` is set or it already is in the trash.
 #[tauri::command]
-fn delete_path(path: String) -> Result<(), String> {
+fn delete_path(
+    app: tauri::AppHandle,
+    path: String,
+    workspace_root: Option<String>,
+    permanent: Option<bool>,
+) -> Result<(), CommandError> {
     let file_path = PathBuf::from(&path);
+    sandbox::check_link(&app, &file_path)?;
+    if let Some(root) = &workspace_root {
+        sandbox::check_workspace(&app, Path::new(root))?;
+    }
     
-    if !file_path.exists() {
-        return Err(format!("Path does not exist: {}", path));
+    if !transfer::exists(&file_path) {
+        return Err(CommandError::not_found(&file_path));
     }
     
-    if file_path.is_dir() {
+    let trash_root = workspace_root
+        .map(PathBuf::from)
+        .filter(|root| !permanent.unwrap_or(false) && !trash::is_in_trash(root, &file_path));
+    
+    if let Some(root) = trash_root {
+        trash::move_to_trash(&root, &file_path)?;
+    } else if file_path.is_dir() && !file_path.is_symlink() {
         fs::remove_dir_all(&file_path)
-            .map_err(|e| format!("Failed to delete directory: {}", e))
+            .context("Failed to delete directory", &file_path)?;
     } else {
         fs::remove_file(&file_path)
-            .map_err(|e| format!("Failed to delete file: {}", e))
+            .context("Failed to delete file", &file_path)?;
     }
+    
+    refresh_indexes(&app, Vec::new(), vec![file_path]);
+    Ok(())
+}
+
+/// Where a moved item ended up, with the notes whose links were rewritten
+#[derive(Debug, Default, Serialize)]
+struct MoveReport {
+    /// `None` if the move was skipped because the destination exists
+    path: Option<String>,
+    #[serde(flatten)]
+    links: links::LinkRewriteReport,
 }
 
 /// Move a file or directory
+///
+/// With `update_links` and a `workspace_root`, links to the moved item
+/// across the workspace are rewritten; see `rename_with_links`. An existing
+/// destination is handled as `on_conflict` says (default: error); see
+/// `transfer::OnConflict`. Links aren't rewritten when folders are merged.
 #[tauri::command]
-fn move_path(source: String, destination: String) -> Result<(), String> {
+fn move_path(
+    app: tauri::AppHandle,
+    source: String,
+    destination: String,
+    workspace_root: Option<String>,
+    update_links: Option<bool>,
+    on_conflict: Option<transfer::OnConflict>,
+) -> Result<MoveReport, CommandError> {
     let src = PathBuf::from(&source);
     let dest = PathBuf::from(&destination);
+    sandbox::check_link(&app, &src)?;
+    sandbox::check(&app, &dest)?;
+    if let Some(root) = &workspace_root {
+        sandbox::check_workspace(&app, Path::new(root))?;
+    }
     
-    if !src.exists() {
-        return Err(format!("Source path does not exist: {}", source));
+    if !transfer::exists(&src) {
+        return Err(CommandError::not_found(&src));
     }
     
     // If destination is a directory, move source into it
@@ -580,78 +1171,125 @@ fn move_path(source: String, destination: String) -> Result<(), String> {
         dest
     };
     
-    if final_dest.exists() {
-        return Err(format!("Destination already exists: {}", final_dest.display()));
-    }
+    let (target, merge) = match transfer::resolve(&src, &final_dest, on_conflict.unwrap_or(transfer::OnConflict::Error))? {
+        transfer::Target::Skip => return Ok(MoveReport::default()),
+        transfer::Target::Free(target) => (target, false),
+        transfer::Target::Merge(target) => (target, true),
+        transfer::Target::Replace(target) => {
+            remove_replaced(workspace_root.as_deref(), &target)?;
+            (target, false)
+        }
+    };
     
     // Create parent directories if they don't exist
-    if let Some(parent) = final_dest.parent() {
+    if let Some(parent) = target.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+            .context("Failed to create parent directories", parent)?;
+    }
+    
+    let links = rename_with_links(&app, &src, &target, workspace_root, update_links, merge)?;
+    
+    Ok(MoveReport {
+        path: Some(target.to_string_lossy().to_string()),
+        links,
+    })
+}
+
+/// Get an item replaced by a move or copy out of the way: into the trash
+/// when it is inside `workspace_root`, otherwise deleted
+fn remove_replaced(workspace_root: Option<&str>, path: &Path) -> Result<(), CommandError> {
+    match workspace_root.map(Path::new) {
+        Some(root) if path.starts_with(root) && !trash::is_in_trash(root, path) => {
+            trash::move_to_trash(root, path).map(|_| ())
+        }
+        _ => transfer::remove_item(path),
     }
+}
+
+/// Rename `from` to `to`, optionally rewriting links in the workspace
+///
+/// When links are updated, every wikilink, embed and relative markdown link
+/// pointing at the item (or into it, for a folder) gets the new location,
+/// and relative links inside moved notes are re-based. The report lists the
+/// changed notes with their previous content so the UI can undo it. If the
+/// notes can't be rewritten, they are restored and the item moved back.
+///
+/// Links aren't updated for a merge: the files it renames to avoid
+/// conflicts would leave links pointing at the wrong notes.
+fn rename_with_links(
+    app: &tauri::AppHandle,
+    from: &Path,
+    to: &Path,
+    workspace_root: Option<String>,
+    update_links: Option<bool>,
+    merge: bool,
+) -> Result<links::LinkRewriteReport, CommandError> {
+    // Links have to be resolved against the layout before the move
+    let plan = match workspace_root {
+        Some(root) if update_links.unwrap_or(false) && !merge => Some(links::plan_move(app, Path::new(&root), from, to)?),
+        _ => None,
+    };
+    
+    if merge {
+        transfer::merge_into(app, from, to)?;
+    } else {
+        transfer::move_item(app, from, to)?;
+    }
+    history::rename(app, from, to);
+    
+    let report = match plan.map(links::apply_move) {
+        Some(Ok(report)) => report,
+        None => links::LinkRewriteReport::default(),
+        Some(Err(error)) => {
+            // The notes were already restored; put the item back too so the
+            // rename doesn't end up half done
+            if transfer::move_item(app, to, from).is_ok() {
+                history::rename(app, to, from);
+            } else {
+                refresh_indexes(app, vec![to.to_path_buf()], vec![from.to_path_buf()]);
+            }
+            return Err(error.into());
+        }
+    };
+    
+    let mut updated = vec![to.to_path_buf()];
+    updated.extend(report.paths());
+    refresh_indexes(app, updated, vec![from.to_path_buf()]);
     
-    fs::rename(&src, &final_dest)
-        .map_err(|e| format!("Failed to move: {}", e))
+    Ok(report)
 }
 
-/// Copy a file to a new location
+/// Copy a file or directory to a new location
+///
+/// An existing destination is handled as `on_conflict` says (default:
+/// auto-rename to "name (copy).ext"); see `transfer::OnConflict`. Returns
+/// the path of the copy, or `None` if it was skipped.
 #[tauri::command]
-fn copy_file(source: String, destination: String) -> Result<(), String> {
+fn copy_file(
+    app: tauri::AppHandle,
+    source: String,
+    destination: String,
+    workspace_root: Option<String>,
+    on_conflict: Option<transfer::OnConflict>,
+) -> Result<Option<String>, CommandError> {
     let src = PathBuf::from(&source);
     let dest = PathBuf::from(&destination);
+    sandbox::check(&app, &src)?;
+    sandbox::check(&app, &dest)?;
+    if let Some(root) = &workspace_root {
+        sandbox::check_workspace(&app, Path::new(root))?;
+    }
     
     if !src.exists() {
-        return Err(format!("Source file does not exist: {}", source));
+        return Err(CommandError::not_found(&src));
     }
     
-    // Check if source is a directory
-    if src.is_dir() {
-        // If destination is a directory, copy into it with same name
-        let final_dest = if dest.is_dir() {
-            let file_name = src.file_name()
-                .ok_or_else(|| "Invalid source directory name".to_string())?;
-            dest.join(file_name)
-        } else {
-            dest
-        };
-
-        // If destination exists, add (copy) or (copy N) suffix
-        let mut target_path = final_dest.clone();
-        if target_path.exists() {
-            let file_stem = target_path.file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| "Invalid directory name".to_string())?
-                .to_string();
-            // Directories usually don't have extensions we care about for renaming, but let's keep logic consistent
-            let extension = target_path.extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("")
-                .to_string();
-            
-            let parent = target_path.parent()
-                .ok_or_else(|| "Invalid parent directory".to_string())?
-                .to_path_buf();
-            
-            let ext_suffix = if extension.is_empty() { String::new() } else { format!(".{}", extension) };
-            target_path = parent.join(format!("{} (copy){}", file_stem, ext_suffix));
-            
-            let mut counter = 2;
-            while target_path.exists() {
-                target_path = parent.join(format!("{} (copy {}){}", file_stem, counter, ext_suffix));
-                counter += 1;
-                if counter > 1000 {
-                    return Err("Too many copies already exist".to_string());
-                }
-            }
-        }
-
-        // Perform recursive copy
-        copy_dir_recursive(&src, &target_path)?;
-        return Ok(());
-    }
-
-    if !src.is_file() {
-        return Err(format!("Source is not a file or directory: {}", source));
+    if !src.is_dir() && !src.is_file() {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Source is not a file or directory: {}", source),
+        )
+        .with_path(&src));
     }
     
     // If destination is a directory, copy into it with same name
@@ -663,74 +1301,42 @@ fn copy_file(source: String, destination: String) -> Result<(), String> {
         dest
     };
     
-    // If destination exists, add (copy) or (copy N) suffix
-    let mut target_path = final_dest.clone();
-    if target_path.exists() {
-        let file_stem = target_path.file_stem()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| "Invalid file name".to_string())?
-            .to_string();
-        let extension = target_path.extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_string();
-        
-        let parent = target_path.parent()
-            .ok_or_else(|| "Invalid parent directory".to_string())?
-            .to_path_buf();
-        
-        // Try "file (copy).ext" first
-        let ext_suffix = if extension.is_empty() { String::new() } else { format!(".{}", extension) };
-        target_path = parent.join(format!("{} (copy){}", file_stem, ext_suffix));
-        
-        // If that exists, try "file (copy 2).ext", "file (copy 3).ext", etc.
-        let mut counter = 2;
-        while target_path.exists() {
-            target_path = parent.join(format!("{} (copy {}){}", file_stem, counter, ext_suffix));
-            counter += 1;
-            
-            // Safety limit to prevent infinite loop
-            if counter > 1000 {
-                return Err("Too many copies already exist".to_string());
-            }
+    let target = match transfer::resolve(&src, &final_dest, on_conflict.unwrap_or(transfer::OnConflict::AutoRename))? {
+        transfer::Target::Skip => return Ok(None),
+        transfer::Target::Free(target) | transfer::Target::Merge(target) => target,
+        transfer::Target::Replace(target) => {
+            remove_replaced(workspace_root.as_deref(), &target)?;
+            target
         }
-    }
+    };
     
     // Create parent directories if they don't exist
-    if let Some(parent) = target_path.parent() {
+    if let Some(parent) = target.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+            .context("Failed to create parent directories", parent)?;
     }
     
-    fs::copy(&src, &target_path)
-        .map_err(|e| format!("Failed to copy file: {}", e))?;
+    // A merge copies into the existing directory; files already there are
+    // kept and the copies renamed
+    transfer::copy_item(&app, &src, &target)?;
     
-    Ok(())
-}
-
-fn copy_dir_recursive(src: &PathBuf, dest: &PathBuf) -> Result<(), String> {
-    fs::create_dir_all(dest)
-        .map_err(|e| format!("Failed to create directory {}: {}", dest.display(), e))?;
-
-    for entry in fs::read_dir(src).map_err(|e| format!("Failed to read directory {}: {}", src.display(), e))? {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let entry_path = entry.path();
-        let dest_path = dest.join(entry.file_name());
-
-        if entry_path.is_dir() {
-            copy_dir_recursive(&entry_path, &dest_path)?;
-        } else {
-            fs::copy(&entry_path, &dest_path)
-                .map_err(|e| format!("Failed to copy file {}: {}", entry_path.display(), e))?;
-        }
-    }
-    Ok(())
+    refresh_indexes(&app, vec![target.clone()], Vec::new());
+    Ok(Some(target.to_string_lossy().to_string()))
 }
 
 /// Check if path exists
+///
+/// Paths outside the sandbox are reported as missing, except folders picked
+/// in the folder dialog so recent workspaces can be checked before opening.
 #[tauri::command]
-fn path_exists(path: String) -> bool {
-    PathBuf::from(&path).exists()
+fn path_exists(app: tauri::AppHandle, path: String) -> bool {
+    let path = PathBuf::from(&path);
+    
+    if sandbox::check(&app, &path).is_err() && !sandbox::is_known_folder(&app, &path) {
+        return false;
+    }
+    
+    path.exists()
 }
 
 // ============================================================================
@@ -759,7 +1365,7 @@ struct FileFilter {
 
 /// Open a save file dialog - returns the selected file path or null if cancelled
 #[tauri::command]
-async fn show_save_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, String> {
+async fn show_save_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, CommandError> {
     use tauri_plugin_dialog::DialogExt;
     
     let mut dialog = app.dialog().file();
@@ -786,14 +1392,19 @@ async fn show_save_dialog(app: tauri::AppHandle, options: FileDialogOptions) ->
     let result = dialog.blocking_save_file();
     
     match result {
-        Some(path) => Ok(Some(path.to_string())),
+        Some(path) => {
+            if let Some(path) = path.as_path() {
+                sandbox::grant_file(&app, path);
+            }
+            Ok(Some(path.to_string()))
+        }
         None => Ok(None),
     }
 }
 
 /// Open a file selection dialog - returns the selected file path or null if cancelled
 #[tauri::command]
-async fn show_open_file_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, String> {
+async fn show_open_file_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, CommandError> {
     use tauri_plugin_dialog::DialogExt;
     
     let mut dialog = app.dialog().file();
@@ -816,14 +1427,19 @@ async fn show_open_file_dialog(app: tauri::AppHandle, options: FileDialogOptions
     let result = dialog.blocking_pick_file();
     
     match result {
-        Some(path) => Ok(Some(path.to_string())),
+        Some(path) => {
+            if let Some(path) = path.as_path() {
+                sandbox::grant_file(&app, path);
+            }
+            Ok(Some(path.to_string()))
+        }
         None => Ok(None),
     }
 }
 
 /// Open multiple file selection dialog - returns array of selected file paths
 #[tauri::command]
-async fn show_open_files_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Vec<String>, String> {
+async fn show_open_files_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Vec<String>, CommandError> {
     use tauri_plugin_dialog::DialogExt;
     
     let mut dialog = app.dialog().file();
@@ -846,14 +1462,19 @@ async fn show_open_files_dialog(app: tauri::AppHandle, options: FileDialogOption
     let result = dialog.blocking_pick_files();
     
     match result {
-        Some(paths) => Ok(paths.iter().map(|p| p.to_string()).collect()),
+        Some(paths) => {
+            for path in paths.iter().filter_map(|p| p.as_path()) {
+                sandbox::grant_file(&app, path);
+            }
+            Ok(paths.iter().map(|p| p.to_string()).collect())
+        }
         None => Ok(vec![]),
     }
 }
 
 /// Open a folder selection dialog - returns the selected folder path or null if cancelled
 #[tauri::command]
-async fn show_open_folder_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, String> {
+async fn show_open_folder_dialog(app: tauri::AppHandle, options: FileDialogOptions) -> Result<Option<String>, CommandError> {
     use tauri_plugin_dialog::DialogExt;
     
     let mut dialog = app.dialog().file();
@@ -869,7 +1490,13 @@ async fn show_open_folder_dialog(app: tauri::AppHandle, options: FileDialogOptio
     let result = dialog.blocking_pick_folder();
     
     match result {
-        Some(path) => Ok(Some(path.to_string())),
+        Some(path) => {
+            // A picked folder may be opened as a workspace from now on
+            if let Some(path) = path.as_path() {
+                sandbox::grant_folder(&app, path);
+            }
+            Ok(Some(path.to_string()))
+        }
         None => Ok(None),
     }
 }
@@ -925,9 +1552,21 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_process::init())
+        .manage(watcher::WatcherState::default())
+        .manage(search::SearchState::default())
+        .manage(grep::GrepState::default())
+        .manage(links::LinkState::default())
+        .manage(sandbox::SandboxState::default())
+        .manage(history::HistoryState::default())
+        .manage(chunked::StreamState::default())
+        .manage(hashing::HashState::default())
+        .register_asynchronous_uri_scheme_protocol(asset::SCHEME, |ctx, request, responder| {
+            asset::handle(ctx.app_handle(), request, responder)
+        })
         .setup(|app| {
+            sandbox::init(app.handle());
             // Apply window configuration on startup
-            apply_window_config(&app.handle());
+            apply_window_config(app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -950,8 +1589,12 @@ pub fn run() {
             list_community_plugins,
             // File system operations
             read_directory,
+            read_directory_page,
             read_file,
             read_file_binary,
+            chunked::read_file_range,
+            chunked::stream_file,
+            chunked::cancel_file_stream,
             write_file,
             write_file_binary,
             create_file,
@@ -961,12 +1604,59 @@ pub fn run() {
             move_path,
             copy_file,
             path_exists,
+            stat::stat_path,
+            hashing::hash_file,
+            hashing::hash_files,
+            stats::workspace_stats,
+            duplicates::find_duplicates,
+            duplicates::merge_duplicates,
+            orphans::find_orphan_attachments,
+            batch::apply_file_operations,
+            ignore::read_ignore_patterns,
+            // Workspace sandbox
+            sandbox::open_workspace,
+            sandbox::close_workspace,
+            // Trash operations
+            trash::list_trash,
+            trash::restore_from_trash,
+            trash::purge_trash,
+            trash::move_paths_to_trash,
+            // Workspace watcher
+            watcher::watch_workspace,
+            watcher::unwatch_workspace,
+            // Full-text search
+            search::index_workspace,
+            search::search_workspace,
+            grep::grep_workspace,
+            grep::cancel_grep,
+            // Link index
+            links::get_outgoing_links,
+            links::get_backlinks,
+            links::get_unresolved_links,
+            // Templates
+            templates::list_templates,
+            templates::template_prompts,
+            templates::create_from_template,
+            // Thumbnails
+            thumbnail::get_thumbnail,
+            // Version history
+            history::list_versions,
+            history::get_version,
+            history::diff_version,
+            history::restore_version,
+            history::prune_history,
             // Dialog operations
             show_save_dialog,
             show_open_file_dialog,
             show_open_files_dialog,
             show_open_folder_dialog
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Write cache changes still waiting for their delayed save
+            if let tauri::RunEvent::Exit = event {
+                hashing::flush(app);
+            }
+        });
 }
@@ -0,0 +1,740 @@
+// ============================================================================
+// LINK INDEX
+// ============================================================================
+//
+// In-memory graph of the wikilinks, embeds and relative markdown links of
+// every note in a workspace. Built on first use, kept current by the file
+// commands and the watcher through `refresh`, and queried for outgoing
+// links, backlinks and unresolved links.
+//
+// Links are stored as written and resolved at query time, so creating,
+// renaming or deleting a file immediately changes what links point to.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::atomic;
+use crate::encoding;
+use crate::error::CommandError;
+use crate::ignore::{self, to_slash_path, IgnoreRules};
+use crate::markdown::{self, LinkKind, ParsedLink};
+use crate::sandbox;
+use crate::search::utf16_len;
+
+/// Link indexes keyed by workspace root
+#[derive(Default)]
+pub struct LinkState {
+    indexes: Mutex<HashMap<PathBuf, LinkIndex>>,
+}
+
+/// Position in a file, shaped like `Position` in MetadataCache
+///
+/// Columns and offsets are in UTF-16 code units, as in JavaScript strings.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Position {
+    start: Location,
+    end: Location,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Location {
+    line: usize,
+    col: usize,
+    offset: usize,
+}
+
+/// A link as returned to the frontend, shaped like `LinkCache` plus the
+/// file it is in and the file it resolves to
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkEntry {
+    /// Note containing the link
+    source_path: String,
+    /// File the link resolves to, `None` if it doesn't resolve
+    target_path: Option<String>,
+    /// Link target as written, without subpath
+    link: String,
+    /// The full link text
+    original: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subpath: Option<String>,
+    kind: LinkKind,
+    embed: bool,
+    position: Position,
+}
+
+/// Characters escaped in rewritten markdown link destinations
+const LINK_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'(')
+    .add(b')')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?');
+
+/// A note whose links were rewritten after a rename or move
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewrittenFile {
+    /// Where the note is now
+    path: String,
+    /// Content before the rewrite, so the change can be undone
+    previous_content: String,
+    /// Number of links rewritten
+    links: usize,
+}
+
+/// Notes changed by a rename or move with link updating
+#[derive(Debug, Default, Serialize)]
+pub struct LinkRewriteReport {
+    files: Vec<RewrittenFile>,
+}
+
+impl LinkRewriteReport {
+    /// Paths of the rewritten notes
+    pub fn paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.files.iter().map(|file| PathBuf::from(&file.path))
+    }
+
+    /// Add the notes of a later rewrite, in order
+    pub fn append(&mut self, other: LinkRewriteReport) {
+        self.files.extend(other.files);
+    }
+
+    /// Put the rewritten notes back the way they were, as far as possible
+    pub fn revert(&self) {
+        for file in self.files.iter().rev() {
+            if let Err(e) = write_note(Path::new(&file.path), &file.previous_content) {
+                eprintln!("[links] Failed to restore {}: {}", file.path, e);
+            }
+        }
+    }
+}
+
+/// Link edits to make once a move has happened, worked out beforehand
+/// because links can only be resolved against the old layout
+pub struct MovePlan {
+    from: PathBuf,
+    to: PathBuf,
+    edits: Vec<FileEdits>,
+}
+
+struct FileEdits {
+    /// The note's path before the move
+    source: PathBuf,
+    content: String,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+/// A parsed link together with what's needed to report it
+struct StoredLink {
+    link: ParsedLink,
+    original: String,
+    position: Position,
+}
+
+struct LinkIndex {
+    root: PathBuf,
+    /// Every file in the workspace keyed by lowercase `/`-separated relative path
+    files: HashMap<String, PathBuf>,
+    /// Files keyed by lowercase file name, and notes also by lowercase stem
+    by_name: HashMap<String, Vec<PathBuf>>,
+    /// Links of each note
+    links: HashMap<PathBuf, Vec<StoredLink>>,
+}
+
+/// Links from a note to other files
+#[tauri::command]
+pub async fn get_outgoing_links(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+) -> Result<Vec<LinkEntry>, CommandError> {
+    with_index(app, workspace_root, move |index| {
+        let source = PathBuf::from(&path);
+        Ok(index
+            .links
+            .get(&source)
+            .map(|links| links.iter().map(|link| index.to_entry(&source, link)).collect())
+            .unwrap_or_default())
+    })
+    .await
+}
+
+/// Links from other notes to a file
+#[tauri::command]
+pub async fn get_backlinks(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    path: String,
+) -> Result<Vec<LinkEntry>, CommandError> {
+    with_index(app, workspace_root, move |index| {
+        let target = PathBuf::from(&path);
+        Ok(index.entries(|entry| entry.target_path.as_deref() == target.to_str()))
+    })
+    .await
+}
+
+/// Links anywhere in the workspace that don't resolve to a file
+#[tauri::command]
+pub async fn get_unresolved_links(
+    app: tauri::AppHandle,
+    workspace_root: String,
+) -> Result<Vec<LinkEntry>, CommandError> {
+    with_index(app, workspace_root, |index| Ok(index.entries(|entry| entry.target_path.is_none()))).await
+}
+
+/// Update open link indexes after files changed on disk
+///
+/// `updated` paths are (re)read, directories recursively; `removed` paths
+/// are dropped along with everything under them. Paths outside any open
+/// index are ignored.
+pub fn refresh(app: &tauri::AppHandle, updated: &[PathBuf], removed: &[PathBuf]) {
+    let Some(state) = app.try_state::<LinkState>() else {
+        return;
+    };
+    let Ok(mut indexes) = state.indexes.lock() else {
+        return;
+    };
+
+    for (root, index) in indexes.iter_mut() {
+        // Different ignore rules can change any part of the graph
+        if updated.iter().chain(removed).any(|p| ignore::is_ignore_file(root, p)) {
+            *index = LinkIndex::build(root);
+            continue;
+        }
+
+        for path in removed.iter().filter(|p| p.starts_with(root)) {
+            index.remove(path);
+        }
+        for path in updated.iter().filter(|p| p.starts_with(root)) {
+            index.add(path);
+        }
+    }
+}
+
+/// Every file in `workspace_root` that some note links to or embeds
+pub fn linked_files(app: &tauri::AppHandle, workspace_root: &Path) -> Result<HashSet<PathBuf>, String> {
+    let state = app.state::<LinkState>();
+    let mut indexes = state
+        .indexes
+        .lock()
+        .map_err(|_| "Link state is poisoned".to_string())?;
+
+    let index = indexes
+        .entry(workspace_root.to_path_buf())
+        .or_insert_with(|| LinkIndex::build(workspace_root));
+
+    Ok(index
+        .links
+        .iter()
+        .flat_map(|(source, stored)| stored.iter().filter_map(|s| index.resolve(source, &s.link)))
+        .cloned()
+        .collect())
+}
+
+/// Work out how links in `workspace_root` must change when `from` (a file
+/// or directory) moves to `to`: links pointing into `from` get the new
+/// location, and relative links inside moved notes are re-based.
+pub fn plan_move(app: &tauri::AppHandle, workspace_root: &Path, from: &Path, to: &Path) -> Result<MovePlan, String> {
+    let state = app.state::<LinkState>();
+    let mut indexes = state
+        .indexes
+        .lock()
+        .map_err(|_| "Link state is poisoned".to_string())?;
+
+    let index = indexes
+        .entry(workspace_root.to_path_buf())
+        .or_insert_with(|| LinkIndex::build(workspace_root));
+
+    Ok(index.plan_move(from, to))
+}
+
+/// Work out how links in `workspace_root` must change so that links to
+/// `from` point at `to`, an existing file, instead; used before `from` is
+/// removed as a duplicate of `to`. Links inside `from` are left alone.
+pub fn plan_retarget(app: &tauri::AppHandle, workspace_root: &Path, from: &Path, to: &Path) -> Result<MovePlan, String> {
+    let mut plan = plan_move(app, workspace_root, from, to)?;
+    plan.edits.retain(|edit| !edit.source.starts_with(from));
+    Ok(plan)
+}
+
+/// Rewrite the notes of a plan once the move is done
+///
+/// Returns the notes that were changed, with their previous content. If a
+/// note can't be written, the notes already changed are restored.
+pub fn apply_move(plan: MovePlan) -> Result<LinkRewriteReport, String> {
+    let mut report = LinkRewriteReport::default();
+
+    for edit in plan.edits {
+        let path = moved_path(&edit.source, &plan.from, &plan.to);
+
+        let mut content = edit.content.clone();
+        let mut replacements = edit.replacements;
+        replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, text) in &replacements {
+            content.replace_range(range.clone(), text);
+        }
+
+        if let Err(e) = write_note(&path, &content) {
+            report.revert();
+            return Err(format!("Failed to update links in {}: {}", path.display(), e));
+        }
+
+        report.files.push(RewrittenFile {
+            path: path.to_string_lossy().to_string(),
+            previous_content: edit.content,
+            links: replacements.len(),
+        });
+    }
+
+    Ok(report)
+}
+
+/// Write a note's new content in the encoding, BOM and line endings it
+/// already has on disk
+fn write_note(path: &Path, content: &str) -> Result<(), String> {
+    let bytes = crate::encode_for_write(path, content, Default::default()).map_err(|e| e.to_string())?;
+    atomic::write_atomic(path, bytes).map_err(|e| e.to_string())
+}
+
+/// Where `path` ends up when `from` moves to `to`
+fn moved_path(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) if rest.as_os_str().is_empty() => to.to_path_buf(),
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Run `f` against a workspace's index on a blocking thread, building the
+/// index first if this is the first query for that workspace
+async fn with_index<T, F>(app: tauri::AppHandle, workspace_root: String, f: F) -> Result<T, CommandError>
+where
+    T: Send + 'static,
+    F: FnOnce(&LinkIndex) -> Result<T, String> + Send + 'static,
+{
+    sandbox::check_workspace(&app, Path::new(&workspace_root))?;
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&workspace_root);
+        let state = app.state::<LinkState>();
+        let mut indexes = state
+            .indexes
+            .lock()
+            .map_err(|_| "Link state is poisoned".to_string())?;
+
+        if !indexes.contains_key(&root) {
+            if !root.is_dir() {
+                return Err(format!("Path is not a directory: {}", workspace_root));
+            }
+            indexes.insert(root.clone(), LinkIndex::build(&root));
+        }
+
+        f(&indexes[&root])
+    })
+    .await
+    .map_err(|e| format!("Link index task failed: {}", e))??;
+
+    Ok(result)
+}
+
+impl LinkIndex {
+    fn build(root: &Path) -> Self {
+        let mut index = LinkIndex {
+            root: root.to_path_buf(),
+            files: HashMap::new(),
+            by_name: HashMap::new(),
+            links: HashMap::new(),
+        };
+
+        for file in IgnoreRules::load(root, &[]).collect_files(root) {
+            index.add_file(file);
+        }
+
+        index
+    }
+
+    /// Add or re-read a file, or every file under a directory
+    fn add(&mut self, path: &Path) {
+        self.remove(path);
+
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+
+        let rules = IgnoreRules::load(&self.root, &[]);
+        if rules.is_path_ignored(relative, path.is_dir()) {
+            return;
+        }
+
+        if path.is_dir() {
+            for file in rules.collect_files_in(&self.root, path) {
+                self.add_file(file);
+            }
+        } else if path.is_file() {
+            self.add_file(path.to_path_buf());
+        }
+    }
+
+    fn add_file(&mut self, path: PathBuf) {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+
+        if markdown::is_note(&path) {
+            if let Ok(bytes) = fs::read(&path) {
+                self.links.insert(path.clone(), parse(&encoding::decode(&bytes).0));
+            }
+        }
+
+        for key in name_keys(&path) {
+            self.by_name.entry(key).or_default().push(path.clone());
+        }
+        self.files.insert(to_slash_path(relative).to_lowercase(), path);
+    }
+
+    /// Drop a file, or everything under a directory
+    fn remove(&mut self, path: &Path) {
+        self.files.retain(|_, file| !file.starts_with(path));
+        self.links.retain(|file, _| !file.starts_with(path));
+        self.by_name.retain(|_, files| {
+            files.retain(|file| !file.starts_with(path));
+            !files.is_empty()
+        });
+    }
+
+    /// Every link in the workspace matching `filter`, grouped by source
+    fn entries(&self, filter: impl Fn(&LinkEntry) -> bool) -> Vec<LinkEntry> {
+        let mut sources: Vec<&PathBuf> = self.links.keys().collect();
+        sources.sort();
+
+        sources
+            .into_iter()
+            .flat_map(|source| {
+                self.links[source]
+                    .iter()
+                    .map(move |link| self.to_entry(source, link))
+            })
+            .filter(|entry| filter(entry))
+            .collect()
+    }
+
+    fn to_entry(&self, source: &Path, stored: &StoredLink) -> LinkEntry {
+        let link = &stored.link;
+
+        LinkEntry {
+            source_path: source.to_string_lossy().to_string(),
+            target_path: self
+                .resolve(source, link)
+                .map(|target| target.to_string_lossy().to_string()),
+            link: link.target.clone(),
+            original: stored.original.clone(),
+            display_text: link.display.clone(),
+            subpath: link.subpath.clone(),
+            kind: link.kind,
+            embed: link.embed,
+            position: stored.position,
+        }
+    }
+
+    /// File a link points to, if it exists
+    fn resolve(&self, source: &Path, link: &ParsedLink) -> Option<&PathBuf> {
+        match link.kind {
+            LinkKind::Wikilink => self.resolve_wikilink(source, &link.target),
+            LinkKind::Markdown => self.resolve_relative(source, &link.target),
+        }
+    }
+
+    /// Resolve `[[target]]` like Obsidian: a path relative to the workspace
+    /// root or the source folder, else a file whose name or name without
+    /// `.md` matches. Ambiguous names prefer the source folder, then the
+    /// shortest path.
+    fn resolve_wikilink(&self, source: &Path, target: &str) -> Option<&PathBuf> {
+        let target = target.trim_start_matches('/');
+        let source_dir = source.parent().unwrap_or(&self.root);
+
+        if target.contains('/') {
+            let found = [self.root.join(target), source_dir.join(target)]
+                .iter()
+                .find_map(|candidate| self.lookup_with_note_extension(candidate));
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        let name = target.rsplit('/').next().unwrap_or(target).to_lowercase();
+        let suffix = format!("/{}", target.to_lowercase());
+
+        let candidates = self.by_name.get(&name)?.iter().filter(|file| {
+            // A partial path like `folder/note` must match the end of the path
+            !target.contains('/') || {
+                let relative = self.relative_key(file);
+                let without_extension = relative.rsplit_once('.').map(|(stem, _)| stem);
+                format!("/{}", relative).ends_with(&suffix)
+                    || without_extension.is_some_and(|stem| format!("/{}", stem).ends_with(&suffix))
+            }
+        });
+
+        candidates.min_by_key(|file| {
+            let same_folder = file.parent() == Some(source_dir);
+            (!same_folder, file.components().count(), file.as_path())
+        })
+    }
+
+    /// Resolve a markdown link relative to the source folder, or to the
+    /// workspace root when it starts with `/`
+    fn resolve_relative(&self, source: &Path, target: &str) -> Option<&PathBuf> {
+        let source_dir = source.parent().unwrap_or(&self.root);
+
+        let candidate = match target.strip_prefix('/') {
+            Some(rooted) => self.root.join(rooted),
+            None => source_dir.join(target),
+        };
+
+        self.lookup_with_note_extension(&candidate)
+    }
+
+    /// Find a file by path, also trying it with `.md` appended
+    fn lookup_with_note_extension(&self, candidate: &Path) -> Option<&PathBuf> {
+        let normalized = normalize(candidate)?;
+        let relative = normalized.strip_prefix(&self.root).ok()?;
+        let key = to_slash_path(relative).to_lowercase();
+
+        self.files
+            .get(&key)
+            .or_else(|| self.files.get(&format!("{}.md", key)))
+    }
+
+    /// Link edits needed when `from` moves to `to`, see [`plan_move`]
+    fn plan_move(&self, from: &Path, to: &Path) -> MovePlan {
+        let mut edits = Vec::new();
+
+        for (source, stored) in &self.links {
+            let affected = source.starts_with(from)
+                || stored
+                    .iter()
+                    .any(|s| self.resolve(source, &s.link).is_some_and(|target| target.starts_with(from)));
+            if !affected {
+                continue;
+            }
+
+            // Re-read rather than trusting the index, so ranges match the disk
+            let Ok(bytes) = fs::read(source) else {
+                continue;
+            };
+            let (content, _) = encoding::decode(&bytes);
+
+            let new_source = moved_path(source, from, to);
+            let mut replacements = Vec::new();
+
+            for link in markdown::parse_links(&content) {
+                let Some(target) = self.resolve(source, &link) else {
+                    continue;
+                };
+
+                let new_target = moved_path(target, from, to);
+                let source_moved = new_source != *source;
+
+                // Only relative markdown links depend on where the source is
+                let rebase = source_moved && link.kind == LinkKind::Markdown && !link.target.starts_with('/');
+                if new_target == *target && !rebase {
+                    continue;
+                }
+
+                let raw = &content[link.target_range.clone()];
+                let replacement = self.link_text(&link, raw, &content, &new_source, &new_target, from);
+                if replacement != raw {
+                    replacements.push((link.target_range.clone(), replacement));
+                }
+            }
+
+            if !replacements.is_empty() {
+                edits.push(FileEdits {
+                    source: source.clone(),
+                    content,
+                    replacements,
+                });
+            }
+        }
+
+        MovePlan {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+            edits,
+        }
+    }
+
+    /// New target text for a link whose source and/or target moved,
+    /// keeping the style it was written in
+    fn link_text(
+        &self,
+        link: &ParsedLink,
+        raw: &str,
+        content: &str,
+        source: &Path,
+        target: &Path,
+        moved_from: &Path,
+    ) -> String {
+        // Links written without `.md` stay without it
+        let keep_extension = !markdown::is_note(target) || markdown::is_note(Path::new(&link.target));
+        let strip_extension = |path: String| match path.rsplit_once('.') {
+            Some((stem, _)) if !keep_extension => stem.to_string(),
+            _ => path,
+        };
+
+        match link.kind {
+            LinkKind::Wikilink => {
+                let name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+                // Linked by name: keep it that way unless another file
+                // already goes by the new name
+                if !raw.contains('/') {
+                    let ambiguous = name_keys(target).iter().any(|key| {
+                        self.by_name.get(key).is_some_and(|files| {
+                            files.iter().any(|file| !file.starts_with(moved_from) && file != target)
+                        })
+                    });
+                    if !ambiguous {
+                        return strip_extension(name);
+                    }
+                }
+
+                let relative = target.strip_prefix(&self.root).unwrap_or(target);
+                strip_extension(to_slash_path(relative))
+            }
+            LinkKind::Markdown => {
+                let path = if raw.starts_with('/') {
+                    let relative = target.strip_prefix(&self.root).unwrap_or(target);
+                    format!("/{}", to_slash_path(relative))
+                } else {
+                    let source_dir = source.parent().unwrap_or(&self.root);
+                    let relative = pathdiff::diff_paths(target, source_dir).unwrap_or_else(|| target.to_path_buf());
+                    to_slash_path(&relative)
+                };
+                let path = strip_extension(path);
+
+                // `<...>` destinations take spaces as-is
+                let in_angle_brackets = content[..link.target_range.start].ends_with('<');
+                if in_angle_brackets {
+                    path
+                } else {
+                    utf8_percent_encode(&path, LINK_ENCODE_SET).to_string()
+                }
+            }
+        }
+    }
+
+    fn relative_key(&self, file: &Path) -> String {
+        to_slash_path(file.strip_prefix(&self.root).unwrap_or(file)).to_lowercase()
+    }
+}
+
+/// Lowercase names a file can be linked by: its file name, and for notes
+/// also the name without extension
+fn name_keys(path: &Path) -> Vec<String> {
+    let mut keys = Vec::new();
+
+    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+        keys.push(name.to_lowercase());
+    }
+    if markdown::is_note(path) {
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            keys.push(stem.to_lowercase());
+        }
+    }
+
+    keys
+}
+
+/// Resolve `.` and `..` without touching the disk
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    Some(normalized)
+}
+
+/// Parse a note's links along with their text and positions
+fn parse(content: &str) -> Vec<StoredLink> {
+    let mut locator = Locator::new(content);
+
+    markdown::parse_links(content)
+        .into_iter()
+        .map(|link| StoredLink {
+            original: content[link.range.clone()].to_string(),
+            position: Position {
+                start: locator.locate(link.range.start),
+                end: locator.locate(link.range.end),
+            },
+            link,
+        })
+        .collect()
+}
+
+/// Converts byte offsets to UTF-16 line/column/offset positions, walking
+/// forward through the content as offsets increase
+struct Locator<'a> {
+    content: &'a str,
+    byte: usize,
+    line: usize,
+    line_start: usize,
+    offset: usize,
+}
+
+impl<'a> Locator<'a> {
+    fn new(content: &'a str) -> Self {
+        Locator {
+            content,
+            byte: 0,
+            line: 0,
+            line_start: 0,
+            offset: 0,
+        }
+    }
+
+    fn locate(&mut self, byte: usize) -> Location {
+        if byte < self.byte {
+            *self = Locator::new(self.content);
+        }
+
+        let skipped = &self.content[self.byte..byte];
+        for (i, c) in skipped.char_indices() {
+            if c == '\n' {
+                self.line += 1;
+                self.line_start = self.byte + i + 1;
+            }
+        }
+        self.offset += utf16_len(skipped);
+        self.byte = byte;
+
+        Location {
+            line: self.line,
+            col: utf16_len(&self.content[self.line_start..byte]),
+            offset: self.offset,
+        }
+    }
+}
@@ -0,0 +1,327 @@
+// ============================================================================
+// MARKDOWN HELPERS
+// ============================================================================
+//
+// Lightweight, allocation-friendly parsing of the bits of a note the backend
+// cares about (frontmatter fields, tags, title). This is deliberately not a
+// full YAML/CommonMark parser; it mirrors what `utils/frontmatter.ts` and
+// `MetadataCache` do in packages/core.
+
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use serde::Serialize;
+
+/// File extensions treated as notes
+pub const NOTE_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Whether a file name or path has a note extension
+pub fn is_note(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| NOTE_EXTENSIONS.iter().any(|n| e.eq_ignore_ascii_case(n)))
+        .unwrap_or(false)
+}
+
+/// Split a note into its frontmatter block (without the `---` fences) and body
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let rest = match content.strip_prefix("---") {
+        Some(rest) => rest,
+        None => return (None, content),
+    };
+
+    let rest = match rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n')) {
+        Some(rest) => rest,
+        None => return (None, content),
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let frontmatter = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return (Some(frontmatter), body);
+        }
+        offset += line.len();
+    }
+
+    (None, content)
+}
+
+/// Read a scalar frontmatter value, with surrounding quotes removed
+pub fn frontmatter_value(frontmatter: &str, key: &str) -> Option<String> {
+    frontmatter.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+        let value = unquote(value);
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Read a frontmatter list, written either inline (`key: [a, b]`, `key: a, b`)
+/// or as a block of `- item` lines
+pub fn frontmatter_list(frontmatter: &str, key: &str) -> Vec<String> {
+    let mut lines = frontmatter.lines();
+    let mut items = Vec::new();
+
+    while let Some(line) = lines.next() {
+        let Some(value) = line.strip_prefix(key).and_then(|v| v.strip_prefix(':')) else {
+            continue;
+        };
+
+        let value = value.trim();
+        if !value.is_empty() {
+            let value = value.trim_start_matches('[').trim_end_matches(']');
+            items.extend(
+                value
+                    .split(',')
+                    .map(|item| unquote(item.trim()).to_string())
+                    .filter(|item| !item.is_empty()),
+            );
+            break;
+        }
+
+        for line in lines.by_ref() {
+            match line.trim_start().strip_prefix("- ") {
+                Some(item) => {
+                    let item = unquote(item.trim());
+                    if !item.is_empty() {
+                        items.push(item.to_string());
+                    }
+                }
+                None => break,
+            }
+        }
+        break;
+    }
+
+    items
+}
+
+/// Collect tags from frontmatter (`tags:`) and inline `#tags` in the body,
+/// lowercased, without the leading `#`, deduplicated in order of appearance
+pub fn extract_tags(content: &str) -> Vec<String> {
+    static INLINE_TAG: OnceLock<Regex> = OnceLock::new();
+    let inline_tag = INLINE_TAG.get_or_init(|| {
+        // A tag needs at least one non-digit so "#1" or "#2024" aren't tags
+        Regex::new(r"(?:^|\s)#([\p{L}\p{N}_/-]*[\p{L}_/-][\p{L}\p{N}_/-]*)").unwrap()
+    });
+
+    let (frontmatter, body) = split_frontmatter(content);
+    let mut tags: Vec<String> = Vec::new();
+
+    let mut push = |tag: &str| {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    };
+
+    if let Some(frontmatter) = frontmatter {
+        for tag in frontmatter_list(frontmatter, "tags") {
+            push(&tag);
+        }
+    }
+
+    for line in prose_lines(body) {
+        for capture in inline_tag.captures_iter(line) {
+            push(&capture[1]);
+        }
+    }
+
+    tags
+}
+
+/// Title of a note: frontmatter `title`, else the first `# ` heading,
+/// else `fallback` (usually the file name without extension)
+pub fn extract_title(content: &str, fallback: &str) -> String {
+    let (frontmatter, body) = split_frontmatter(content);
+
+    if let Some(title) = frontmatter.and_then(|fm| frontmatter_value(fm, "title")) {
+        return title;
+    }
+
+    prose_lines(body)
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Lines of a note body outside fenced code blocks
+pub fn prose_lines(body: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+
+    body.lines().filter(move |line| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            return false;
+        }
+        !in_fence
+    })
+}
+
+/// Kind of link syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// `[[target#subpath|display]]`
+    Wikilink,
+    /// `[display](target#subpath)`
+    Markdown,
+}
+
+/// A link to another file found in a note
+#[derive(Debug, Clone)]
+pub struct ParsedLink {
+    pub kind: LinkKind,
+    /// `![[...]]` or `![...](...)`
+    pub embed: bool,
+    /// Link target without subpath, percent-decoded for markdown links
+    pub target: String,
+    /// Heading or block reference after `#`
+    pub subpath: Option<String>,
+    pub display: Option<String>,
+    /// Byte range of the whole link in the content
+    pub range: Range<usize>,
+    /// Byte range of the target as written (before decoding, without subpath)
+    pub target_range: Range<usize>,
+}
+
+/// Find wikilinks, embeds and relative markdown links outside frontmatter,
+/// code blocks and inline code, in order of appearance
+///
+/// External links (`https:`, `mailto:`...) and same-file anchors are skipped.
+pub fn parse_links(content: &str) -> Vec<ParsedLink> {
+    static WIKILINK: OnceLock<Regex> = OnceLock::new();
+    static MARKDOWN_LINK: OnceLock<Regex> = OnceLock::new();
+    static INLINE_CODE: OnceLock<Regex> = OnceLock::new();
+
+    let wikilink = WIKILINK.get_or_init(|| Regex::new(r"(!?)\[\[([^\[\]\n]+?)\]\]").unwrap());
+    let markdown_link = MARKDOWN_LINK.get_or_init(|| {
+        Regex::new(r#"(!?)\[([^\]\n]*)\]\(\s*(<[^>\n]+>|[^)\s]+)(?:\s+"[^"\n]*")?\s*\)"#).unwrap()
+    });
+    let inline_code = INLINE_CODE.get_or_init(|| Regex::new(r"`[^`\n]+`").unwrap());
+
+    let (_, body) = split_frontmatter(content);
+    let mut offset = content.len() - body.len();
+    let mut in_fence = false;
+    let mut links = Vec::new();
+
+    for line in body.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let code: Vec<Range<usize>> = inline_code.find_iter(line).map(|m| m.range()).collect();
+        let in_code = |range: &Range<usize>| code.iter().any(|c| c.start < range.end && range.start < c.end);
+
+        for captures in wikilink.captures_iter(line) {
+            let whole = captures.get(0).unwrap();
+            let inner = captures.get(2).unwrap();
+            if in_code(&whole.range()) {
+                continue;
+            }
+
+            let (reference, display) = match inner.as_str().split_once('|') {
+                Some((reference, display)) => (reference, Some(display.trim().to_string())),
+                None => (inner.as_str(), None),
+            };
+            // A `|` escaped for use inside a table leaves a trailing backslash
+            let reference = reference.strip_suffix('\\').unwrap_or(reference);
+            let (target, subpath) = split_subpath(reference);
+
+            let trimmed = target.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let target_start = line_start + inner.start() + (target.len() - target.trim_start().len());
+            links.push(ParsedLink {
+                kind: LinkKind::Wikilink,
+                embed: !captures[1].is_empty(),
+                target: trimmed.to_string(),
+                subpath,
+                display,
+                range: line_start + whole.start()..line_start + whole.end(),
+                target_range: target_start..target_start + trimmed.len(),
+            });
+        }
+
+        for captures in markdown_link.captures_iter(line) {
+            let whole = captures.get(0).unwrap();
+            let destination = captures.get(3).unwrap();
+            if in_code(&whole.range()) {
+                continue;
+            }
+
+            let (raw, raw_start) = match destination
+                .as_str()
+                .strip_prefix('<')
+                .and_then(|d| d.strip_suffix('>'))
+            {
+                Some(inner) => (inner, destination.start() + 1),
+                None => (destination.as_str(), destination.start()),
+            };
+
+            let (target, subpath) = split_subpath(raw);
+            if target.is_empty() || has_scheme(target) {
+                continue;
+            }
+
+            let display = &captures[2];
+            let target_start = line_start + raw_start;
+            links.push(ParsedLink {
+                kind: LinkKind::Markdown,
+                embed: !captures[1].is_empty(),
+                target: percent_decode_str(target).decode_utf8_lossy().to_string(),
+                subpath,
+                display: (!display.is_empty()).then(|| display.to_string()),
+                range: line_start + whole.start()..line_start + whole.end(),
+                target_range: target_start..target_start + target.len(),
+            });
+        }
+    }
+
+    links.sort_by_key(|link| link.range.start);
+    links
+}
+
+/// Split `target#subpath` at the first `#`
+fn split_subpath(reference: &str) -> (&str, Option<String>) {
+    match reference.split_once('#') {
+        Some((target, subpath)) => (target, Some(subpath.to_string())),
+        None => (reference, None),
+    }
+}
+
+/// Whether a link destination is a URL (`https:`, `mailto:`, `obsidian:`...)
+/// rather than a path; a single letter followed by `:` is a Windows drive
+fn has_scheme(target: &str) -> bool {
+    match target.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+        }
+        None => false,
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
@@ -0,0 +1,126 @@
+// ============================================================================
+// ORPHANED ATTACHMENTS
+// ============================================================================
+//
+// Attachments that no note links to or embeds any more, e.g. images pasted
+// into a note that was later deleted. Every file that isn't a note is
+// checked against the links of the link index, so wikilinks, embeds and
+// relative markdown links all count as references. Files only mentioned
+// elsewhere (frontmatter, plugin data) look orphaned, so the results are
+// meant to be reviewed before they go to the trash with
+// `move_paths_to_trash`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CommandError, ErrorCode};
+use crate::ignore::{self, IgnoreRules};
+use crate::stat::{self, FileKind};
+use crate::{links, markdown, sandbox};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanOptions {
+    /// Extra gitignore-style patterns on top of the workspace's ignore files
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    /// Only look for attachments inside this folder, e.g. the attachments
+    /// folder; notes everywhere in the workspace still count as references
+    folder: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanAttachment {
+    path: String,
+    kind: FileKind,
+    size: u64,
+    /// Milliseconds since the Unix epoch
+    modified: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanReport {
+    /// Largest first
+    orphans: Vec<OrphanAttachment>,
+    total_bytes: u64,
+    /// Attachments checked, orphaned or not
+    attachments_scanned: usize,
+}
+
+/// Attachments in a workspace that no note links to or embeds
+#[tauri::command]
+pub async fn find_orphan_attachments(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    options: Option<OrphanOptions>,
+) -> Result<OrphanReport, CommandError> {
+    let options = options.unwrap_or_default();
+
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    if !root.is_dir() {
+        return Err(CommandError::not_a_directory(&root));
+    }
+
+    let folder = match &options.folder {
+        Some(folder) => {
+            let folder = PathBuf::from(folder);
+            if !folder.starts_with(&root) {
+                return Err(CommandError::new(
+                    ErrorCode::InvalidInput,
+                    format!("Folder is not inside the workspace: {}", folder.display()),
+                )
+                .with_path(&folder));
+            }
+            if !folder.is_dir() {
+                return Err(CommandError::not_a_directory(&folder));
+            }
+            folder
+        }
+        None => root.clone(),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || find(&app, &root, &folder, &options))
+        .await
+        .map_err(|e| format!("Orphan search failed: {}", e))?
+}
+
+fn find(
+    app: &tauri::AppHandle,
+    root: &Path,
+    folder: &Path,
+    options: &OrphanOptions,
+) -> Result<OrphanReport, CommandError> {
+    let linked = links::linked_files(app, root)?;
+
+    let attachments: Vec<PathBuf> = IgnoreRules::load(root, &options.ignore_patterns)
+        .collect_files_in(root, folder)
+        .into_iter()
+        .filter(|path| !markdown::is_note(path) && !ignore::is_ignore_file(root, path))
+        .collect();
+
+    let mut orphans: Vec<OrphanAttachment> = attachments
+        .iter()
+        .filter(|path| !linked.contains(*path))
+        .filter_map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            Some(OrphanAttachment {
+                path: path.to_string_lossy().to_string(),
+                kind: stat::kind(path, false),
+                size: metadata.len(),
+                modified: crate::modified_millis(&metadata),
+            })
+        })
+        .collect();
+    orphans.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(OrphanReport {
+        total_bytes: orphans.iter().map(|orphan| orphan.size).sum(),
+        attachments_scanned: attachments.len(),
+        orphans,
+    })
+}
@@ -0,0 +1,309 @@
+// ============================================================================
+// PATH SANDBOX
+// ============================================================================
+//
+// File commands may only touch paths inside an opened workspace, the themes
+// and plugins folders in the app config directory, or files the user picked
+// in one of the native dialogs. Every incoming path is canonicalized before the check so
+// `..` and symlinks can't step outside; for a path that doesn't exist yet,
+// its closest existing ancestor is canonicalized instead. Commands that act
+// on a symlink itself only canonicalize its parent.
+//
+// A folder can only become a workspace after the user picked it in the
+// folder dialog. Picked folders are remembered in `workspaces.json` in the
+// app data directory. File commands can't reach it, and the config file
+// commands refuse it for platforms where the config and data directories
+// are the same, so `open_workspace` can reopen them on later launches but
+// can't be used to grant access to arbitrary paths. Nothing else grants a
+// folder: the workspaces listed in the frontend's `app.json` are writable
+// from the webview, so they have to be picked again once.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::Manager;
+
+use crate::atomic;
+use crate::error::CommandError;
+
+/// Folders picked in the folder dialog, in the app data directory
+const KNOWN_FOLDERS_FILE: &str = "workspaces.json";
+
+#[derive(Default)]
+pub struct SandboxState {
+    sandbox: Mutex<Sandbox>,
+}
+
+#[derive(Default)]
+struct Sandbox {
+    /// Themes and plugins folders in the app config directory
+    app_dirs: Vec<PathBuf>,
+    /// Workspaces opened with `open_workspace`
+    workspaces: Vec<PathBuf>,
+    /// Folders picked in the folder dialog, which may be opened as workspaces
+    known_folders: Vec<PathBuf>,
+    /// Files picked in a file dialog during this session
+    picked_files: Vec<PathBuf>,
+}
+
+/// Register the app folders and the remembered workspace folders
+pub fn init(app: &tauri::AppHandle) {
+    let app_dirs = app
+        .path()
+        .app_config_dir()
+        .map(|config_dir| vec![config_dir.join("themes"), config_dir.join("plugins")])
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|dir| {
+            let _ = fs::create_dir_all(&dir);
+            dir.canonicalize().ok()
+        })
+        .collect();
+
+    let known_folders = load_known_folders(app);
+
+    if let Ok(mut sandbox) = lock(app) {
+        sandbox.app_dirs = app_dirs;
+        sandbox.known_folders = known_folders;
+    }
+}
+
+/// Open a workspace so file commands accept paths inside it
+///
+/// Only folders the user picked in the folder dialog (or folders inside
+/// them) can be opened.
+#[tauri::command]
+pub fn open_workspace(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
+    let requested = Path::new(&path);
+    let root = resolve(requested).ok_or_else(|| CommandError::access_denied(requested))?;
+
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", path).into());
+    }
+
+    let mut sandbox = lock(&app)?;
+    if !sandbox.known_folders.iter().any(|folder| root.starts_with(folder)) {
+        return Err(CommandError::access_denied(requested));
+    }
+
+    if !sandbox.workspaces.contains(&root) {
+        sandbox.workspaces.push(root);
+    }
+    Ok(())
+}
+
+/// Stop accepting paths inside a workspace
+#[tauri::command]
+pub fn close_workspace(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
+    let Some(root) = resolve(Path::new(&path)) else {
+        return Ok(());
+    };
+
+    lock(&app)?.workspaces.retain(|workspace| workspace != &root);
+    Ok(())
+}
+
+/// Reject a path outside the open workspaces, the themes and plugins folders
+/// and the files picked in a dialog
+pub fn check(app: &tauri::AppHandle, path: &Path) -> Result<(), CommandError> {
+    let resolved = resolve(path).ok_or_else(|| CommandError::access_denied(path))?;
+
+    if lock(app)?.allows(&resolved) {
+        Ok(())
+    } else {
+        Err(CommandError::access_denied(path))
+    }
+}
+
+/// Like `check`, for commands that act on a symlink itself rather than on
+/// what it points to, e.g. deleting, renaming or moving it
+pub fn check_link(app: &tauri::AppHandle, path: &Path) -> Result<(), CommandError> {
+    let resolved = resolve_link(path).ok_or_else(|| CommandError::access_denied(path))?;
+
+    if lock(app)?.allows(&resolved) {
+        Ok(())
+    } else {
+        Err(CommandError::access_denied(path))
+    }
+}
+
+/// Reject a path that isn't inside an open workspace, e.g. a workspace root
+/// passed to a workspace-wide command
+pub fn check_workspace(app: &tauri::AppHandle, root: &Path) -> Result<(), CommandError> {
+    let resolved = resolve(root).ok_or_else(|| CommandError::access_denied(root))?;
+
+    if lock(app)?.workspaces.iter().any(|workspace| resolved.starts_with(workspace)) {
+        Ok(())
+    } else {
+        Err(CommandError::access_denied(root))
+    }
+}
+
+/// Canonical root of the open workspace containing `path`, the innermost
+/// one if workspaces are nested
+pub fn workspace_of(app: &tauri::AppHandle, path: &Path) -> Option<PathBuf> {
+    let resolved = resolve(path)?;
+
+    lock(app)
+        .ok()?
+        .workspaces
+        .iter()
+        .filter(|workspace| resolved.starts_with(workspace))
+        .max_by_key(|workspace| workspace.components().count())
+        .cloned()
+}
+
+/// Whether a path is a folder picked in the folder dialog or inside one,
+/// e.g. a recent workspace that hasn't been opened yet
+pub fn is_known_folder(app: &tauri::AppHandle, path: &Path) -> bool {
+    let Some(resolved) = resolve(path) else {
+        return false;
+    };
+
+    lock(app).is_ok_and(|sandbox| sandbox.known_folders.iter().any(|folder| resolved.starts_with(folder)))
+}
+
+/// Reject a config file that would overwrite the remembered workspace
+/// folders, which lives next to the config files on macOS and Windows
+pub fn check_config_file(app: &tauri::AppHandle, path: &Path) -> Result<(), CommandError> {
+    let protected = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|data_dir| resolve(&data_dir.join(KNOWN_FOLDERS_FILE)));
+
+    match resolve(path) {
+        Some(resolved) if Some(&resolved) != protected.as_ref() => Ok(()),
+        _ => Err(CommandError::access_denied(path)),
+    }
+}
+
+/// Reject a theme or plugin id that isn't a single plain path component
+pub fn check_name(name: &str) -> Result<(), CommandError> {
+    let mut components = Path::new(name).components();
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\']) => Ok(()),
+        _ => Err(CommandError::invalid_name(name)),
+    }
+}
+
+/// Reject a file name that could leave the directory it is joined to;
+/// nested names like `assets/icon.png` are fine
+pub fn check_relative_path(name: &str) -> Result<(), CommandError> {
+    let path = Path::new(name);
+
+    if name.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(CommandError::invalid_name(name));
+    }
+    Ok(())
+}
+
+/// Remember a folder the user picked so it can be opened as a workspace
+pub fn grant_folder(app: &tauri::AppHandle, path: &Path) {
+    let Some(folder) = resolve(path) else {
+        return;
+    };
+
+    let Ok(mut sandbox) = lock(app) else {
+        return;
+    };
+
+    if !sandbox.known_folders.contains(&folder) {
+        sandbox.known_folders.push(folder);
+        save_known_folders(app, &sandbox.known_folders);
+    }
+}
+
+/// Allow access to a file the user picked for the rest of the session
+pub fn grant_file(app: &tauri::AppHandle, path: &Path) {
+    let Some(file) = resolve(path) else {
+        return;
+    };
+
+    if let Ok(mut sandbox) = lock(app) {
+        if !sandbox.picked_files.contains(&file) {
+            sandbox.picked_files.push(file);
+        }
+    }
+}
+
+impl Sandbox {
+    fn allows(&self, resolved: &Path) -> bool {
+        self.workspaces
+            .iter()
+            .chain(&self.app_dirs)
+            .any(|root| resolved.starts_with(root))
+            || self.picked_files.iter().any(|file| file == resolved)
+    }
+}
+
+fn lock(app: &tauri::AppHandle) -> Result<MutexGuard<'_, Sandbox>, CommandError> {
+    app.state::<SandboxState>()
+        .inner()
+        .sandbox
+        .lock()
+        .map_err(|_| "Sandbox state is poisoned".to_string().into())
+}
+
+/// Canonical form of an absolute path, following symlinks and `..`
+///
+/// Components that don't exist yet are appended to the canonical form of
+/// the closest existing ancestor; `None` if they contain `..` or the path
+/// is relative.
+pub fn resolve(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() {
+        return None;
+    }
+
+    let mut existing = path;
+    let mut missing = Vec::new();
+
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(missing.iter().rev().fold(canonical, |resolved, name| resolved.join(name)));
+        }
+
+        // `file_name` is `None` for a trailing `..`, which can't be resolved
+        // without the folder it leaves
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+/// Canonical form of a path's parent with the last component appended as
+/// is, so a symlink at the path itself isn't followed
+fn resolve_link(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    Some(resolve(path.parent()?)?.join(name))
+}
+
+fn load_known_folders(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return Vec::new();
+    };
+
+    fs::read_to_string(data_dir.join(KNOWN_FOLDERS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_known_folders(app: &tauri::AppHandle, folders: &[PathBuf]) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+
+    let result = serde_json::to_string_pretty(folders)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            fs::create_dir_all(&data_dir)
+                .and_then(|_| atomic::write_atomic(&data_dir.join(KNOWN_FOLDERS_FILE), content))
+                .map_err(|e| e.to_string())
+        });
+
+    if let Err(e) = result {
+        eprintln!("[sandbox] Failed to save workspace folders: {}", e);
+    }
+}
@@ -0,0 +1,778 @@
+// ============================================================================
+// FULL-TEXT SEARCH
+// ============================================================================
+//
+// A persistent tantivy index per workspace, stored under the app data dir.
+// `index_workspace` brings it in sync with the disk, file commands and the
+// watcher keep it current through `refresh`, and `search_workspace` runs
+// queries with phrase ("..."), prefix (foo*), field (tag:, path:, title:)
+// and negation (-foo) support.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhrasePrefixQuery, PhraseQuery,
+    Query, TermQuery,
+};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, FAST, STORED, STRING,
+    TEXT,
+};
+use tantivy::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use tauri::Manager;
+
+use crate::ignore::{self, IgnoreRules};
+use crate::error::CommandError;
+use crate::encoding;
+use crate::markdown;
+use crate::sandbox;
+
+/// Directory under the app data dir holding one index per workspace
+const INDEX_DIR: &str = "search";
+
+/// Indexing memory budget; tantivy requires at least 15 MB per thread
+const WRITER_MEMORY: usize = 20_000_000;
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// Bytes of context shown before the first match in a snippet
+const SNIPPET_CONTEXT: usize = 60;
+
+/// Maximum snippet length in bytes
+const SNIPPET_LENGTH: usize = 200;
+
+const TAG_TOKENIZER: &str = "tag";
+
+struct Fields {
+    /// Absolute path, the document key
+    id: Field,
+    /// Workspace-relative path, tokenized for `path:` queries
+    path: Field,
+    title: Field,
+    body: Field,
+    tag: Field,
+    /// Milliseconds since the Unix epoch, used to skip unchanged files
+    modified: Field,
+}
+
+struct WorkspaceIndex {
+    root: PathBuf,
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+/// Open search indexes keyed by workspace root
+#[derive(Default)]
+pub struct SearchState {
+    indexes: Mutex<HashMap<PathBuf, Arc<WorkspaceIndex>>>,
+}
+
+/// Result of syncing an index with the disk
+#[derive(Debug, Serialize)]
+pub struct IndexStats {
+    indexed: usize,
+    removed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    path: String,
+    title: String,
+    score: f32,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<SearchSnippet>,
+}
+
+/// Excerpt of the file around the matches
+///
+/// Offsets are in UTF-16 code units so they can be used directly with
+/// JavaScript string methods.
+#[derive(Debug, Serialize)]
+pub struct SearchSnippet {
+    text: String,
+    /// Offset of `text` within the file content
+    offset: usize,
+    /// Match ranges within `text`
+    highlights: Vec<MatchRange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Build or update the search index of a workspace
+#[tauri::command]
+pub async fn index_workspace(app: tauri::AppHandle, workspace_root: String) -> Result<IndexStats, CommandError> {
+    sandbox::check_workspace(&app, Path::new(&workspace_root))?;
+
+    let stats = tauri::async_runtime::spawn_blocking(move || {
+        let index = open_index(&app, Path::new(&workspace_root))?;
+        index.sync()
+    })
+    .await
+    .map_err(|e| format!("Indexing task failed: {}", e))??;
+
+    Ok(stats)
+}
+
+/// Search a workspace's index
+#[tauri::command]
+pub async fn search_workspace(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchResult>, CommandError> {
+    sandbox::check_workspace(&app, Path::new(&workspace_root))?;
+
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        let index = open_index(&app, Path::new(&workspace_root))?;
+        index.search(&query, limit.unwrap_or(DEFAULT_LIMIT))
+    })
+    .await
+    .map_err(|e| format!("Search task failed: {}", e))??;
+
+    Ok(results)
+}
+
+/// Update open indexes after files changed on disk
+///
+/// `updated` paths are (re)indexed, directories recursively; `removed` paths
+/// are dropped along with everything under them. Paths outside any open
+/// index are ignored. Runs in the background so file commands return fast.
+pub fn refresh(app: &tauri::AppHandle, updated: Vec<PathBuf>, removed: Vec<PathBuf>) {
+    let Some(state) = app.try_state::<SearchState>() else {
+        return;
+    };
+
+    let indexes: Vec<Arc<WorkspaceIndex>> = match state.indexes.lock() {
+        Ok(indexes) => indexes.values().cloned().collect(),
+        Err(_) => return,
+    };
+
+    if indexes.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        for index in indexes {
+            let updated: Vec<&PathBuf> = updated.iter().filter(|p| p.starts_with(&index.root)).collect();
+            let removed: Vec<&PathBuf> = removed.iter().filter(|p| p.starts_with(&index.root)).collect();
+
+            if updated.is_empty() && removed.is_empty() {
+                continue;
+            }
+
+            if let Err(e) = index.refresh(&updated, &removed) {
+                eprintln!("[search] Failed to update index for {}: {}", index.root.display(), e);
+            }
+        }
+    });
+}
+
+fn open_index(app: &tauri::AppHandle, root: &Path) -> Result<Arc<WorkspaceIndex>, String> {
+    let state = app.state::<SearchState>();
+    let mut indexes = state
+        .indexes
+        .lock()
+        .map_err(|_| "Search state is poisoned".to_string())?;
+
+    if let Some(index) = indexes.get(root) {
+        return Ok(index.clone());
+    }
+
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", root.display()));
+    }
+
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get data directory: {}", e))?;
+    let index_dir = data_dir.join(INDEX_DIR).join(workspace_key(root));
+
+    let index = Arc::new(WorkspaceIndex::open(root, &index_dir)?);
+    indexes.insert(root.to_path_buf(), index.clone());
+
+    Ok(index)
+}
+
+/// Stable directory name for a workspace's index, also used by other
+/// per-workspace stores
+pub fn workspace_key(root: &Path) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(root.to_string_lossy().as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn build_schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+
+    let tag_options = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TAG_TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqs),
+        )
+        .set_stored();
+
+    let fields = Fields {
+        id: builder.add_text_field("id", STRING | STORED | FAST),
+        path: builder.add_text_field("path", TEXT),
+        title: builder.add_text_field("title", TEXT | STORED),
+        body: builder.add_text_field("body", TEXT | STORED),
+        tag: builder.add_text_field("tag", tag_options),
+        modified: builder.add_u64_field("modified", STORED | FAST),
+    };
+
+    (builder.build(), fields)
+}
+
+fn open_or_create(dir: &Path, schema: Schema) -> Result<Index, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+
+    let directory = MmapDirectory::open(dir).map_err(|e| format!("Failed to open index directory: {}", e))?;
+
+    Index::open_or_create(directory, schema).map_err(|e| format!("Failed to open index: {}", e))
+}
+
+impl WorkspaceIndex {
+    fn open(root: &Path, dir: &Path) -> Result<Self, String> {
+        let (schema, fields) = build_schema();
+
+        // The index is only a cache: if it's unreadable or was built with an
+        // older schema, start over
+        let index = match open_or_create(dir, schema.clone()) {
+            Ok(index) => index,
+            Err(_) => {
+                let _ = fs::remove_dir_all(dir);
+                open_or_create(dir, schema)?
+            }
+        };
+
+        index.tokenizers().register(
+            TAG_TOKENIZER,
+            TextAnalyzer::builder(RawTokenizer::default()).filter(LowerCaser).build(),
+        );
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e| format!("Failed to open index reader: {}", e))?;
+
+        let writer = index
+            .writer_with_num_threads(1, WRITER_MEMORY)
+            .map_err(|e| format!("Failed to open index writer: {}", e))?;
+
+        Ok(WorkspaceIndex {
+            root: root.to_path_buf(),
+            index,
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    /// Reindex changed files, add new ones and drop deleted ones
+    fn sync(&self) -> Result<IndexStats, String> {
+        let mut writer = self.lock_writer()?;
+
+        let mut indexed_files = self.indexed_files()?;
+        let disk_files = collect_notes(&IgnoreRules::load(&self.root, &[]), &self.root, &self.root);
+
+        let total = disk_files.len();
+        let mut indexed = 0;
+
+        for (path, modified) in disk_files {
+            let id = path.to_string_lossy().to_string();
+            if indexed_files.remove(&id) != Some(modified) {
+                self.index_file(&mut writer, &path, modified)?;
+                indexed += 1;
+            }
+        }
+
+        // Whatever is left was indexed but is no longer on disk
+        let removed = indexed_files.len();
+        for id in indexed_files.keys() {
+            writer.delete_term(Term::from_field_text(self.fields.id, id));
+        }
+
+        if indexed > 0 || removed > 0 {
+            self.commit(&mut writer)?;
+        }
+
+        Ok(IndexStats { indexed, removed, total })
+    }
+
+    fn refresh(&self, updated: &[&PathBuf], removed: &[&PathBuf]) -> Result<(), String> {
+        // Different ignore rules can add or drop notes anywhere
+        if updated.iter().chain(removed).any(|p| ignore::is_ignore_file(&self.root, p)) {
+            return self.sync().map(|_| ());
+        }
+
+        let rules = IgnoreRules::load(&self.root, &[]);
+        let mut writer = self.lock_writer()?;
+        let mut changed = false;
+
+        if !removed.is_empty() {
+            for id in self.indexed_files()?.keys() {
+                let id_path = Path::new(id);
+                if removed.iter().any(|removed| id_path.starts_with(removed)) {
+                    writer.delete_term(Term::from_field_text(self.fields.id, id));
+                    changed = true;
+                }
+            }
+        }
+
+        for path in updated {
+            let files = if path.is_dir() {
+                if self.is_ignored(&rules, path) {
+                    Vec::new()
+                } else {
+                    collect_notes(&rules, &self.root, path)
+                }
+            } else {
+                self.indexable_modified(&rules, path)
+                    .map(|modified| vec![(path.to_path_buf(), modified)])
+                    .unwrap_or_default()
+            };
+
+            for (file, modified) in files {
+                if self.indexed_modified(&file)? != Some(modified) {
+                    self.index_file(&mut writer, &file, modified)?;
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            self.commit(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let clauses = parse_query(query);
+        if clauses.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for clause in &clauses {
+            if let Some(query) = self.clause_query(clause)? {
+                let occur = if clause.negated { Occur::MustNot } else { Occur::Must };
+                subqueries.push((occur, query));
+            }
+        }
+
+        // A query of only exclusions matches nothing unless we start from everything
+        if subqueries.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+            subqueries.push((Occur::Must, Box::new(AllQuery)));
+        }
+
+        let query = BooleanQuery::new(subqueries);
+        let searcher = self.reader.searcher();
+
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit))
+            .map_err(|e| format!("Search failed: {}", e))?;
+
+        let matchers = self.snippet_matchers(&clauses)?;
+        let mut results = Vec::with_capacity(top_docs.len());
+
+        for (score, address) in top_docs {
+            let document: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| format!("Failed to load search result: {}", e))?;
+
+            let text = |field: Field| {
+                document
+                    .get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+
+            let body = text(self.fields.body);
+            let tags = document
+                .get_all(self.fields.tag)
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect();
+
+            results.push(SearchResult {
+                path: text(self.fields.id),
+                title: text(self.fields.title),
+                score,
+                tags,
+                snippet: self.make_snippet(&body, &matchers)?,
+            });
+        }
+
+        Ok(results)
+    }
+
+    fn index_file(&self, writer: &mut IndexWriter, path: &Path, modified: u64) -> Result<(), String> {
+        let id = path.to_string_lossy().to_string();
+        writer.delete_term(Term::from_field_text(self.fields.id, &id));
+
+        // Unreadable files are left out; anything else is decoded like the editor does
+        let Ok(bytes) = fs::read(path) else {
+            return Ok(());
+        };
+        let (content, _) = encoding::decode(&bytes);
+
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+
+        let mut document = doc!(
+            self.fields.id => id,
+            self.fields.path => relative.to_string_lossy().to_string(),
+            self.fields.title => markdown::extract_title(&content, stem),
+            self.fields.modified => modified,
+        );
+        for tag in markdown::extract_tags(&content) {
+            document.add_text(self.fields.tag, &tag);
+        }
+        document.add_text(self.fields.body, &content);
+
+        writer
+            .add_document(document)
+            .map_err(|e| format!("Failed to index {}: {}", path.display(), e))?;
+
+        Ok(())
+    }
+
+    fn commit(&self, writer: &mut IndexWriter) -> Result<(), String> {
+        writer.commit().map_err(|e| format!("Failed to commit index: {}", e))?;
+        self.reader.reload().map_err(|e| format!("Failed to reload index: {}", e))
+    }
+
+    fn lock_writer(&self) -> Result<std::sync::MutexGuard<'_, IndexWriter>, String> {
+        self.writer.lock().map_err(|_| "Index writer is poisoned".to_string())
+    }
+
+    /// Every indexed path with the modification time it was indexed at
+    fn indexed_files(&self) -> Result<HashMap<String, u64>, String> {
+        let searcher = self.reader.searcher();
+        let mut files = HashMap::new();
+
+        for segment in searcher.segment_readers() {
+            let fast_fields = segment.fast_fields();
+            let ids = fast_fields
+                .str("id")
+                .map_err(|e| format!("Failed to read index: {}", e))?
+                .ok_or_else(|| "Index is missing the id column".to_string())?;
+            let modified = fast_fields
+                .u64("modified")
+                .map_err(|e| format!("Failed to read index: {}", e))?;
+
+            for doc in segment.doc_ids_alive() {
+                let mut id = String::new();
+                if let Some(ord) = ids.term_ords(doc).next() {
+                    ids.ord_to_str(ord, &mut id)
+                        .map_err(|e| format!("Failed to read index: {}", e))?;
+                }
+                files.insert(id, modified.first(doc).unwrap_or(0));
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Modification time a single file was indexed at, if it is indexed
+    fn indexed_modified(&self, path: &Path) -> Result<Option<u64>, String> {
+        let searcher = self.reader.searcher();
+        let term = Term::from_field_text(self.fields.id, &path.to_string_lossy());
+        let query = TermQuery::new(term, IndexRecordOption::Basic);
+
+        let top = searcher
+            .search(&query, &TopDocs::with_limit(1))
+            .map_err(|e| format!("Failed to read index: {}", e))?;
+
+        let Some((_, address)) = top.first() else {
+            return Ok(None);
+        };
+
+        let document: TantivyDocument = searcher
+            .doc(*address)
+            .map_err(|e| format!("Failed to read index: {}", e))?;
+
+        Ok(document.get_first(self.fields.modified).and_then(|v| v.as_u64()))
+    }
+
+    /// Whether a path is outside the workspace or ignored by its rules
+    fn is_ignored(&self, rules: &IgnoreRules, path: &Path) -> bool {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => rules.is_path_ignored(relative, path.is_dir()),
+            Err(_) => true,
+        }
+    }
+
+    /// Modification time of a note inside the workspace that should be
+    /// indexed; `None` for ignored paths (`.trash`, `.git`...) and non-notes
+    fn indexable_modified(&self, rules: &IgnoreRules, path: &Path) -> Option<u64> {
+        if self.is_ignored(rules, path) || !markdown::is_note(path) {
+            return None;
+        }
+
+        fs::metadata(path).ok().map(|metadata| crate::modified_millis(&metadata))
+    }
+
+    fn clause_query(&self, clause: &Clause) -> Result<Option<Box<dyn Query>>, String> {
+        let fields: Vec<(Field, f32)> = match clause.field {
+            Some(QueryField::Tag) => vec![(self.fields.tag, 1.0)],
+            Some(QueryField::Path) => vec![(self.fields.path, 1.0)],
+            Some(QueryField::Title) => vec![(self.fields.title, 1.0)],
+            None => vec![(self.fields.title, 2.0), (self.fields.body, 1.0), (self.fields.path, 1.0)],
+        };
+
+        let text = match clause.field {
+            Some(QueryField::Tag) => clause.text.trim_start_matches('#'),
+            _ => &clause.text,
+        };
+
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        for (field, boost) in fields {
+            let terms = self.tokenize(field, text)?;
+
+            let query: Box<dyn Query> = match terms.len() {
+                0 => continue,
+                1 if clause.prefix => Box::new(FuzzyTermQuery::new_prefix(terms[0].clone(), 0, true)),
+                1 => Box::new(TermQuery::new(terms[0].clone(), IndexRecordOption::WithFreqs)),
+                _ if clause.prefix => Box::new(PhrasePrefixQuery::new(terms)),
+                _ => Box::new(PhraseQuery::new(terms)),
+            };
+
+            subqueries.push((Occur::Should, Box::new(BoostQuery::new(query, boost))));
+        }
+
+        if subqueries.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Box::new(BooleanQuery::new(subqueries))))
+    }
+
+    fn tokenize(&self, field: Field, text: &str) -> Result<Vec<Term>, String> {
+        Ok(self
+            .tokenize_text(field, text)?
+            .into_iter()
+            .map(|token| Term::from_field_text(field, &token))
+            .collect())
+    }
+
+    fn tokenize_text(&self, field: Field, text: &str) -> Result<Vec<String>, String> {
+        let mut analyzer = self
+            .index
+            .tokenizer_for_field(field)
+            .map_err(|e| format!("Failed to get tokenizer: {}", e))?;
+
+        let mut tokens = Vec::new();
+        let mut stream = analyzer.token_stream(text);
+        while stream.advance() {
+            tokens.push(stream.token().text.clone());
+        }
+
+        Ok(tokens)
+    }
+
+    /// Terms to highlight in the body, from the clauses that search it
+    fn snippet_matchers(&self, clauses: &[Clause]) -> Result<Vec<Matcher>, String> {
+        let mut matchers = Vec::new();
+
+        for clause in clauses.iter().filter(|c| c.field.is_none() && !c.negated) {
+            let tokens = self.tokenize_text(self.fields.body, &clause.text)?;
+            let last = tokens.len().saturating_sub(1);
+
+            for (i, token) in tokens.into_iter().enumerate() {
+                matchers.push(if clause.prefix && i == last {
+                    Matcher::Prefix(token)
+                } else {
+                    Matcher::Exact(token)
+                });
+            }
+        }
+
+        Ok(matchers)
+    }
+
+    fn make_snippet(&self, body: &str, matchers: &[Matcher]) -> Result<Option<SearchSnippet>, String> {
+        if matchers.is_empty() {
+            return Ok(None);
+        }
+
+        let mut analyzer = self
+            .index
+            .tokenizer_for_field(self.fields.body)
+            .map_err(|e| format!("Failed to get tokenizer: {}", e))?;
+
+        let mut matches = Vec::new();
+        let mut stream = analyzer.token_stream(body);
+        while stream.advance() {
+            let token = stream.token();
+            if matchers.iter().any(|m| m.matches(&token.text)) {
+                matches.push(token.offset_from..token.offset_to);
+            }
+        }
+
+        let Some(first) = matches.first() else {
+            return Ok(None);
+        };
+
+        let start = floor_char_boundary(body, first.start.saturating_sub(SNIPPET_CONTEXT));
+        let end = floor_char_boundary(body, (start + SNIPPET_LENGTH).min(body.len()));
+        let text = &body[start..end];
+
+        let highlights = matches
+            .iter()
+            .filter(|m| m.start >= start && m.end <= end)
+            .map(|m| MatchRange {
+                start: utf16_len(&body[start..m.start]),
+                end: utf16_len(&body[start..m.end]),
+            })
+            .collect();
+
+        Ok(Some(SearchSnippet {
+            text: text.to_string(),
+            offset: utf16_len(&body[..start]),
+            highlights,
+        }))
+    }
+}
+
+enum Matcher {
+    Exact(String),
+    Prefix(String),
+}
+
+impl Matcher {
+    fn matches(&self, token: &str) -> bool {
+        match self {
+            Matcher::Exact(term) => token == term,
+            Matcher::Prefix(prefix) => token.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueryField {
+    Tag,
+    Path,
+    Title,
+}
+
+/// One whitespace-separated part of a query
+#[derive(Debug)]
+struct Clause {
+    field: Option<QueryField>,
+    text: String,
+    prefix: bool,
+    negated: bool,
+}
+
+/// Split a query into clauses: `word`, `word*`, `"a phrase"`, `"a phr"*`,
+/// `field:value`, each optionally negated with a leading `-`
+fn parse_query(input: &str) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let mut rest = input.trim_start();
+
+    while !rest.is_empty() {
+        let negated = rest.len() > 1 && rest.starts_with('-');
+        if negated {
+            rest = &rest[1..];
+        }
+
+        let mut field = None;
+        if let Some((name, value)) = rest.split_once(':') {
+            let parsed = match name {
+                "tag" => Some(QueryField::Tag),
+                "path" => Some(QueryField::Path),
+                "title" => Some(QueryField::Title),
+                _ => None,
+            };
+            if parsed.is_some() && !name.contains(char::is_whitespace) {
+                field = parsed;
+                rest = value;
+            }
+        }
+
+        let (text, remainder) = if let Some(quoted) = rest.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+
+        let (text, prefix, remainder) = match remainder.strip_prefix('*') {
+            Some(remainder) => (text, true, remainder),
+            None => match text.strip_suffix('*') {
+                Some(text) => (text, true, remainder),
+                None => (text, false, remainder),
+            },
+        };
+
+        if !text.is_empty() {
+            clauses.push(Clause {
+                field,
+                text: text.to_string(),
+                prefix,
+                negated,
+            });
+        }
+
+        rest = remainder.trim_start();
+    }
+
+    clauses
+}
+
+/// Notes under `dir` with their modification times, skipping ignored
+/// entries such as `.trash` and `.git`
+fn collect_notes(rules: &IgnoreRules, root: &Path, dir: &Path) -> Vec<(PathBuf, u64)> {
+    rules
+        .collect_files_in(root, dir)
+        .into_iter()
+        .filter(|path| markdown::is_note(path))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, crate::modified_millis(&metadata)))
+        })
+        .collect()
+}
+
+/// Largest char boundary at or before `index`
+pub fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Length of `text` in UTF-16 code units, as JavaScript counts it
+pub fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
@@ -0,0 +1,192 @@
+// ============================================================================
+// FILE DETAILS
+// ============================================================================
+//
+// Properties of files and folders beyond those in `FileNode`, so the
+// explorer can show and sort by them without reading every file from the
+// frontend: creation time, modification time in milliseconds, the read-only
+// flag, a MIME type and kind from the extension and, for notes, line and
+// word counts and the frontmatter title. `stat_path` returns them for one
+// item; `read_directory` adds them to every node with `include_details`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::{CommandError, IoContext};
+use crate::{asset, encoding, markdown, sandbox};
+
+/// Notes larger than this get no `NoteStats`
+const MAX_NOTE_STATS_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Broad kind of an item, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileKind {
+    Folder,
+    Note,
+    Image,
+    Audio,
+    Video,
+    Pdf,
+    Text,
+    Font,
+    Other,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteStats {
+    line_count: usize,
+    /// Words outside the frontmatter
+    word_count: usize,
+    /// Frontmatter `title`, if set
+    title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDetails {
+    /// Milliseconds since the Unix epoch; `None` where the file system
+    /// doesn't record it
+    created: Option<u64>,
+    /// Milliseconds since the Unix epoch
+    modified: Option<u64>,
+    read_only: bool,
+    kind: FileKind,
+    /// `None` for folders
+    mime_type: Option<&'static str>,
+    /// Only for notes up to `MAX_NOTE_STATS_BYTES`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<NoteStats>,
+}
+
+/// A file or folder and its details, as returned by `stat_path`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathStat {
+    name: String,
+    path: String,
+    is_directory: bool,
+    /// Size in bytes; `None` for folders
+    size: Option<u64>,
+    /// The item is a symbolic link; the other fields describe what it
+    /// points to, or the link itself when it is broken
+    is_symlink: bool,
+    /// Where the link points, as stored in the link
+    symlink_target: Option<String>,
+    /// The link points at nothing
+    is_broken_symlink: bool,
+    #[serde(flatten)]
+    details: FileDetails,
+}
+
+/// Details of one file or folder
+#[tauri::command]
+pub fn stat_path(app: tauri::AppHandle, path: String) -> Result<PathStat, CommandError> {
+    let item_path = PathBuf::from(&path);
+    sandbox::check_link(&app, &item_path)?;
+
+    let link_metadata = fs::symlink_metadata(&item_path).context("Failed to read metadata", &item_path)?;
+    let is_symlink = link_metadata.is_symlink();
+
+    let (metadata, is_broken_symlink) = if is_symlink {
+        match fs::metadata(&item_path) {
+            Ok(metadata) => (metadata, false),
+            Err(_) => (link_metadata, true),
+        }
+    } else {
+        (link_metadata, false)
+    };
+
+    let symlink_target = if is_symlink {
+        fs::read_link(&item_path).ok().map(|target| target.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    let is_directory = metadata.is_dir();
+
+    Ok(PathStat {
+        name: item_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        size: (!is_directory).then_some(metadata.len()),
+        details: details(&item_path, &metadata),
+        path,
+        is_directory,
+        is_symlink,
+        symlink_target,
+        is_broken_symlink,
+    })
+}
+
+/// Details of an item whose metadata was already read; notes are read to
+/// count their lines and words
+pub fn details(path: &Path, metadata: &fs::Metadata) -> FileDetails {
+    let kind = kind(path, metadata.is_dir());
+
+    let note = if kind == FileKind::Note && metadata.is_file() && metadata.len() <= MAX_NOTE_STATS_BYTES {
+        fs::read(path).ok().map(|bytes| note_stats(&encoding::decode(&bytes).0))
+    } else {
+        None
+    };
+
+    FileDetails {
+        created: millis(metadata.created()),
+        modified: millis(metadata.modified()),
+        read_only: metadata.permissions().readonly(),
+        kind,
+        mime_type: (!metadata.is_dir()).then(|| mime_essence(path)),
+        note,
+    }
+}
+
+/// Kind of an item from its extension
+pub fn kind(path: &Path, is_dir: bool) -> FileKind {
+    if is_dir {
+        return FileKind::Folder;
+    }
+    if markdown::is_note(path) {
+        return FileKind::Note;
+    }
+
+    match mime_essence(path) {
+        "application/pdf" => FileKind::Pdf,
+        "application/json" => FileKind::Text,
+        mime => match mime.split_once('/').map(|(kind, _)| kind) {
+            Some("image") => FileKind::Image,
+            Some("audio") => FileKind::Audio,
+            Some("video") => FileKind::Video,
+            Some("text") => FileKind::Text,
+            Some("font") => FileKind::Font,
+            _ => FileKind::Other,
+        },
+    }
+}
+
+/// MIME type without parameters such as `charset`
+fn mime_essence(path: &Path) -> &'static str {
+    let mime = asset::mime_type(path);
+    mime.split_once(';').map_or(mime, |(essence, _)| essence)
+}
+
+fn note_stats(content: &str) -> NoteStats {
+    let (frontmatter, body) = markdown::split_frontmatter(content);
+
+    NoteStats {
+        line_count: content.lines().count(),
+        word_count: body.split_whitespace().count(),
+        title: frontmatter.and_then(|frontmatter| markdown::frontmatter_value(frontmatter, "title")),
+    }
+}
+
+fn millis(time: io::Result<SystemTime>) -> Option<u64> {
+    time.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
@@ -0,0 +1,359 @@
+// ============================================================================
+// WORKSPACE STATISTICS
+// ============================================================================
+//
+// Numbers about a whole workspace, gathered by walking it in the backend so
+// the webview doesn't have to read every note: note and attachment counts,
+// bytes by kind of file, words and characters in notes, the largest and the
+// most recently modified files, and words written per day. Words are counted
+// outside the frontmatter, the same way `stat_path` counts them.
+//
+// Words written on a day, in the caller's time zone, are how much each note
+// written to that day grew over it. A note's size at midnight comes from the
+// version history: the first version saved after midnight holds the content
+// the note had then, or the note is unchanged since if there is none.
+// Versions are saved at most once per snapshot interval, so writes shortly
+// before midnight may count toward the next day, and notes without history
+// (e.g. edited outside the app) count as unchanged.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CommandError;
+use crate::history::{self, HistoryView};
+use crate::ignore::IgnoreRules;
+use crate::stat::{self, FileKind};
+use crate::{encoding, markdown, sandbox};
+
+/// Notes larger than this are counted but not read for words
+const MAX_NOTE_BYTES: u64 = 10 * 1024 * 1024;
+
+const DEFAULT_LIST_LENGTH: usize = 10;
+
+/// Longest `largest_files` and `recent_files` a caller can ask for
+const MAX_LIST_LENGTH: usize = 100;
+
+const DEFAULT_DAYS: u32 = 30;
+
+/// Most days a caller can ask for in `words_by_day`
+const MAX_DAYS: u32 = 366;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStatsOptions {
+    /// Extra gitignore-style patterns on top of the workspace's ignore files
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    /// Length of `largest_files` and `recent_files` (default 10, at most 100)
+    list_length: Option<usize>,
+    /// Days in `words_by_day`, ending today (default 30, 1 to 366)
+    days: Option<u32>,
+    /// Offset of the caller's time zone from UTC, e.g. `-300` for UTC-5;
+    /// days start at local midnight
+    #[serde(default)]
+    utc_offset_minutes: i32,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+    note_count: usize,
+    /// Files that aren't notes
+    attachment_count: usize,
+    total_bytes: u64,
+    /// Files and bytes per kind, most bytes first
+    by_kind: Vec<KindTotal>,
+    /// Words in notes, outside their frontmatter
+    word_count: usize,
+    character_count: usize,
+    /// Characters other than whitespace
+    character_count_no_spaces: usize,
+    /// Largest files first
+    largest_files: Vec<FileEntry>,
+    /// Most recently modified files first
+    recent_files: Vec<FileEntry>,
+    /// One entry per day, oldest first
+    words_by_day: Vec<DayWords>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KindTotal {
+    kind: FileKind,
+    count: usize,
+    bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEntry {
+    path: String,
+    kind: FileKind,
+    size: u64,
+    /// Milliseconds since the Unix epoch
+    modified: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayWords {
+    /// Local date as `YYYY-MM-DD`
+    date: String,
+    /// Words added to notes that day; a note that shrank adds none
+    words: usize,
+    /// Notes written to that day
+    notes: usize,
+}
+
+/// A file and what was counted in it
+struct Counted {
+    entry: FileEntry,
+    words: usize,
+    characters: usize,
+    characters_no_spaces: usize,
+    /// Days in the window the note was written to, with the words added
+    written: Vec<(i64, usize)>,
+}
+
+/// The days of `words_by_day`, counted from the Unix epoch in the caller's
+/// time zone
+struct Window {
+    first_day: i64,
+    today: i64,
+    /// Offset of the time zone from UTC in milliseconds
+    offset: i64,
+}
+
+/// Statistics of every non-ignored file in a workspace
+#[tauri::command]
+pub async fn workspace_stats(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    options: Option<WorkspaceStatsOptions>,
+) -> Result<WorkspaceStats, CommandError> {
+    let options = options.unwrap_or_default();
+
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    if !root.is_dir() {
+        return Err(CommandError::not_a_directory(&root));
+    }
+
+    // Version history is keyed by the canonical root
+    let history = sandbox::resolve(&root).and_then(|canonical| history::view(&app, &canonical));
+
+    tauri::async_runtime::spawn_blocking(move || collect(&root, history.as_ref(), &options))
+        .await
+        .map_err(|e| format!("Statistics task failed: {}", e).into())
+}
+
+fn collect(root: &Path, history: Option<&HistoryView>, options: &WorkspaceStatsOptions) -> WorkspaceStats {
+    let list_length = options.list_length.unwrap_or(DEFAULT_LIST_LENGTH).min(MAX_LIST_LENGTH);
+    let offset = i64::from(options.utc_offset_minutes) * 60 * 1000;
+    let days = i64::from(options.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS));
+    let today = local_day(crate::now_millis(), offset);
+    let window = Window {
+        first_day: today - days + 1,
+        today,
+        offset,
+    };
+
+    let files = IgnoreRules::load(root, &options.ignore_patterns).collect_files(root);
+    let counted: Vec<Counted> = files
+        .par_iter()
+        .filter_map(|path| count(path, history, &window))
+        .collect();
+
+    let mut by_kind: HashMap<FileKind, KindTotal> = HashMap::new();
+    let mut by_day: HashMap<i64, (usize, usize)> = HashMap::new();
+    let mut stats = WorkspaceStats::default();
+
+    for file in &counted {
+        let entry = &file.entry;
+
+        if entry.kind == FileKind::Note {
+            stats.note_count += 1;
+
+            for &(day, added) in &file.written {
+                let (words, notes) = by_day.entry(day).or_default();
+                *words += added;
+                *notes += 1;
+            }
+        } else {
+            stats.attachment_count += 1;
+        }
+
+        stats.total_bytes += entry.size;
+        stats.word_count += file.words;
+        stats.character_count += file.characters;
+        stats.character_count_no_spaces += file.characters_no_spaces;
+
+        let total = by_kind.entry(entry.kind).or_insert(KindTotal {
+            kind: entry.kind,
+            count: 0,
+            bytes: 0,
+        });
+        total.count += 1;
+        total.bytes += entry.size;
+    }
+
+    stats.by_kind = by_kind.into_values().collect();
+    stats.by_kind.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(b.count.cmp(&a.count)));
+
+    stats.largest_files = top(&counted, list_length, |entry| entry.size);
+    stats.recent_files = top(&counted, list_length, |entry| entry.modified);
+
+    stats.words_by_day = (window.first_day..=window.today)
+        .map(|day| {
+            let (words, notes) = by_day.get(&day).copied().unwrap_or_default();
+            DayWords {
+                date: date_string(day),
+                words,
+                notes,
+            }
+        })
+        .collect();
+
+    stats
+}
+
+/// Read a file's metadata and, for notes, count their words and characters
+/// and the words written to them on each day of the window
+fn count(path: &Path, history: Option<&HistoryView>, window: &Window) -> Option<Counted> {
+    let metadata = fs::metadata(path).ok()?;
+
+    let entry = FileEntry {
+        path: path.to_string_lossy().to_string(),
+        kind: stat::kind(path, false),
+        size: metadata.len(),
+        modified: crate::modified_millis(&metadata),
+    };
+
+    let mut counted = Counted {
+        entry,
+        words: 0,
+        characters: 0,
+        characters_no_spaces: 0,
+        written: Vec::new(),
+    };
+
+    if counted.entry.kind == FileKind::Note && metadata.len() <= MAX_NOTE_BYTES {
+        if let Ok(bytes) = fs::read(path) {
+            let (content, _) = encoding::decode(&bytes);
+            let (_, body) = markdown::split_frontmatter(&content);
+
+            counted.words = body.split_whitespace().count();
+            counted.characters = body.chars().count();
+            counted.characters_no_spaces = body.chars().filter(|c| !c.is_whitespace()).count();
+            counted.written = written_by_day(path, counted.entry.modified, counted.words, history, window);
+        }
+    }
+
+    Some(counted)
+}
+
+/// Days of the window a note was written to, with how many words it grew
+/// by over each; see the module comment for how its size at midnight is
+/// found
+fn written_by_day(
+    path: &Path,
+    modified: u64,
+    words: usize,
+    history: Option<&HistoryView>,
+    window: &Window,
+) -> Vec<(i64, usize)> {
+    // Not written to since before the window
+    if modified < window.start(window.first_day) {
+        return Vec::new();
+    }
+
+    let Some(history) = history else {
+        return Vec::new();
+    };
+    let versions = history.versions(path);
+
+    // Words of each version, read once however many midnights it covers
+    let mut version_words: HashMap<&str, Option<usize>> = HashMap::new();
+    let mut words_at = |time: u64| match versions.iter().find(|version| version.created_at() > time) {
+        Some(version) => *version_words
+            .entry(version.hash())
+            .or_insert_with(|| history.read(version).map(|bytes| body_words(&bytes))),
+        None => Some(words),
+    };
+
+    let mut written = Vec::new();
+    for day in window.first_day..=window.today {
+        let (start, end) = (window.start(day), window.start(day + 1));
+
+        let active = (start..end).contains(&modified)
+            || versions.iter().any(|version| (start..end).contains(&version.created_at()));
+        if !active {
+            continue;
+        }
+
+        // A version pruned in the meantime leaves the day's growth unknown
+        let added = match (words_at(start), words_at(end)) {
+            (Some(before), Some(after)) => after.saturating_sub(before),
+            _ => 0,
+        };
+        written.push((day, added));
+    }
+
+    written
+}
+
+/// Words of note content outside the frontmatter
+fn body_words(bytes: &[u8]) -> usize {
+    let (content, _) = encoding::decode(bytes);
+    let (_, body) = markdown::split_frontmatter(&content);
+    body.split_whitespace().count()
+}
+
+impl Window {
+    /// Milliseconds since the Unix epoch at which a day starts
+    fn start(&self, day: i64) -> u64 {
+        (day * MILLIS_PER_DAY - self.offset).max(0) as u64
+    }
+}
+
+/// The `length` files with the largest `key`
+fn top(files: &[Counted], length: usize, key: impl Fn(&FileEntry) -> u64) -> Vec<FileEntry> {
+    let mut entries: Vec<&FileEntry> = files.iter().map(|file| &file.entry).collect();
+    entries.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| a.path.cmp(&b.path)));
+    entries.into_iter().take(length).cloned().collect()
+}
+
+/// Days since the Unix epoch of a time, in a time zone `offset`
+/// milliseconds from UTC
+fn local_day(millis: u64, offset: i64) -> i64 {
+    (millis as i64 + offset).div_euclid(MILLIS_PER_DAY)
+}
+
+/// `YYYY-MM-DD` of a day counted from the Unix epoch
+fn date_string(day: i64) -> String {
+    let (year, month, day_of_month) = civil_date(day);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// Year, month and day of the month of a day counted from the Unix epoch,
+/// in the proleptic Gregorian calendar
+pub fn civil_date(day: i64) -> (i64, u32, u32) {
+    let shifted = day + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months counted from March, so February's leap day comes last
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month as u32, day_of_month as u32)
+}
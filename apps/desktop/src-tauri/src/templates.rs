@@ -0,0 +1,412 @@
+// ============================================================================
+// NOTE TEMPLATES
+// ============================================================================
+//
+// New notes created from markdown templates kept in a workspace folder.
+// Placeholders are written `{{name}}` or `{{name:argument}}`:
+//
+// - `{{date}}`, `{{date:YYYY-MM-DD}}`, `{{time}}`, `{{time:HH:mm}}`: the
+//   current local date or time, formatted with moment-style tokens
+//   (`YYYY`, `MMMM`, `DD`, `dddd`, `HH`, `hh`, `mm`, `A`...); text in
+//   `[brackets]` is kept as is
+// - `{{title}}`: the `title` variable, or else the new note's name
+// - `{{cursor}}`: removed; its position is returned so the editor can put
+//   the cursor there
+// - `{{prompt:Question}}`: the answer to a question the frontend asks
+//   first, passed as the variable `Question`; see `template_prompts`
+// - `{{include:Other template}}`: the body of another template, relative to
+//   the including one, without its frontmatter
+// - `{{anything else}}`: the variable of that name; unknown names are left
+//   as they are
+//
+// The target path is expanded the same way, so `Journal/{{date:YYYY}}/
+// {{date:YYYY-MM-DD}}` files daily notes by year.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CommandError, ErrorCode, IoContext};
+use crate::ignore::{to_slash_path, IgnoreRules};
+use crate::search::utf16_len;
+use crate::{atomic, encoding, markdown, sandbox, stats};
+
+/// Includes nested deeper than this are refused
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+const DEFAULT_DATE_FORMAT: &str = "YYYY-MM-DD";
+const DEFAULT_TIME_FORMAT: &str = "HH:mm";
+
+/// Title of a note whose path uses `{{title}}` without a `title` variable
+const DEFAULT_TITLE: &str = "Untitled";
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+/// Format tokens, longest first so `MMMM` isn't read as `MM` twice
+const FORMAT_TOKENS: &[&str] = &[
+    "YYYY", "YY", "MMMM", "MMM", "MM", "M", "DD", "D", "dddd", "ddd", "HH", "H", "hh", "h", "mm", "m", "ss", "s",
+    "A", "a",
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateOptions {
+    /// Offset of the caller's time zone from UTC, e.g. `-300` for UTC-5
+    #[serde(default)]
+    utc_offset_minutes: i32,
+    /// When the target exists, use "name 1.md", "name 2.md"... instead of
+    /// failing
+    #[serde(default)]
+    auto_rename: bool,
+}
+
+/// A template, as listed by `list_templates`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+    /// Path relative to the templates folder, `/`-separated, without `.md`
+    name: String,
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedNote {
+    path: String,
+    /// Where `{{cursor}}` was, in UTF-16 code units from the start of the
+    /// note; `None` if the template has none
+    cursor: Option<usize>,
+}
+
+/// What placeholders expand to
+struct Context<'a> {
+    vars: &'a HashMap<String, String>,
+    title: String,
+    /// Local time in milliseconds since the Unix epoch
+    now: i64,
+}
+
+/// Templates in a folder and its subfolders, sorted by name; an empty list
+/// if the folder doesn't exist
+#[tauri::command]
+pub fn list_templates(app: tauri::AppHandle, folder: String) -> Result<Vec<TemplateInfo>, CommandError> {
+    let folder = PathBuf::from(&folder);
+    sandbox::check(&app, &folder)?;
+
+    if !folder.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates: Vec<TemplateInfo> = IgnoreRules::default()
+        .collect_files(&folder)
+        .into_iter()
+        .filter(|path| markdown::is_note(path))
+        .map(|path| {
+            let relative = to_slash_path(path.strip_prefix(&folder).unwrap_or(&path));
+            TemplateInfo {
+                name: relative.rsplit_once('.').map_or(relative.clone(), |(stem, _)| stem.to_string()),
+                path: path.to_string_lossy().to_string(),
+            }
+        })
+        .collect();
+
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    Ok(templates)
+}
+
+/// Questions of the `{{prompt:...}}` placeholders of a template and the
+/// templates it includes, in order of appearance and without repeats
+#[tauri::command]
+pub fn template_prompts(app: tauri::AppHandle, template: String) -> Result<Vec<String>, CommandError> {
+    let content = load(&app, Path::new(&template), &mut Vec::new())?;
+
+    let mut prompts: Vec<String> = Vec::new();
+    for caps in placeholder().captures_iter(&content) {
+        if &caps[1] == "prompt" {
+            let question = argument(&caps).unwrap_or_default().to_string();
+            if !prompts.contains(&question) {
+                prompts.push(question);
+            }
+        }
+    }
+
+    Ok(prompts)
+}
+
+/// Create a note at `target` from `template`, expanding placeholders in
+/// both the target path and the content
+///
+/// A relative `target` is taken from `workspace_root`, and `.md` is added
+/// when it has no extension. Missing parent folders are created.
+#[tauri::command]
+pub fn create_from_template(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    template: String,
+    target: String,
+    vars: Option<HashMap<String, String>>,
+    options: Option<TemplateOptions>,
+) -> Result<CreatedNote, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+
+    let vars = vars.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    let now = crate::now_millis() as i64 + i64::from(options.utc_offset_minutes) * 60 * 1000;
+
+    let content = load(&app, Path::new(&template), &mut Vec::new())?;
+
+    // The title isn't known before the path is, so the path gets the
+    // variable or a placeholder title
+    let path_context = Context {
+        vars: &vars,
+        title: vars.get("title").cloned().unwrap_or_else(|| DEFAULT_TITLE.to_string()),
+        now,
+    };
+    let path = target_path(&root, &expand(&target, &path_context).0)?;
+    sandbox::check(&app, &path)?;
+
+    let path = if !path.exists() {
+        path
+    } else if options.auto_rename {
+        numbered_path(&path)?
+    } else {
+        return Err(CommandError::already_exists(&path));
+    };
+
+    let context = Context {
+        vars: &vars,
+        title: vars.get("title").cloned().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }),
+        now,
+    };
+    let (content, cursor) = expand(&content, &context);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create parent directories", parent)?;
+    }
+    atomic::write_atomic(&path, &content).context("Failed to create note", &path)?;
+
+    crate::refresh_indexes(&app, vec![path.clone()], Vec::new());
+
+    Ok(CreatedNote {
+        path: path.to_string_lossy().to_string(),
+        cursor: cursor.map(|cursor| utf16_len(&content[..cursor])),
+    })
+}
+
+/// Matches `{{name}}` and `{{name:argument}}`
+fn placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][\w-]*)\s*(?::([^}\n]*))?\}\}").unwrap())
+}
+
+fn argument<'a>(caps: &Captures<'a>) -> Option<&'a str> {
+    caps.get(2).map(|arg| arg.as_str().trim())
+}
+
+/// Read a template with its includes expanded; `stack` holds the templates
+/// being expanded, to catch one that includes itself
+fn load(app: &tauri::AppHandle, path: &Path, stack: &mut Vec<PathBuf>) -> Result<String, CommandError> {
+    sandbox::check(app, path)?;
+
+    let canonical = sandbox::resolve(path).unwrap_or_else(|| path.to_path_buf());
+    if stack.contains(&canonical) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Template includes itself: {}", path.display()),
+        )
+        .with_path(path));
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Templates are included too deeply: {}", path.display()),
+        )
+        .with_path(path));
+    }
+
+    let bytes = fs::read(path).context("Failed to read template", path)?;
+    let (content, _) = encoding::decode(&bytes);
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    stack.push(canonical);
+
+    let mut expanded = String::with_capacity(content.len());
+    let mut last = 0;
+    for caps in placeholder().captures_iter(&content) {
+        if &caps[1] != "include" {
+            continue;
+        }
+
+        let name = argument(&caps).unwrap_or_default();
+        sandbox::check_relative_path(name)?;
+
+        let mut included = dir.join(name);
+        if included.extension().is_none() {
+            included.set_extension("md");
+        }
+
+        let whole = caps.get(0).unwrap();
+        expanded.push_str(&content[last..whole.start()]);
+        expanded.push_str(markdown::split_frontmatter(&load(app, &included, stack)?).1);
+        last = whole.end();
+    }
+    expanded.push_str(&content[last..]);
+
+    stack.pop();
+    Ok(expanded)
+}
+
+/// Replace the placeholders of `text`; returns the result and the byte
+/// offset of the first `{{cursor}}`
+fn expand(text: &str, context: &Context) -> (String, Option<usize>) {
+    let mut expanded = String::with_capacity(text.len());
+    let mut cursor = None;
+    let mut last = 0;
+
+    for caps in placeholder().captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        expanded.push_str(&text[last..whole.start()]);
+        last = whole.end();
+
+        let argument = argument(&caps).filter(|arg| !arg.is_empty());
+        match &caps[1] {
+            "date" => expanded.push_str(&format_time(argument.unwrap_or(DEFAULT_DATE_FORMAT), context.now)),
+            "time" => expanded.push_str(&format_time(argument.unwrap_or(DEFAULT_TIME_FORMAT), context.now)),
+            "title" => expanded.push_str(&context.title),
+            "cursor" => {
+                cursor.get_or_insert(expanded.len());
+            }
+            "prompt" => {
+                if let Some(answer) = argument.and_then(|question| context.vars.get(question)) {
+                    expanded.push_str(answer);
+                }
+            }
+            name => match context.vars.get(name) {
+                Some(value) => expanded.push_str(value),
+                None => expanded.push_str(whole.as_str()),
+            },
+        }
+    }
+    expanded.push_str(&text[last..]);
+
+    (expanded, cursor)
+}
+
+/// Format a local time in milliseconds since the Unix epoch with
+/// moment-style tokens
+fn format_time(format: &str, millis: i64) -> String {
+    let day = millis.div_euclid(MILLIS_PER_DAY);
+    let (year, month, day_of_month) = stats::civil_date(day);
+    // 1970-01-01 was a Thursday
+    let weekday = (day + 4).rem_euclid(7) as usize;
+    let seconds = millis.rem_euclid(MILLIS_PER_DAY) / 1000;
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let hour12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+
+    let mut formatted = String::new();
+    let mut rest = format;
+
+    while let Some(c) = rest.chars().next() {
+        // `[text]` is kept as is
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                formatted.push_str(&rest[1..end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+
+        let Some(token) = FORMAT_TOKENS.iter().find(|token| rest.starts_with(**token)) else {
+            formatted.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+
+        let value = match *token {
+            "YYYY" => format!("{:04}", year),
+            "YY" => format!("{:02}", year.rem_euclid(100)),
+            "MMMM" => MONTHS[month as usize - 1].to_string(),
+            "MMM" => MONTHS[month as usize - 1][..3].to_string(),
+            "MM" => format!("{:02}", month),
+            "M" => month.to_string(),
+            "DD" => format!("{:02}", day_of_month),
+            "D" => day_of_month.to_string(),
+            "dddd" => WEEKDAYS[weekday].to_string(),
+            "ddd" => WEEKDAYS[weekday][..3].to_string(),
+            "HH" => format!("{:02}", hour),
+            "H" => hour.to_string(),
+            "hh" => format!("{:02}", hour12),
+            "h" => hour12.to_string(),
+            "mm" => format!("{:02}", minute),
+            "m" => minute.to_string(),
+            "ss" => format!("{:02}", second),
+            "s" => second.to_string(),
+            "A" => if hour < 12 { "AM" } else { "PM" }.to_string(),
+            _ => if hour < 12 { "am" } else { "pm" }.to_string(),
+        };
+        formatted.push_str(&value);
+        rest = &rest[token.len()..];
+    }
+
+    formatted
+}
+
+/// Absolute path of an expanded target, which must stay inside the workspace
+fn target_path(root: &Path, target: &str) -> Result<PathBuf, CommandError> {
+    let target = Path::new(target.trim());
+    let relative = target.strip_prefix(root).unwrap_or(target);
+
+    if relative.as_os_str().is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Target is not a path inside the workspace: {}", target.display()),
+        )
+        .with_path(target));
+    }
+
+    let mut path = root.join(relative);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    Ok(path)
+}
+
+/// First free "name 1.md", "name 2.md"... next to `path`
+fn numbered_path(path: &Path) -> Result<PathBuf, CommandError> {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..=1000)
+        .map(|counter| path.with_file_name(format!("{} {}{}", stem, counter, extension)))
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| CommandError::already_exists(path))
+}
@@ -0,0 +1,173 @@
+// ============================================================================
+// THUMBNAILS
+// ============================================================================
+//
+// Downscaled copies of PNG, JPEG, GIF and WebP attachments, cached in the
+// app cache directory as `<path hash>-<max size>-<mtime>.<ext>`. A file that
+// changed on disk gets a new mtime and so a new entry; the outdated entries
+// for the same path and size are removed when the new one is written.
+//
+// Thumbnails are served through the asset protocol as
+// `inkdown-asset://localhost/<path>?thumbnail=<max size>`; `get_thumbnail`
+// makes one ahead of time and reports its size, e.g. for layout.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+use crate::atomic;
+use crate::error::{CommandError, ErrorCode};
+use crate::sandbox;
+
+/// Folder inside the app cache directory
+const CACHE_DIR: &str = "thumbnails";
+
+/// Formats that are decoded and resized; anything else is served as is
+const SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+const MIN_SIZE: u32 = 16;
+const MAX_SIZE: u32 = 2048;
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Size of the image the asset protocol serves for `?thumbnail=`
+#[derive(Debug, Serialize)]
+pub struct Thumbnail {
+    width: u32,
+    height: u32,
+}
+
+/// Make the thumbnail of an image inside an open workspace, fitting in a
+/// `max_size` × `max_size` box, and return its size
+#[tauri::command]
+pub async fn get_thumbnail(app: tauri::AppHandle, path: String, max_size: u32) -> Result<Thumbnail, CommandError> {
+    let source = PathBuf::from(&path);
+    sandbox::check_workspace(&app, &source)?;
+
+    if !is_supported(&source) {
+        return Err(CommandError::new(ErrorCode::InvalidInput, format!("Unsupported image format: {}", path)).with_path(&source));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let thumbnail = thumbnail_path(&app, &source, max_size)?;
+        let (width, height) = image::image_dimensions(&thumbnail).map_err(|e| e.to_string())?;
+
+        Ok(Thumbnail { width, height })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Whether thumbnails can be made for this file
+pub fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Path of the cached thumbnail for `source`, generating it if needed;
+/// the source itself if it already fits
+pub fn thumbnail_path(app: &tauri::AppHandle, source: &Path, max_size: u32) -> Result<PathBuf, String> {
+    let max_size = max_size.clamp(MIN_SIZE, MAX_SIZE);
+
+    let metadata = fs::metadata(source).map_err(|e| format!("Failed to read image: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(CACHE_DIR);
+
+    // Entries for this path and size; only the one matching the current
+    // mtime is valid
+    let prefix = format!("{}-{}-", path_hash(source), max_size);
+    let stem = format!("{}{:x}", prefix, modified);
+
+    for extension in ["jpg", "png"] {
+        let cached = cache_dir.join(format!("{}.{}", stem, extension));
+        if cached.is_file() {
+            return Ok(cached);
+        }
+    }
+
+    // Only the header is read here, so small images are never decoded
+    let (width, height) = image::image_dimensions(source).map_err(|e| format!("Failed to read image: {}", e))?;
+    if width <= max_size && height <= max_size {
+        return Ok(source.to_path_buf());
+    }
+
+    let thumbnail = decode(source)?.thumbnail(max_size, max_size);
+
+    // JPEG is far smaller for photos; keep PNG where transparency matters
+    let (format, extension, thumbnail) = if thumbnail.color().has_alpha() {
+        (ImageFormat::Png, "png", thumbnail)
+    } else {
+        (ImageFormat::Jpeg, "jpg", DynamicImage::ImageRgb8(thumbnail.to_rgb8()))
+    };
+
+    let mut bytes = Vec::new();
+    if format == ImageFormat::Jpeg {
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY);
+        thumbnail.write_with_encoder(encoder).map_err(|e| e.to_string())?;
+    } else {
+        thumbnail
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .map_err(|e| e.to_string())?;
+    }
+
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("Failed to create thumbnail cache: {}", e))?;
+    remove_outdated(&cache_dir, &prefix);
+
+    let cached = cache_dir.join(format!("{}.{}", stem, extension));
+    atomic::write_atomic(&cached, bytes).map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    Ok(cached)
+}
+
+/// Decode an image, rotated according to its EXIF orientation so phone
+/// photos aren't shown sideways
+fn decode(path: &Path) -> Result<DynamicImage, String> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode image: {}", e))?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+/// Remove cached thumbnails of earlier versions of a file
+fn remove_outdated(cache_dir: &Path, prefix: &str) {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(prefix) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn path_hash(path: &Path) -> String {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+
+    digest[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,257 @@
+// ============================================================================
+// MOVING AND COPYING
+// ============================================================================
+//
+// Shared by `move_path`, `copy_file` and batch operations. What happens
+// when the destination already exists is chosen with `OnConflict`. Moves
+// that `fs::rename` can't do because the destination is on another file
+// system are done as a copy followed by deleting the source. Copies keep
+// the modification and access times of what they copy, so a copied or
+// moved note doesn't look freshly edited.
+
+use std::fs::{self, File, FileTimes};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{CommandError, ErrorCode, IoContext};
+use crate::DirectoryStack;
+
+/// What to do when the destination of a move or copy already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OnConflict {
+    /// Fail with an error
+    Error,
+    /// Replace the existing item
+    Overwrite,
+    /// Leave both items where they are and do nothing
+    Skip,
+    /// Use the first free name of "name (copy).ext", "name (copy 2).ext"...
+    AutoRename,
+    /// Merge a directory into an existing directory; files present on both
+    /// sides, and items that aren't both directories, are auto-renamed
+    MergeDirectories,
+}
+
+/// Where a moved or copied item goes, once conflicts are resolved
+pub enum Target {
+    /// Nothing is in the way
+    Free(PathBuf),
+    /// The destination exists and is left alone
+    Skip,
+    /// The existing item has to be removed first
+    Replace(PathBuf),
+    /// Merge into the existing directory
+    Merge(PathBuf),
+}
+
+/// Decide where `source` goes when moved or copied to `destination`
+pub fn resolve(source: &Path, destination: &Path, on_conflict: OnConflict) -> Result<Target, CommandError> {
+    if !exists(destination) {
+        return Ok(Target::Free(destination.to_path_buf()));
+    }
+
+    let target = match on_conflict {
+        OnConflict::Error => return Err(CommandError::already_exists(destination)),
+        OnConflict::Skip => Target::Skip,
+        OnConflict::Overwrite => {
+            if same_file::is_same_file(source, destination).unwrap_or(false) {
+                return Err(CommandError::new(
+                    ErrorCode::InvalidInput,
+                    format!("Can't replace an item with itself: {}", destination.display()),
+                )
+                .with_path(destination));
+            }
+            Target::Replace(destination.to_path_buf())
+        }
+        OnConflict::MergeDirectories if source.is_dir() && destination.is_dir() => {
+            Target::Merge(destination.to_path_buf())
+        }
+        OnConflict::AutoRename | OnConflict::MergeDirectories => {
+            Target::Free(unique_path(destination, source.is_dir(), exists)?)
+        }
+    };
+
+    Ok(target)
+}
+
+/// Find a free path for a copy of `path`: the path itself if `exists` says
+/// it's free, otherwise "name (copy).ext", then "name (copy 2).ext"... The
+/// extension is only split off for files.
+pub fn unique_path(path: &Path, is_dir: bool, exists: impl Fn(&Path) -> bool) -> Result<PathBuf, String> {
+    if !exists(path) {
+        return Ok(path.to_path_buf());
+    }
+
+    let name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| "Invalid file name".to_string())?;
+    let parent = path.parent().ok_or_else(|| "Invalid parent directory".to_string())?;
+
+    // "notes.md" becomes "notes (copy).md"; ".gitignore" and folders have
+    // no extension
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !is_dir && !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+
+    for counter in 1..=1000 {
+        let suffix = if counter == 1 {
+            "(copy)".to_string()
+        } else {
+            format!("(copy {})", counter)
+        };
+
+        let candidate = parent.join(format!("{} {}{}", stem, suffix, extension));
+        if !exists(&candidate) {
+            return Ok(candidate);
+        }
+    }
+
+    Err("Too many copies already exist".to_string())
+}
+
+/// Move `from` to the free path `to`, copying and deleting when they are
+/// on different file systems
+pub fn move_item(app: &tauri::AppHandle, from: &Path, to: &Path) -> Result<(), CommandError> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(error) = copy_item(app, from, to) {
+                let _ = remove_item(to);
+                return Err(error);
+            }
+            remove_item(from)
+        }
+        Err(e) => Err(CommandError::io("Failed to move", from, e)),
+    }
+}
+
+/// Move the contents of directory `from` into the existing directory `to`,
+/// merging subdirectories and renaming files that exist on both sides
+pub fn merge_into(app: &tauri::AppHandle, from: &Path, to: &Path) -> Result<(), CommandError> {
+    for entry in fs::read_dir(from).context("Failed to read directory", from)? {
+        let entry = entry.context("Failed to read entry", from)?;
+        let source = entry.path();
+        let destination = to.join(entry.file_name());
+
+        if source.is_dir() && destination.is_dir() {
+            merge_into(app, &source, &destination)?;
+        } else {
+            move_item(app, &source, &unique_path(&destination, source.is_dir(), exists)?)?;
+        }
+    }
+
+    fs::remove_dir(from).context("Failed to remove directory", from)
+}
+
+/// Copy a file or a directory tree to `to`, keeping modification times;
+/// files that already exist inside a directory being copied into are kept
+/// and the copy is auto-renamed
+pub fn copy_item(app: &tauri::AppHandle, from: &Path, to: &Path) -> Result<(), CommandError> {
+    if !from.is_dir() {
+        return copy_file_with_times(from, to);
+    }
+
+    fs::create_dir_all(to).context("Failed to create directory", to)?;
+
+    // With the copy on the stack it is skipped when copying a folder into itself
+    let mut stack = DirectoryStack::new(app);
+    stack.enter(to)?;
+    stack.enter(from)?;
+
+    copy_dir_entries(from, to, &mut stack)?;
+    copy_times(from, to);
+    Ok(())
+}
+
+/// Copy the contents of `src`, following symlinks but never into a folder
+/// that is already on `stack` or to anything outside the sandbox
+fn copy_dir_entries(src: &Path, dest: &Path, stack: &mut DirectoryStack) -> Result<(), CommandError> {
+    for entry in fs::read_dir(src).context("Failed to read directory", src)? {
+        let entry = entry.context("Failed to read entry", src)?;
+        let entry_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+
+        if entry_path.is_dir() {
+            // A symlink loop (or the copy itself): nothing new to copy
+            if !stack.enter(&entry_path)? {
+                continue;
+            }
+
+            let dest_path = if exists(&dest_path) && !dest_path.is_dir() {
+                unique_path(&dest_path, true, exists)?
+            } else {
+                dest_path
+            };
+
+            let result = fs::create_dir_all(&dest_path)
+                .context("Failed to create directory", &dest_path)
+                .and_then(|_| copy_dir_entries(&entry_path, &dest_path, stack));
+            stack.leave();
+            result?;
+
+            copy_times(&entry_path, &dest_path);
+        } else if entry_path.exists() {
+            if !stack.allows(&entry_path) {
+                continue;
+            }
+
+            let dest_path = unique_path(&dest_path, false, exists)?;
+            copy_file_with_times(&entry_path, &dest_path)?;
+        } else {
+            // A broken symlink points at nothing to copy
+            continue;
+        }
+    }
+    Ok(())
+}
+
+fn copy_file_with_times(from: &Path, to: &Path) -> Result<(), CommandError> {
+    fs::copy(from, to).context("Failed to copy file", from)?;
+    copy_times(from, to);
+    Ok(())
+}
+
+/// Give `to` the modification and access times of `from`, where the
+/// platform allows it (not for directories on Windows)
+fn copy_times(from: &Path, to: &Path) {
+    let Ok(metadata) = fs::metadata(from) else {
+        return;
+    };
+
+    let mut times = FileTimes::new();
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+
+    let file = if to.is_dir() {
+        File::open(to)
+    } else {
+        File::options().write(true).open(to)
+    };
+    if let Ok(file) = file {
+        let _ = file.set_times(times);
+    }
+}
+
+/// Whether anything, even a broken symlink, is at `path`
+pub fn exists(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+/// Delete a file or a directory tree
+pub fn remove_item(path: &Path) -> Result<(), CommandError> {
+    let result = if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.context("Failed to remove", path)
+}
@@ -0,0 +1,356 @@
+// ============================================================================
+// WORKSPACE TRASH
+// ============================================================================
+//
+// Deleted items are moved into `<workspace>/.trash/<id>/<name>` with a
+// `<workspace>/.trash/<id>.json` sidecar recording where they came from and
+// when they were deleted, so they can be listed, restored or purged later.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic;
+use crate::error::{CommandError, ErrorCode, IoContext};
+use crate::ignore::to_slash_path;
+use crate::sandbox;
+use crate::transfer;
+
+/// Name of the trash directory at the workspace root
+pub const TRASH_DIR: &str = ".trash";
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Sidecar stored next to each trashed item
+#[derive(Debug, Serialize, Deserialize)]
+struct TrashInfo {
+    /// Original path relative to the workspace root, `/`-separated
+    original_path: String,
+    /// Milliseconds since the Unix epoch
+    deleted_at: u64,
+    is_directory: bool,
+}
+
+/// An item in the trash, as returned to the frontend
+#[derive(Debug, Serialize)]
+pub struct TrashEntry {
+    id: String,
+    name: String,
+    /// Absolute path the item will be restored to
+    original_path: String,
+    deleted_at: u64,
+    is_directory: bool,
+}
+
+/// Whether `path` is the trash directory or inside it
+pub fn is_in_trash(workspace_root: &Path, path: &Path) -> bool {
+    path.starts_with(workspace_root.join(TRASH_DIR))
+}
+
+/// Move a file or directory inside `workspace_root` into the trash;
+/// returns the id of the trash item
+pub fn move_to_trash(workspace_root: &Path, path: &Path) -> Result<String, CommandError> {
+    let relative = path
+        .strip_prefix(workspace_root)
+        .map_err(|_| CommandError::access_denied(path))?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| CommandError::invalid_name(&path.to_string_lossy()))?;
+
+    let trash_dir = workspace_root.join(TRASH_DIR);
+    let id = new_id(&trash_dir);
+    let item_dir = trash_dir.join(&id);
+    let sidecar = sidecar_path(&trash_dir, &id);
+
+    let info = TrashInfo {
+        original_path: to_slash_path(relative),
+        deleted_at: crate::now_millis(),
+        is_directory: path.is_dir(),
+    };
+    let info_json = serde_json::to_string_pretty(&info)
+        .map_err(|e| format!("Failed to serialize trash info: {}", e))?;
+
+    fs::create_dir_all(&item_dir).context("Failed to create trash directory", &item_dir)?;
+
+    // Write the sidecar first so a crash never leaves an item we can't restore
+    atomic::write_atomic(&sidecar, info_json).context("Failed to write trash info", &sidecar)?;
+
+    if let Err(e) = fs::rename(path, item_dir.join(name)) {
+        let _ = fs::remove_file(&sidecar);
+        let _ = fs::remove_dir(&item_dir);
+        return Err(CommandError::io("Failed to move to trash", path, e));
+    }
+
+    Ok(id)
+}
+
+/// Outcome of moving one path to the trash with `move_paths_to_trash`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedPath {
+    path: String,
+    /// Id of the trash item; `None` if the path couldn't be moved
+    id: Option<String>,
+    error: Option<CommandError>,
+}
+
+/// Undo `move_to_trash`: put the item back at `path` and forget it
+pub fn take_back(workspace_root: &Path, id: &str, path: &Path) -> Result<(), CommandError> {
+    let trash_dir = workspace_root.join(TRASH_DIR);
+    let info = read_info(&trash_dir, id)?;
+
+    fs::rename(item_path(&trash_dir, id, &info), path).context("Failed to take back from trash", path)?;
+
+    remove_item(&trash_dir, id)
+}
+
+/// List items in the workspace trash, most recently deleted first
+#[tauri::command]
+pub fn list_trash(app: tauri::AppHandle, workspace_root: String) -> Result<Vec<TrashEntry>, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+
+    list_items(&root)
+}
+
+/// Move many files or directories inside a workspace to its trash
+///
+/// Each path is handled on its own: one that can't be moved is reported
+/// with its error and doesn't stop the others. Results are in the order of
+/// `paths`.
+#[tauri::command]
+pub fn move_paths_to_trash(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    paths: Vec<String>,
+) -> Result<Vec<TrashedPath>, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+
+    let mut removed = Vec::new();
+    let results = paths
+        .into_iter()
+        .map(|path| {
+            let item = PathBuf::from(&path);
+            let (id, error) = match trash_one(&app, &root, &item) {
+                Ok(id) => {
+                    removed.push(item);
+                    (Some(id), None)
+                }
+                Err(error) => (None, Some(error)),
+            };
+
+            TrashedPath { path, id, error }
+        })
+        .collect();
+
+    crate::refresh_indexes(&app, Vec::new(), removed);
+    Ok(results)
+}
+
+fn trash_one(app: &tauri::AppHandle, root: &Path, path: &Path) -> Result<String, CommandError> {
+    sandbox::check_link(app, path)?;
+
+    if !path.starts_with(root) || path == root || is_in_trash(root, path) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Path can't be moved to the trash: {}", path.display()),
+        )
+        .with_path(path));
+    }
+    if !transfer::exists(path) {
+        return Err(CommandError::not_found(path));
+    }
+
+    move_to_trash(root, path)
+}
+
+fn list_items(root: &Path) -> Result<Vec<TrashEntry>, CommandError> {
+    let trash_dir = root.join(TRASH_DIR);
+
+    if !trash_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+
+    let entries = fs::read_dir(&trash_dir).context("Failed to read trash directory", &trash_dir)?;
+
+    for entry in entries {
+        let entry = entry.context("Failed to read entry", &trash_dir)?;
+        let path = entry.path();
+
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        // Skip sidecars that are unreadable or whose item has gone missing
+        if let Ok(info) = read_info(&trash_dir, id) {
+            if item_path(&trash_dir, id, &info).exists() {
+                items.push(to_entry(root, id, info));
+            }
+        }
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+
+    Ok(items)
+}
+
+/// Restore an item from the trash to its original location
+///
+/// Missing parent folders are recreated. If something already exists at the
+/// original path, the item is restored as "name (copy).ext" like `copy_file`.
+/// Returns the path the item was restored to.
+#[tauri::command]
+pub fn restore_from_trash(app: tauri::AppHandle, workspace_root: String, id: String) -> Result<String, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    let trash_dir = root.join(TRASH_DIR);
+
+    let info = read_info(&trash_dir, &id)?;
+    let source = item_path(&trash_dir, &id, &info);
+
+    if !transfer::exists(&source) {
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Trash item is missing: {}", id)).with_path(&source));
+    }
+
+    let original = resolve_original_path(&root, &info.original_path)?;
+    let target = transfer::unique_path(&original, info.is_directory, transfer::exists)?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).context("Failed to create parent directories", parent)?;
+    }
+
+    fs::rename(&source, &target).context("Failed to restore from trash", &source)?;
+
+    remove_item(&trash_dir, &id)?;
+
+    let restored = target
+        .to_str()
+        .ok_or_else(|| "Invalid path".to_string())?
+        .to_string();
+
+    crate::refresh_indexes(&app, vec![target], Vec::new());
+    Ok(restored)
+}
+
+/// Permanently delete items from the trash
+///
+/// With `id`, only that item is purged. With `older_than_days`, only items
+/// deleted more than that many days ago are purged. With neither, the whole
+/// trash is emptied. Returns the number of items purged.
+#[tauri::command]
+pub fn purge_trash(
+    app: tauri::AppHandle,
+    workspace_root: String,
+    id: Option<String>,
+    older_than_days: Option<u64>,
+) -> Result<usize, CommandError> {
+    let root = PathBuf::from(&workspace_root);
+    sandbox::check_workspace(&app, &root)?;
+    let trash_dir = root.join(TRASH_DIR);
+
+    if let Some(id) = id {
+        read_info(&trash_dir, &id)?;
+        remove_item(&trash_dir, &id)?;
+        return Ok(1);
+    }
+
+    let cutoff = older_than_days.map(|days| crate::now_millis().saturating_sub(days * MILLIS_PER_DAY));
+
+    let mut purged = 0;
+
+    for entry in list_items(&root)? {
+        if cutoff.is_none_or(|cutoff| entry.deleted_at < cutoff) {
+            remove_item(&trash_dir, &entry.id)?;
+            purged += 1;
+        }
+    }
+
+    Ok(purged)
+}
+
+fn read_info(trash_dir: &Path, id: &str) -> Result<TrashInfo, CommandError> {
+    // Ids come from the frontend; never let one point outside the trash
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(CommandError::new(ErrorCode::InvalidInput, format!("Invalid trash id: {}", id)));
+    }
+
+    let sidecar = sidecar_path(trash_dir, id);
+    let content = fs::read_to_string(&sidecar).context(format!("Failed to read trash info for {}", id), &sidecar)?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        CommandError::new(ErrorCode::Io, format!("Invalid trash info for {}: {}", id, e)).with_path(&sidecar)
+    })
+}
+
+fn remove_item(trash_dir: &Path, id: &str) -> Result<(), CommandError> {
+    let item_dir = trash_dir.join(id);
+
+    if item_dir.exists() {
+        fs::remove_dir_all(&item_dir).context(format!("Failed to delete trash item {}", id), &item_dir)?;
+    }
+
+    let sidecar = sidecar_path(trash_dir, id);
+    fs::remove_file(&sidecar).context(format!("Failed to delete trash info for {}", id), &sidecar)
+}
+
+fn to_entry(root: &Path, id: &str, info: TrashInfo) -> TrashEntry {
+    let name = info
+        .original_path
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    TrashEntry {
+        id: id.to_string(),
+        name,
+        original_path: root.join(&info.original_path).to_string_lossy().to_string(),
+        deleted_at: info.deleted_at,
+        is_directory: info.is_directory,
+    }
+}
+
+fn item_path(trash_dir: &Path, id: &str, info: &TrashInfo) -> PathBuf {
+    let name = info.original_path.rsplit('/').next().unwrap_or_default();
+    trash_dir.join(id).join(name)
+}
+
+fn sidecar_path(trash_dir: &Path, id: &str) -> PathBuf {
+    trash_dir.join(format!("{}.json", id))
+}
+
+/// Join a sidecar's relative path onto the root, refusing anything that
+/// would escape the workspace (a hand-edited sidecar could contain `..`)
+fn resolve_original_path(root: &Path, relative: &str) -> Result<PathBuf, CommandError> {
+    let relative = Path::new(relative);
+
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Invalid original path in trash info: {}", relative.display()),
+        ));
+    }
+
+    Ok(root.join(relative))
+}
+
+fn new_id(trash_dir: &Path) -> String {
+    loop {
+        let id = format!("{}-{}", crate::now_millis(), ID_COUNTER.fetch_add(1, Ordering::Relaxed));
+        if !trash_dir.join(&id).exists() {
+            return id;
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// ============================================================================
+// WORKSPACE FILE WATCHER
+// ============================================================================
+//
+// Watches a workspace root recursively and forwards debounced file system
+// changes to the webview as `workspace-file-changes` events, so edits made
+// outside the app (git, Syncthing, other editors) are noticed right away.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache};
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::error::CommandError;
+use crate::ignore::{self, IgnoreRules};
+use crate::{atomic, sandbox, trash};
+
+/// Name of the Tauri event carrying a batch of workspace changes
+pub const FILE_CHANGES_EVENT: &str = "workspace-file-changes";
+
+/// Default debounce window when the caller doesn't provide one
+const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// Kind of change, matching `FileChangeEvent['type']` in packages/core
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Create,
+    Modify,
+    Delete,
+    Rename,
+}
+
+/// A single file change, shaped like `FileChangeEvent` in packages/core
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    #[serde(rename = "type")]
+    pub kind: FileChangeKind,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub is_directory: bool,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Payload of a `workspace-file-changes` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeBatch {
+    pub workspace: String,
+    pub changes: Vec<FileChange>,
+}
+
+/// Active watchers keyed by workspace root
+#[derive(Default)]
+pub struct WatcherState {
+    watchers: Mutex<HashMap<PathBuf, Debouncer<RecommendedWatcher, RecommendedCache>>>,
+}
+
+/// Start watching a workspace root recursively
+///
+/// Changes to paths matched by the workspace's `.gitignore` /
+/// `.inkdownignore` or by `ignore_patterns` are not reported.
+#[tauri::command]
+pub fn watch_workspace(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WatcherState>,
+    path: String,
+    debounce_ms: Option<u64>,
+    ignore_patterns: Option<Vec<String>>,
+) -> Result<(), CommandError> {
+    let root = PathBuf::from(&path);
+    sandbox::check_workspace(&app, &root)?;
+
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", path).into());
+    }
+
+    let mut watchers = state
+        .watchers
+        .lock()
+        .map_err(|_| "Watcher state is poisoned".to_string())?;
+
+    // Watching the same root twice would emit every event twice
+    if watchers.contains_key(&root) {
+        return Ok(());
+    }
+
+    let workspace = path.clone();
+    let workspace_root = root.clone();
+    let timeout = Duration::from_millis(debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS));
+    let ignore_patterns = ignore_patterns.unwrap_or_default();
+    let load_rules = move |root: &Path| IgnoreRules::load(root, &ignore_patterns).include_hidden(true);
+    let mut rules = load_rules(&root);
+
+    let mut debouncer = new_debouncer(timeout, None, move |result: DebounceEventResult| {
+        match result {
+            Ok(events) => {
+                // Pick up edits to the ignore files before filtering
+                let ignore_file_changed = events
+                    .iter()
+                    .flat_map(|event| &event.paths)
+                    .any(|path| ignore::is_ignore_file(&workspace_root, path));
+                if ignore_file_changed {
+                    rules = load_rules(&workspace_root);
+                }
+
+                let changes: Vec<FileChange> = events
+                    .iter()
+                    .filter_map(|event| to_file_change(&workspace_root, &rules, event))
+                    .collect();
+                if changes.is_empty() {
+                    return;
+                }
+
+                refresh_indexes(&app, &changes);
+
+                let batch = FileChangeBatch {
+                    workspace: workspace.clone(),
+                    changes,
+                };
+                if let Err(e) = app.emit(FILE_CHANGES_EVENT, batch) {
+                    eprintln!("[watcher] Failed to emit file changes: {}", e);
+                }
+            }
+            Err(errors) => {
+                for e in errors {
+                    eprintln!("[watcher] Watch error in {}: {}", workspace, e);
+                }
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    debouncer
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", path, e))?;
+
+    watchers.insert(root, debouncer);
+    Ok(())
+}
+
+/// Stop watching a workspace root (no-op if it isn't watched)
+#[tauri::command]
+pub fn unwatch_workspace(state: tauri::State<'_, WatcherState>, path: String) -> Result<(), CommandError> {
+    let debouncer = state
+        .watchers
+        .lock()
+        .map_err(|_| "Watcher state is poisoned".to_string())?
+        .remove(&PathBuf::from(&path));
+
+    // Dropping the debouncer would also stop it, but stopping explicitly
+    // flushes the background thread before we return
+    if let Some(debouncer) = debouncer {
+        debouncer.stop();
+    }
+
+    Ok(())
+}
+
+/// Map a debounced notify event to the payload sent to the frontend
+fn to_file_change(root: &Path, rules: &IgnoreRules, event: &DebouncedEvent) -> Option<FileChange> {
+    let timestamp = crate::now_millis();
+
+    let (kind, path, old_path) = match event.kind {
+        EventKind::Create(_) => (FileChangeKind::Create, event.paths.first()?, None),
+        EventKind::Remove(_) => (FileChangeKind::Delete, event.paths.first()?, None),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            let [from, to] = event.paths.as_slice() else {
+                return None;
+            };
+            if atomic::is_temp_file(from) {
+                // An atomic save renames its temp file over the target
+                (FileChangeKind::Modify, to, None)
+            } else if trash::is_in_trash(root, to) {
+                // Moved into the trash: a delete as far as the workspace is concerned
+                (FileChangeKind::Delete, from, None)
+            } else if trash::is_in_trash(root, from) {
+                // Restored from the trash
+                (FileChangeKind::Create, to, None)
+            } else {
+                (FileChangeKind::Rename, to, Some(from))
+            }
+        }
+        // Only one side of the rename was inside the workspace
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            (FileChangeKind::Delete, event.paths.first()?, None)
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+            (FileChangeKind::Create, event.paths.first()?, None)
+        }
+        // Metadata-only changes (permissions, atime) don't change content
+        EventKind::Modify(ModifyKind::Metadata(_)) => return None,
+        EventKind::Modify(_) => (FileChangeKind::Modify, event.paths.first()?, None),
+        _ => return None,
+    };
+
+    if atomic::is_temp_file(path) || trash::is_in_trash(root, path) {
+        return None;
+    }
+
+    let is_directory = path.is_dir();
+    let is_ignored = |path: &Path, is_directory: bool| {
+        path.strip_prefix(root)
+            .is_ok_and(|relative| rules.is_path_ignored(relative, is_directory))
+    };
+
+    if is_ignored(path, is_directory) {
+        // Renamed into an ignored location: gone as far as the app is concerned
+        return match old_path {
+            Some(old_path) if !is_ignored(old_path, is_directory) => Some(FileChange {
+                kind: FileChangeKind::Delete,
+                path: path_to_string(old_path)?,
+                old_path: None,
+                is_directory,
+                timestamp,
+            }),
+            _ => None,
+        };
+    }
+
+    // Renamed out of an ignored location: new as far as the app is concerned
+    let (kind, old_path) = match old_path {
+        Some(old_path) if is_ignored(old_path, is_directory) => (FileChangeKind::Create, None),
+        old_path => (kind, old_path),
+    };
+
+    Some(FileChange {
+        kind,
+        path: path_to_string(path)?,
+        old_path: old_path.and_then(|p| path_to_string(p)),
+        is_directory,
+        timestamp,
+    })
+}
+
+/// Keep the backend indexes in step with changes made outside the app
+fn refresh_indexes(app: &tauri::AppHandle, changes: &[FileChange]) {
+    let mut updated = Vec::new();
+    let mut removed = Vec::new();
+
+    for change in changes {
+        match change.kind {
+            FileChangeKind::Create | FileChangeKind::Modify => updated.push(PathBuf::from(&change.path)),
+            FileChangeKind::Delete => removed.push(PathBuf::from(&change.path)),
+            FileChangeKind::Rename => {
+                updated.push(PathBuf::from(&change.path));
+                removed.extend(change.old_path.as_ref().map(PathBuf::from));
+            }
+        }
+    }
+
+    crate::refresh_indexes(app, updated, removed);
+}
+
+fn path_to_string(path: &Path) -> Option<String> {
+    path.to_str().map(|s| s.to_string())
+}
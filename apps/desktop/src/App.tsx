@@ -15,7 +15,7 @@ import {
 } from '@inkdown/ui';
 import { invoke } from '@tauri-apps/api/core';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
-import { ask, open as openDialog } from '@tauri-apps/plugin-dialog';
+import { ask } from '@tauri-apps/plugin-dialog';
 import { revealItemInDir } from '@tauri-apps/plugin-opener';
 import type { SelectedItem, SortOrder } from './components/FileExplorer';
 import { FileExplorer } from './components/FileExplorer';
@@ -153,9 +153,9 @@ const AppContent: React.FC = () => {
                     // Verify workspace still exists
                     const exists = await app.fileSystemManager.exists(workspace);
                     if (exists) {
+                        // Sync workspace path to core before anything reads from it
+                        await app.fileSystemManager.setWorkspacePath(workspace);
                         setRootPath(workspace);
-                        // Sync workspace path to core
-                        app.fileSystemManager.setWorkspacePath(workspace);
 
                         // Load expanded dirs from config
                         if (config?.expandedDirs) {
@@ -319,10 +319,9 @@ const AppContent: React.FC = () => {
             const previousPath = rootPath;
             const isWorkspaceChange = previousPath && previousPath !== path;
             
-            setRootPath(path);
-
             // Sync workspace path to core
-            app.fileSystemManager.setWorkspacePath(path);
+            await app.fileSystemManager.setWorkspacePath(path);
+            setRootPath(path);
             await app.workspace.refreshFileTree();
 
             // Reset expanded dirs when changing workspace
@@ -414,15 +413,13 @@ const AppContent: React.FC = () => {
                 name: 'Open Workspace',
                 hotkey: ['Mod', 'Shift', 'O'],
                 callback: async () => {
-                    const selected = await openDialog({
-                        directory: true,
-                        multiple: false,
+                    // The backend dialog grants access to the picked folder
+                    const selected = await app.dialog.showOpenFolderDialog({
                         title: 'Select Workspace Folder',
                     });
 
                     if (selected) {
-                        const path = selected as string;
-                        await handleWorkspaceSelected(path);
+                        await handleWorkspaceSelected(selected);
                     }
                 },
             },
@@ -528,7 +525,10 @@ const AppContent: React.FC = () => {
     const loadFiles = useCallback(async () => {
         if (!rootPath) return;
         try {
-            const fileTree = await app.fileSystemManager.readDirectory(rootPath, true);
+            const fileTree = await app.fileSystemManager.readDirectory(rootPath, true, {
+                workspaceRoot: rootPath,
+                respectIgnoreFiles: true,
+            });
             setFiles(fileTree);
         } catch (error: any) {
             console.error('Failed to load files:', error);
@@ -597,19 +597,18 @@ const AppContent: React.FC = () => {
     }, [app, loadFiles]);
 
     const handleOpenDialog = useCallback(async (): Promise<string | null> => {
-        const selected = await openDialog({
-            directory: true,
-            multiple: false,
+        // The backend dialog grants access to the picked folder
+        const selected = await app.dialog.showOpenFolderDialog({
             title: 'Select Workspace Folder',
         });
 
         // If a directory was selected, switch to it
         if (selected) {
-            await handleWorkspaceSelected(selected as string);
+            await handleWorkspaceSelected(selected);
         }
 
-        return selected as string | null;
-    }, [handleWorkspaceSelected]);
+        return selected;
+    }, [app, handleWorkspaceSelected]);
 
     const handleCreateFile = useCallback(
         async (filePath: string) => {
@@ -805,7 +804,7 @@ const AppContent: React.FC = () => {
             if (items.length === 1) {
                 const item = items[0];
                 title = 'Delete';
-                message = `Are you sure you want to delete "${item.name}"?\n\nThis action cannot be undone.`;
+                message = `Are you sure you want to delete "${item.name}"?\n\nIt will be moved to the workspace trash.`;
             } else {
                 title = `Delete ${items.length} Items`;
                 const itemNames = items
@@ -813,7 +812,7 @@ const AppContent: React.FC = () => {
                     .map((i) => `• ${i.name}`)
                     .join('\n');
                 const moreText = items.length > 5 ? `\n...and ${items.length - 5} more` : '';
-                message = `Are you sure you want to delete the following items?\n\n${itemNames}${moreText}\n\nThis action cannot be undone.`;
+                message = `Are you sure you want to delete the following items?\n\n${itemNames}${moreText}\n\nThey will be moved to the workspace trash.`;
             }
 
             return await ask(message, {
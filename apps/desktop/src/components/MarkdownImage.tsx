@@ -2,6 +2,9 @@ import type { App } from '@inkdown/core';
 import type React from 'react';
 import { useEffect, useState } from 'react';
 
+/** Largest width/height of the images shown in the preview, in pixels */
+const THUMBNAIL_SIZE = 1600;
+
 interface MarkdownImageProps {
     src?: string;
     alt?: string;
@@ -112,10 +115,12 @@ export const MarkdownImage: React.FC<MarkdownImageProps> = ({
                 const resolvedPath = resolveImagePath(src, currentFilePath);
                 console.log('[MarkdownImage] Resolved path:', resolvedPath);
 
-                // Convert to data URL
-                const dataUrl = await convertFileToDataUrl(app, resolvedPath);
-                console.log('[MarkdownImage] Data URL created, length:', dataUrl.length);
-                setImageSrc(dataUrl);
+                // Stream a cached thumbnail from the asset protocol when
+                // available, otherwise fall back to a data URL
+                const imageUrl =
+                    app.fileSystemManager.getThumbnailUrl(resolvedPath, THUMBNAIL_SIZE) ??
+                    (await convertFileToDataUrl(app, resolvedPath));
+                setImageSrc(imageUrl);
             } catch (err) {
                 console.error('[MarkdownImage] Failed to load image:', err);
                 setError(`Failed to load image: ${src}`);
@@ -190,6 +190,12 @@ export interface FilesConfig {
     newAttachmentsLocation: 'root' | 'folder';
     /** Custom folder path for new attachments (relative to workspace) */
     newAttachmentsFolder: string;
+    /** Rewrite links to a note or attachment when it is renamed or moved */
+    updateLinksOnMove: boolean;
+    /** Folder holding note templates (relative to workspace) */
+    templatesFolder: string;
+    /** Template new notes are created from ('' for empty notes) */
+    newNoteTemplate: string;
 }
 
 interface TabInfo {
@@ -276,8 +282,12 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
         newNotesFolder: '',
         newAttachmentsLocation: 'root',
         newAttachmentsFolder: '',
+        updateLinksOnMove: true,
+        templatesFolder: 'Templates',
+        newNoteTemplate: '',
     });
     const [workspaceFolders, setWorkspaceFolders] = useState<string[]>([]);
+    const [templateNames, setTemplateNames] = useState<string[]>([]);
 
     // Window Settings State
     const [windowConfig, setWindowConfig] = useState<WindowConfig>({
@@ -305,7 +315,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
             try {
                 const config = await app.configManager.loadConfig<FilesConfig>('files');
                 if (config) {
-                    setFilesConfig(config);
+                    setFilesConfig((prev) => ({ ...prev, ...config }));
                 }
             } catch (error: any) {
                 console.error('Failed to load files config:', error);
@@ -346,8 +356,18 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
             }
         };
 
+        const loadTemplateNames = async () => {
+            try {
+                const templates = await app.filesConfigManager.listTemplates();
+                setTemplateNames(templates.map((template) => template.name));
+            } catch (error: any) {
+                console.error('Failed to load templates:', error);
+            }
+        };
+
         loadFilesConfig();
         loadWorkspaceFolders();
+        loadTemplateNames();
     }, [app]);
 
     // Load window config
@@ -1236,6 +1256,46 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
                                             />
                                         </Setting>
                                     )}
+
+                                    <Setting
+                                        name="Template folder location"
+                                        description="Relative path from workspace root to the folder holding note templates"
+                                    >
+                                        <AutocompleteInput
+                                            value={filesConfig.templatesFolder}
+                                            onChange={(value) =>
+                                                handleFilesConfigChange('templatesFolder', value)
+                                            }
+                                            suggestions={workspaceFolders}
+                                            placeholder="Templates"
+                                        />
+                                    </Setting>
+
+                                    <Setting
+                                        name="Template for new notes"
+                                        description="New notes start from this template; leave empty for blank notes"
+                                    >
+                                        <AutocompleteInput
+                                            value={filesConfig.newNoteTemplate}
+                                            onChange={(value) =>
+                                                handleFilesConfigChange('newNoteTemplate', value)
+                                            }
+                                            suggestions={templateNames}
+                                            placeholder="None"
+                                        />
+                                    </Setting>
+
+                                    <Setting
+                                        name="Automatically update internal links"
+                                        description="Rewrite links to a note or attachment when it is renamed or moved"
+                                    >
+                                        <Toggle
+                                            checked={filesConfig.updateLinksOnMove}
+                                            onChange={(checked) =>
+                                                handleFilesConfigChange('updateLinksOnMove', checked)
+                                            }
+                                        />
+                                    </Setting>
                                 </div>
                             )}
 
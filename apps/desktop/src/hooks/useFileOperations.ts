@@ -1,7 +1,7 @@
 import type { App } from '@inkdown/core';
 import { invoke } from '@tauri-apps/api/core';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
-import { ask, open as openDialog } from '@tauri-apps/plugin-dialog';
+import { ask } from '@tauri-apps/plugin-dialog';
 import { revealItemInDir } from '@tauri-apps/plugin-opener';
 import { useCallback } from 'react';
 import type { SelectedItem } from '../components/FileExplorer';
@@ -198,22 +198,13 @@ export const useFileOperations = (
 
     const handleOpenDialog = useCallback(async (): Promise<string | null> => {
         try {
-            const result = await openDialog({
-                directory: true,
-                multiple: false,
-                title: 'Select Folder',
-            });
-
-            if (typeof result === 'string') {
-                return result;
-            }
-
-            return null;
+            // The backend dialog grants access to the picked folder
+            return await app.dialog.showOpenFolderDialog({ title: 'Select Folder' });
         } catch (error: any) {
             console.error('Failed to open dialog:', error);
             return null;
         }
-    }, []);
+    }, [app]);
 
     return {
         handleCreateFile,
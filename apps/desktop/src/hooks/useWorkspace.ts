@@ -28,9 +28,9 @@ export const useWorkspace = (app: App) => {
                     // Verify workspace still exists
                     const exists = await app.fileSystemManager.exists(workspace);
                     if (exists) {
+                        // Sync workspace path to core before anything reads from it
+                        await app.fileSystemManager.setWorkspacePath(workspace);
                         setRootPath(workspace);
-                        // Sync workspace path to core
-                        app.fileSystemManager.setWorkspacePath(workspace);
                     }
                 }
 
@@ -62,8 +62,8 @@ export const useWorkspace = (app: App) => {
 
     const handleWorkspaceSelected = useCallback(
         async (path: string) => {
+            await app.fileSystemManager.setWorkspacePath(path);
             setRootPath(path);
-            app.fileSystemManager.setWorkspacePath(path);
 
             // Save to config
             const config = await app.configManager.loadConfig<AppConfig>('app');
@@ -384,4 +384,99 @@ export class ConfirmModal extends Modal {
     }
 }
 
+/**
+ * PromptModal - Asks one or more questions and returns the answers,
+ * keyed by question
+ */
+export class PromptModal extends Modal {
+    private questions: string[] = [];
+    private answers: Record<string, string> = {};
+    private submitText = 'Submit';
+    private submitted = false;
+    private submitCallback?: (answers: Record<string, string>) => void;
+    private cancelCallback?: () => void;
+
+    constructor(app: App) {
+        super(app);
+        this.containerEl.addClass('prompt-modal');
+    }
+
+    setQuestions(questions: string[]): this {
+        this.questions = questions;
+        return this;
+    }
+
+    setSubmitText(text: string): this {
+        this.submitText = text;
+        return this;
+    }
+
+    onSubmit(callback: (answers: Record<string, string>) => void): this {
+        this.submitCallback = callback;
+        return this;
+    }
+
+    /**
+     * Called when the modal is closed without submitting
+     */
+    onCancel(callback: () => void): this {
+        this.cancelCallback = callback;
+        return this;
+    }
+
+    onOpen(): void {
+        const { contentEl } = this;
+        contentEl.empty();
+        this.submitted = false;
+        this.answers = Object.fromEntries(this.questions.map((question) => [question, '']));
+
+        const inputs = this.questions.map((question) => {
+            const fieldEl = contentEl.createDiv({ cls: 'prompt-modal-field' });
+            fieldEl.createEl('label', { text: question });
+
+            const inputEl = fieldEl.createEl('input', { attr: { type: 'text' } });
+            inputEl.addEventListener('input', () => {
+                this.answers[question] = inputEl.value;
+            });
+            inputEl.addEventListener('keydown', (evt) => {
+                if (evt.key === 'Enter') {
+                    evt.preventDefault();
+                    this.submit();
+                }
+            });
+            return inputEl;
+        });
+
+        // Buttons
+        const buttonContainer = contentEl.createDiv({ cls: 'modal-button-container' });
+
+        const cancelBtn = buttonContainer.createEl('button', {
+            cls: 'mod-cancel',
+            text: 'Cancel',
+        });
+        cancelBtn.addEventListener('click', () => this.close());
+
+        const submitBtn = buttonContainer.createEl('button', {
+            cls: 'mod-cta',
+            text: this.submitText,
+        });
+        submitBtn.addEventListener('click', () => this.submit());
+
+        inputs[0]?.focus();
+    }
+
+    onClose(): void {
+        this.contentEl.empty();
+        if (!this.submitted) {
+            this.cancelCallback?.();
+        }
+    }
+
+    private submit(): void {
+        this.submitted = true;
+        this.submitCallback?.({ ...this.answers });
+        this.close();
+    }
+}
+
 export default Modal;
@@ -4,6 +4,8 @@
  * Custom error types for consistent error handling across the application
  */
 
+import type { NativeError } from '../native/types';
+
 /**
  * Base application error class
  */
@@ -30,8 +32,8 @@ export class AppError extends Error {
 export class FileSystemError extends AppError {
     public readonly path: string;
 
-    constructor(message: string, path: string, code = 'FILESYSTEM_ERROR') {
-        super(message, code);
+    constructor(message: string, path: string, code = 'FILESYSTEM_ERROR', metadata?: Record<string, any>) {
+        super(message, code, metadata);
         this.name = 'FileSystemError';
         this.path = path;
     }
@@ -87,6 +89,33 @@ export function isAppError(error: unknown): error is AppError {
     return error instanceof AppError;
 }
 
+/**
+ * Type guard for the `{ code, message, path, details }` value native
+ * commands reject with
+ */
+export function isNativeError(error: unknown): error is NativeError {
+    if (typeof error !== 'object' || error === null) {
+        return false;
+    }
+
+    const { code, message } = error as Record<string, unknown>;
+    return typeof code === 'string' && typeof message === 'string';
+}
+
+/**
+ * Convert the rejection value of a native command to an AppError. Errors
+ * about a path become FileSystemErrors; `details` becomes the metadata.
+ */
+export function fromNativeError(error: NativeError): AppError {
+    const metadata = error.details ?? undefined;
+
+    if (error.path) {
+        return new FileSystemError(error.message, error.path, error.code, metadata);
+    }
+
+    return new AppError(error.message, error.code, metadata);
+}
+
 /**
  * Convert any error-like value to AppError
  */
@@ -101,6 +130,11 @@ export function toAppError(error: unknown): AppError {
         return new AppError(error.message, 'UNKNOWN_ERROR');
     }
 
+    // Native command rejection
+    if (isNativeError(error)) {
+        return fromNativeError(error);
+    }
+
     // String
     if (typeof error === 'string') {
         return new AppError(error, 'UNKNOWN_ERROR');
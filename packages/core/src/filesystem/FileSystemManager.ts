@@ -1,4 +1,49 @@
-import { native } from '../native';
+import {
+    type CreatedNote,
+    type DiffLine,
+    type DirectoryPage,
+    type DuplicateOptions,
+    type DuplicateReport,
+    type FileChunk,
+    type FileHash,
+    type FileMetadata,
+    type FileOperation,
+    type FileOperationsReport,
+    type FileRange,
+    type FileStreamSummary,
+    type FileVersion,
+    type GrepFileResult,
+    type GrepOptions,
+    type GrepSummary,
+    type HashAlgorithm,
+    type HashContent,
+    type LinkEntry,
+    type LinkRewriteReport,
+    type MergeDuplicatesReport,
+    type MoveOptions,
+    type MoveResult,
+    type NativeFileChange,
+    type OrphanOptions,
+    type OrphanReport,
+    type OnConflict,
+    native,
+    type PathStat,
+    type PruneHistoryOptions,
+    type PruneHistoryReport,
+    type ReadDirectoryOptions,
+    type ReadFileOptions,
+    type ReadFileResult,
+    type SearchResult,
+    type TemplateInfo,
+    type TemplateOptions,
+    type ThumbnailInfo,
+    type TrashEntry,
+    type TrashedPath,
+    type WatchWorkspaceOptions,
+    type WorkspaceStats,
+    type WorkspaceStatsOptions,
+    type WriteFileOptions,
+} from '../native';
 
 export interface FileNode {
     name: string;
@@ -7,6 +52,8 @@ export interface FileNode {
     children?: FileNode[];
     size?: number;
     modified?: number;
+    isSymlink?: boolean;
+    symlinkTarget?: string;
 }
 
 /**
@@ -28,15 +75,62 @@ export class FileSystemManager {
     /**
      * Read directory structure
      */
-    async readDirectory(path: string, recursive = false): Promise<FileNode[]> {
+    async readDirectory(
+        path: string,
+        recursive = false,
+        options?: ReadDirectoryOptions,
+    ): Promise<FileNode[]> {
         try {
-            return await native.fs.readDirectory(path, recursive);
+            return await native.fs.readDirectory(path, recursive, options);
         } catch (error: any) {
             console.error('Failed to read directory:', error);
             throw error;
         }
     }
 
+    /**
+     * Read one page of a folder's direct children
+     * Falls back to slicing a full listing when the platform can't paginate
+     */
+    async readDirectoryPage(
+        path: string,
+        offset: number,
+        limit: number,
+        options?: ReadDirectoryOptions,
+    ): Promise<DirectoryPage> {
+        try {
+            if (native.fs.readDirectoryPage) {
+                return await native.fs.readDirectoryPage(path, offset, limit, options);
+            }
+
+            const nodes = await native.fs.readDirectory(path, false, options);
+            const entries = nodes.slice(offset, offset + limit);
+            return {
+                entries,
+                total: nodes.length,
+                hasMore: offset + entries.length < nodes.length,
+            };
+        } catch (error: any) {
+            console.error('Failed to read directory:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Patterns from the open workspace's `.gitignore` and `.inkdownignore`
+     * Returns no patterns when the platform can't read ignore files
+     */
+    async readIgnorePatterns(): Promise<string[]> {
+        if (!this.workspacePath || !native.fs.readIgnorePatterns) return [];
+
+        try {
+            return await native.fs.readIgnorePatterns(this.workspacePath);
+        } catch (error: any) {
+            console.error('Failed to read ignore patterns:', error);
+            return [];
+        }
+    }
+
     /**
      * Read file content
      */
@@ -49,6 +143,20 @@ export class FileSystemManager {
         }
     }
 
+    /**
+     * Read file content along with its on-disk metadata
+     * Pass the metadata back to writeFile to detect concurrent edits
+     * Rejects with a FileTooLargeError for files above `options.maxSize`
+     */
+    async readFileWithMetadata(path: string, options?: ReadFileOptions): Promise<ReadFileResult> {
+        try {
+            return await native.fs.readFileWithMetadata(path, options);
+        } catch (error: any) {
+            console.error('Failed to read file:', error);
+            throw error;
+        }
+    }
+
     /**
      * Read binary file content
      * Returns Uint8Array for binary files like images
@@ -62,17 +170,86 @@ export class FileSystemManager {
         }
     }
 
+    /**
+     * Decode a byte range of a text file, e.g. to page through a file that
+     * is too large to open
+     */
+    async readFileRange(path: string, offset: number, length: number): Promise<FileRange> {
+        if (!native.fs.readFileRange) {
+            throw new Error('Ranged reads are not available');
+        }
+        return native.fs.readFileRange(path, offset, length);
+    }
+
+    /**
+     * Read a text file in chunks for a read-only preview, passing each
+     * chunk to `onChunk` in order
+     * Returns null if the platform can't stream files
+     */
+    async streamFile(
+        streamId: string,
+        path: string,
+        onChunk: (chunk: FileChunk) => void,
+        chunkSize?: number,
+    ): Promise<FileStreamSummary | null> {
+        if (!native.fs.streamFile) return null;
+        return native.fs.streamFile(streamId, path, onChunk, chunkSize);
+    }
+
+    /**
+     * Cancel a running file stream
+     */
+    async cancelFileStream(streamId: string): Promise<void> {
+        await native.fs.cancelFileStream?.(streamId);
+    }
+
+    /**
+     * URL to load a workspace file from in img/audio/video/iframe elements,
+     * streamed by the platform instead of read into memory
+     * Returns null if the platform can't serve files by URL
+     */
+    getAssetUrl(path: string): string | null {
+        return native.fs.getAssetUrl?.(path) ?? null;
+    }
+
+    /**
+     * URL of a downscaled copy of an image, cached by the platform and
+     * regenerated when the image changes
+     * Returns null if the platform can't make thumbnails
+     */
+    getThumbnailUrl(path: string, maxSize: number): string | null {
+        return native.fs.getThumbnailUrl?.(path, maxSize) ?? null;
+    }
+
+    /**
+     * Generate (or reuse) the cached thumbnail of an image and return its
+     * URL and size
+     * Returns null if the platform can't make thumbnails
+     */
+    async getThumbnail(path: string, maxSize: number): Promise<ThumbnailInfo | null> {
+        if (!native.fs.getThumbnail) return null;
+        return native.fs.getThumbnail(path, maxSize);
+    }
+
     /**
      * Write file content
+     * @param options - Optional expected mtime/hash; rejects with a
+     * FileConflictError if the file changed on disk since it was read
      */
-    async writeFile(path: string, content: string): Promise<void> {
+    async writeFile(
+        path: string,
+        content: string,
+        options?: WriteFileOptions,
+    ): Promise<FileMetadata> {
         try {
-            await native.fs.writeFile(path, content);
+            const metadata = await native.fs.writeFile(path, content, options);
 
             // Emit file:created event through workspace
             if (this._app?.workspace) {
                 this._app.workspace.trigger('file:created', path);
             }
+
+            return metadata;
         } catch (error: any) {
             console.error('Failed to write file:', error);
             throw error;
@@ -134,10 +311,12 @@ export class FileSystemManager {
 
     /**
      * Rename a file or directory
+     * Links to it are rewritten when `updateLinksOnMove` is enabled
+     * @returns Notes whose links were rewritten
      */
-    async rename(oldPath: string, newPath: string): Promise<void> {
+    async rename(oldPath: string, newPath: string): Promise<LinkRewriteReport> {
         try {
-            await native.fs.rename(oldPath, newPath);
+            return await native.fs.rename(oldPath, newPath, await this.moveOptions());
         } catch (error: any) {
             console.error('Failed to rename:', error);
             throw error;
@@ -146,28 +325,145 @@ export class FileSystemManager {
 
     /**
      * Delete a file or directory
+     * Items inside the open workspace go to its trash unless `permanent` is set
      */
-    async delete(path: string): Promise<void> {
+    async delete(path: string, permanent = false): Promise<void> {
         try {
-            await native.fs.delete(path);
+            await native.fs.delete(path, {
+                workspaceRoot: this.workspacePath ?? undefined,
+                permanent,
+            });
         } catch (error: any) {
             console.error('Failed to delete:', error);
             throw error;
         }
     }
 
+    /**
+     * List items in the current workspace's trash
+     */
+    async listTrash(): Promise<TrashEntry[]> {
+        if (!this.workspacePath) return [];
+        return native.fs.listTrash(this.workspacePath);
+    }
+
+    /**
+     * Restore an item from the trash
+     * @returns Path the item was restored to
+     */
+    async restoreFromTrash(id: string): Promise<string> {
+        if (!this.workspacePath) {
+            throw new Error('No workspace is open');
+        }
+        return native.fs.restoreFromTrash(this.workspacePath, id);
+    }
+
+    /**
+     * Permanently delete one item from the trash, or empty it
+     */
+    async purgeTrash(id?: string): Promise<number> {
+        if (!this.workspacePath) return 0;
+        return native.fs.purgeTrash(this.workspacePath, { id });
+    }
+
+    /**
+     * Move many items in the open workspace to its trash
+     * Returns null when the platform has no bulk trash
+     */
+    async movePathsToTrash(paths: string[]): Promise<TrashedPath[] | null> {
+        if (!this.workspacePath) {
+            throw new Error('No workspace is open');
+        }
+        if (!native.fs.movePathsToTrash) return null;
+        return native.fs.movePathsToTrash(this.workspacePath, paths);
+    }
+
+    /**
+     * Purge trash items older than the configured retention period
+     */
+    private async purgeExpiredTrash(workspacePath: string): Promise<void> {
+        try {
+            const config = await this._app?.filesConfigManager?.getConfig();
+            const retentionDays = config?.trashRetentionDays ?? 0;
+            if (retentionDays > 0) {
+                await native.fs.purgeTrash(workspacePath, { olderThanDays: retentionDays });
+            }
+        } catch (error: any) {
+            console.error('Failed to purge expired trash:', error);
+        }
+    }
+
     /**
      * Move a file or directory
+     * Links to it are rewritten when `updateLinksOnMove` is enabled
+     * @param onConflict - What to do if the destination exists (default: fail)
+     * @returns Where the item ended up and the notes whose links were rewritten
      */
-    async move(source: string, destination: string): Promise<void> {
+    async move(source: string, destination: string, onConflict?: OnConflict): Promise<MoveResult> {
         try {
-            await native.fs.move(source, destination);
+            return await native.fs.move(source, destination, {
+                ...(await this.moveOptions()),
+                onConflict,
+            });
         } catch (error: any) {
             console.error('Failed to move:', error);
             throw error;
         }
     }
 
+    /**
+     * Copy a file or directory, keeping modification times
+     * @param onConflict - What to do if the destination exists (default:
+     * "name (copy).ext"); replaced items go to the workspace trash
+     * @returns Path of the copy, or null if it was skipped
+     */
+    async copy(source: string, destination: string, onConflict?: OnConflict): Promise<string | null> {
+        try {
+            return await native.fs.copy(source, destination, {
+                workspaceRoot: this.workspacePath ?? undefined,
+                onConflict,
+            });
+        } catch (error: any) {
+            console.error('Failed to copy:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Run several operations in the open workspace as a unit: either all of
+     * them are applied, or the ones already done are rolled back
+     * Check `committed` on the report; per-operation errors are in `results`
+     */
+    async applyFileOperations(operations: FileOperation[]): Promise<FileOperationsReport> {
+        if (!this.workspacePath) {
+            throw new Error('No workspace is open');
+        }
+        if (!native.fs.applyFileOperations) {
+            throw new Error('Batch file operations are not available');
+        }
+        return native.fs.applyFileOperations(this.workspacePath, operations);
+    }
+
+    /**
+     * Put back the content of notes changed by a link rewrite
+     * To fully undo a rename, call this before renaming the item back
+     */
+    async revertLinkRewrites(report: LinkRewriteReport): Promise<void> {
+        for (const file of report.files) {
+            await native.fs.writeFile(file.path, file.previousContent);
+        }
+    }
+
+    private async moveOptions(): Promise<MoveOptions> {
+        if (!this.workspacePath) return {};
+
+        const config = await this._app?.filesConfigManager?.getConfig();
+        return {
+            workspaceRoot: this.workspacePath,
+            updateLinks: config?.updateLinksOnMove ?? false,
+        };
+    }
+
     /**
      * Check if path exists
      */
@@ -180,6 +476,28 @@ export class FileSystemManager {
         }
     }
 
+    /**
+     * Details of a file or folder for the properties view
+     * Returns null if the platform can't report them
+     */
+    async stat(path: string): Promise<PathStat | null> {
+        if (!native.fs.stat) return null;
+        return native.fs.stat(path);
+    }
+
+    /**
+     * Hash files natively, e.g. to detect changed notes without reading them
+     * Returns null if the platform can't hash files
+     */
+    async hashFiles(
+        paths: string[],
+        algorithm?: HashAlgorithm,
+        content?: HashContent,
+    ): Promise<FileHash[] | null> {
+        if (!native.fs.hashFiles) return null;
+        return native.fs.hashFiles(paths, algorithm, content);
+    }
+
     /**
      * Watch a directory for changes (placeholder for future implementation)
      */
@@ -192,6 +510,234 @@ export class FileSystemManager {
         };
     }
 
+    /**
+     * Watch a workspace root for changes made outside the app
+     * Returns null when the platform has no native watcher
+     */
+    async watchWorkspace(
+        path: string,
+        callback: (changes: NativeFileChange[]) => void,
+        options?: WatchWorkspaceOptions,
+    ): Promise<(() => Promise<void>) | null> {
+        if (!native.fs.watchWorkspace) {
+            return null;
+        }
+
+        try {
+            return await native.fs.watchWorkspace(path, callback, options);
+        } catch (error: any) {
+            console.error('Failed to watch workspace:', error);
+            return null;
+        }
+    }
+
+    /**
+     * Full-text search over the open workspace
+     * Returns no results when the platform has no native index
+     */
+    async searchWorkspace(query: string, limit?: number): Promise<SearchResult[]> {
+        if (!this.workspacePath || !native.fs.searchWorkspace) {
+            return [];
+        }
+        return native.fs.searchWorkspace(this.workspacePath, query, limit);
+    }
+
+    /**
+     * Grep the open workspace, streaming matches to `onResults`
+     * Returns null when the platform has no native search
+     */
+    async grepWorkspace(
+        searchId: string,
+        query: string,
+        options: GrepOptions,
+        onResults: (result: GrepFileResult) => void,
+    ): Promise<GrepSummary | null> {
+        if (!this.workspacePath || !native.fs.grepWorkspace) {
+            return null;
+        }
+        return native.fs.grepWorkspace(searchId, this.workspacePath, query, options, onResults);
+    }
+
+    /**
+     * Cancel a running grep search
+     */
+    async cancelGrep(searchId: string): Promise<void> {
+        await native.fs.cancelGrep?.(searchId);
+    }
+
+    /**
+     * Statistics of the open workspace, with days in the local time zone
+     * unless `utcOffsetMinutes` is given
+     * Returns null when the platform has no native statistics
+     */
+    async workspaceStats(options: WorkspaceStatsOptions = {}): Promise<WorkspaceStats | null> {
+        if (!this.workspacePath || !native.fs.workspaceStats) {
+            return null;
+        }
+        return native.fs.workspaceStats(this.workspacePath, {
+            utcOffsetMinutes: -new Date().getTimezoneOffset(),
+            ...options,
+        });
+    }
+
+    /**
+     * Find duplicate files in the open workspace
+     * Returns null when the platform has no native duplicate detection
+     */
+    async findDuplicates(options?: DuplicateOptions): Promise<DuplicateReport | null> {
+        if (!this.workspacePath || !native.fs.findDuplicates) {
+            return null;
+        }
+        return native.fs.findDuplicates(this.workspacePath, options);
+    }
+
+    /**
+     * Keep `keep` and move `duplicates` to the trash, pointing links to them at `keep`
+     * Returns null when the platform has no native duplicate detection
+     */
+    async mergeDuplicates(keep: string, duplicates: string[]): Promise<MergeDuplicatesReport | null> {
+        if (!this.workspacePath) {
+            throw new Error('No workspace is open');
+        }
+        if (!native.fs.mergeDuplicates) return null;
+        return native.fs.mergeDuplicates(this.workspacePath, keep, duplicates);
+    }
+
+    /**
+     * Find attachments in the open workspace that no note links to or embeds
+     * Returns null when the platform has no native link index
+     */
+    async findOrphanAttachments(options?: OrphanOptions): Promise<OrphanReport | null> {
+        if (!this.workspacePath || !native.fs.findOrphanAttachments) {
+            return null;
+        }
+        return native.fs.findOrphanAttachments(this.workspacePath, options);
+    }
+
+    /**
+     * Links from a note to other files
+     * Returns no links when the platform has no native link index
+     */
+    async getOutgoingLinks(path: string): Promise<LinkEntry[]> {
+        if (!this.workspacePath || !native.fs.getOutgoingLinks) return [];
+        return native.fs.getOutgoingLinks(this.workspacePath, path);
+    }
+
+    /**
+     * Links from other notes to a file
+     */
+    async getBacklinks(path: string): Promise<LinkEntry[]> {
+        if (!this.workspacePath || !native.fs.getBacklinks) return [];
+        return native.fs.getBacklinks(this.workspacePath, path);
+    }
+
+    /**
+     * Links in the workspace that don't resolve to a file
+     */
+    async getUnresolvedLinks(): Promise<LinkEntry[]> {
+        if (!this.workspacePath || !native.fs.getUnresolvedLinks) return [];
+        return native.fs.getUnresolvedLinks(this.workspacePath);
+    }
+
+    /**
+     * Templates in a folder and its subfolders
+     * Returns no templates when the platform has no native templates
+     */
+    async listTemplates(folder: string): Promise<TemplateInfo[]> {
+        if (!native.fs.listTemplates) return [];
+        return native.fs.listTemplates(folder);
+    }
+
+    /**
+     * Questions a template asks with `{{prompt:...}}`
+     */
+    async templatePrompts(template: string): Promise<string[]> {
+        if (!native.fs.templatePrompts) return [];
+        return native.fs.templatePrompts(template);
+    }
+
+    /**
+     * Create a note in the open workspace from a template, with dates and
+     * times in the local time zone unless `utcOffsetMinutes` is given
+     * Returns null when the platform has no native templates
+     */
+    async createFromTemplate(
+        template: string,
+        target: string,
+        vars: Record<string, string> = {},
+        options: TemplateOptions = {},
+    ): Promise<CreatedNote | null> {
+        if (!this.workspacePath) {
+            throw new Error('No workspace is open');
+        }
+        if (!native.fs.createFromTemplate) return null;
+        return native.fs.createFromTemplate(this.workspacePath, template, target, vars, {
+            utcOffsetMinutes: -new Date().getTimezoneOffset(),
+            ...options,
+        });
+    }
+
+    /**
+     * Saved versions of a file, newest first
+     * Returns no versions when the platform has no version history
+     */
+    async listVersions(path: string): Promise<FileVersion[]> {
+        if (!this.workspacePath || !native.fs.listVersions) return [];
+        return native.fs.listVersions(this.workspacePath, path);
+    }
+
+    /**
+     * Content of a saved version
+     */
+    async getVersion(path: string, id: string): Promise<string> {
+        if (!this.workspacePath || !native.fs.getVersion) {
+            throw new Error('Version history is not available');
+        }
+        return native.fs.getVersion(this.workspacePath, path, id);
+    }
+
+    /**
+     * Line diff from a saved version to the current file
+     */
+    async diffVersion(path: string, id: string): Promise<DiffLine[]> {
+        if (!this.workspacePath || !native.fs.diffVersion) {
+            throw new Error('Version history is not available');
+        }
+        return native.fs.diffVersion(this.workspacePath, path, id);
+    }
+
+    /**
+     * Replace a file with a saved version; the current content is kept as a
+     * version so the restore can be undone
+     */
+    async restoreVersion(path: string, id: string): Promise<void> {
+        if (!this.workspacePath || !native.fs.restoreVersion) {
+            throw new Error('Version history is not available');
+        }
+        await native.fs.restoreVersion(this.workspacePath, path, id);
+    }
+
+    /**
+     * Remove old versions by age and then by total size
+     */
+    async pruneHistory(options?: PruneHistoryOptions): Promise<PruneHistoryReport | null> {
+        if (!this.workspacePath || !native.fs.pruneHistory) return null;
+        return native.fs.pruneHistory(this.workspacePath, options);
+    }
+
+    /**
+     * Bring the workspace's search index up to date with the disk
+     */
+    private async indexWorkspace(workspacePath: string): Promise<void> {
+        if (!native.fs.indexWorkspace) return;
+
+        try {
+            await native.fs.indexWorkspace(workspacePath);
+        } catch (error: any) {
+            console.error('Failed to index workspace:', error);
+        }
+    }
+
     /**
      * Get file extension
      */
@@ -239,11 +785,25 @@ export class FileSystemManager {
      */
     private workspacePath: string | null = null;
 
-    setWorkspacePath(path: string): void {
+    /**
+     * Open a workspace, allowing file operations inside it and closing the
+     * previous one
+     */
+    async setWorkspacePath(path: string): Promise<void> {
+        const previous = this.workspacePath;
+        if (native.fs.openWorkspace) {
+            await native.fs.openWorkspace(path);
+            if (previous && previous !== path) {
+                await native.fs.closeWorkspace?.(previous);
+            }
+        }
+
         this.workspacePath = path;
         if (this._app?.workspace) {
             this._app.workspace.trigger('workspace:ready', path);
         }
+        void this.purgeExpiredTrash(path);
+        void this.indexWorkspace(path);
     }
 
     getWorkspacePath(): string | null {
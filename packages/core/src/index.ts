@@ -14,6 +14,8 @@ export {
     ValidationError,
     NetworkError,
     isAppError,
+    isNativeError,
+    fromNativeError,
     toAppError,
 } from './errors/AppError';
 export type { Result } from './errors/Result';
@@ -33,7 +35,7 @@ export type {
 export { EditorSuggest } from './components/EditorSuggest';
 export type { FuzzyMatch, SearchMatch } from './components/FuzzySuggestModal';
 export { FuzzySuggestModal, PopoverSuggest } from './components/FuzzySuggestModal';
-export { ConfirmModal, KeyboardScope, Modal } from './components/Modal';
+export { ConfirmModal, KeyboardScope, Modal, PromptModal } from './components/Modal';
 export { injectNoticeStyles, Notice } from './components/Notice';
 export { PluginSettingTab } from './components/PluginSettingTab';
 export type {
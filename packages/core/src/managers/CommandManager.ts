@@ -162,10 +162,18 @@ export class CommandManager {
                 callback: async () => {
                     try {
                         // Create a new note using FilesConfigManager
-                        const newNotePath = await this.app.filesConfigManager.createNewNote();
-
-                        // Open the new note in a tab
+                        const note = await this.app.filesConfigManager.createNewNote();
+                        if (!note) {
+                            return;
+                        }
+                        const newNotePath = note.path;
+
+                        // Open the new note in a tab, with the cursor where the
+                        // template's {{cursor}} was
                         await this.app.tabManager.openTab(newNotePath);
+                        if (note.cursor !== null) {
+                            this.app.workspace.activeEditor?.setCursor(note.cursor);
+                        }
 
                         // Trigger file-create event to refresh file tree
                         const filename = newNotePath.split('/').pop() || 'Untitled.md';
@@ -6,6 +6,8 @@
  */
 
 import type { App } from '../App';
+import { PromptModal } from '../components/Modal';
+import type { CreatedNote, TemplateInfo } from '../native';
 
 /**
  * Files configuration interface
@@ -19,6 +21,14 @@ export interface FilesConfig {
     newAttachmentsLocation: 'root' | 'folder';
     /** Custom folder path for new attachments (relative to workspace) */
     newAttachmentsFolder: string;
+    /** Days to keep deleted items in the workspace trash (0 keeps them forever) */
+    trashRetentionDays: number;
+    /** Rewrite links to a note or attachment when it is renamed or moved */
+    updateLinksOnMove: boolean;
+    /** Folder holding note templates (relative to workspace) */
+    templatesFolder: string;
+    /** Template new notes are created from, by name in the templates folder ('' for empty notes) */
+    newNoteTemplate: string;
 }
 
 /**
@@ -29,6 +39,10 @@ export const DEFAULT_FILES_CONFIG: FilesConfig = {
     newNotesFolder: '',
     newAttachmentsLocation: 'root',
     newAttachmentsFolder: '',
+    trashRetentionDays: 30,
+    updateLinksOnMove: true,
+    templatesFolder: 'Templates',
+    newNoteTemplate: '',
 };
 
 /**
@@ -156,12 +170,61 @@ export class FilesConfigManager {
         return filename;
     }
 
+    /**
+     * List the templates in the configured templates folder
+     */
+    async listTemplates(): Promise<TemplateInfo[]> {
+        await this.loadConfig();
+
+        const workspacePath = this.app.fileSystemManager.getWorkspacePath();
+        if (!workspacePath || !this.config.templatesFolder) {
+            return [];
+        }
+
+        return this.app.fileSystemManager.listTemplates(
+            this.app.fileSystemManager.joinPath(workspacePath, this.config.templatesFolder),
+        );
+    }
+
+    /**
+     * Create a note from a template in the configured new notes location
+     * The note is named after the `title` variable, or "Untitled", "Untitled 1", etc.
+     * @param template - Template name in the templates folder
+     * @param vars - Template variables, including answers to its prompts
+     * @returns The created note, or null if the platform has no native templates
+     */
+    async createNoteFromTemplate(
+        template: string,
+        vars: Record<string, string> = {},
+    ): Promise<CreatedNote | null> {
+        await this.loadConfig();
+
+        const workspacePath = this.app.fileSystemManager.getWorkspacePath();
+        if (!workspacePath) {
+            throw new Error('No workspace opened');
+        }
+
+        const templatePath = this.getTemplatePath(workspacePath, template);
+
+        const target =
+            this.config.newNotesLocation === 'folder' && this.config.newNotesFolder
+                ? `${this.config.newNotesFolder}/{{title}}`
+                : '{{title}}';
+
+        return this.app.fileSystemManager.createFromTemplate(templatePath, target, vars, {
+            autoRename: true,
+        });
+    }
+
     /**
      * Create a new note with a unique name
      * Uses the configured default location and generates "Untitled", "Untitled 1", etc.
-     * @returns The full path of the created note
+     * Notes start from the configured new note template, if any, after
+     * asking the questions of its `{{prompt:...}}` placeholders
+     * @returns The created note with the position of the template's
+     * `{{cursor}}`, or null if the user cancelled the prompts
      */
-    async createNewNote(): Promise<string> {
+    async createNewNote(): Promise<CreatedNote | null> {
         await this.loadConfig();
 
         const workspacePath = this.app.fileSystemManager.getWorkspacePath();
@@ -169,6 +232,21 @@ export class FilesConfigManager {
             throw new Error('No workspace opened');
         }
 
+        if (this.config.newNoteTemplate) {
+            const prompts = await this.app.fileSystemManager.templatePrompts(
+                this.getTemplatePath(workspacePath, this.config.newNoteTemplate),
+            );
+            const vars = prompts.length > 0 ? await this.askTemplatePrompts(prompts) : {};
+            if (!vars) {
+                return null;
+            }
+
+            const note = await this.createNoteFromTemplate(this.config.newNoteTemplate, vars);
+            if (note) {
+                return note;
+            }
+        }
+
         // Determine target directory
         let targetDir = workspacePath;
         if (this.config.newNotesLocation === 'folder' && this.config.newNotesFolder) {
@@ -199,7 +277,35 @@ export class FilesConfigManager {
         // Create the file with empty content
         await this.app.fileSystemManager.writeFile(fullPath, '');
 
-        return fullPath;
+        return { path: fullPath, cursor: null };
+    }
+
+    /**
+     * Absolute path of a template in the templates folder
+     */
+    private getTemplatePath(workspacePath: string, template: string): string {
+        const templateFile = template.endsWith('.md') ? template : `${template}.md`;
+        return this.app.fileSystemManager.joinPath(
+            workspacePath,
+            this.config.templatesFolder,
+            templateFile,
+        );
+    }
+
+    /**
+     * Ask the questions of a template's prompts
+     * @returns Answers keyed by question, or null if the user cancelled
+     */
+    private askTemplatePrompts(prompts: string[]): Promise<Record<string, string> | null> {
+        return new Promise((resolve) => {
+            new PromptModal(this.app)
+                .setTitle('New note')
+                .setQuestions(prompts)
+                .setSubmitText('Create')
+                .onSubmit((answers) => resolve(answers))
+                .onCancel(() => resolve(null))
+                .open();
+        });
     }
 
     /**
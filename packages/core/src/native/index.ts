@@ -51,22 +51,94 @@ export { type NativeModules, native } from './NativeBridge';
 
 // Types
 export type {
+    AccessDeniedError,
     AlertOptions,
     ConfirmOptions,
+    CopyOptions,
+    CreatedNote,
+    DayWords,
+    DeleteOptions,
+    DiffLine,
+    DirectoryPage,
+    DuplicateFile,
+    DuplicateGroup,
+    DuplicateKind,
+    DuplicateOptions,
+    DuplicateReport,
     ExportFeature,
     ExportOptions,
     ExportResult,
+    FileConflictError,
+    FileDetails,
+    FileChunk,
+    FileEncodingError,
     FileFilter,
+    FileHash,
     FileInfo,
+    FileKind,
+    FileMetadata,
     FileNode,
+    FileOperation,
+    FileOperationResult,
+    FileOperationStatus,
+    FileOperationsReport,
+    FileRange,
+    FileStreamSummary,
+    FileTooLargeError,
+    FileVersion,
     FolderDialogOptions,
+    GrepFileResult,
+    GrepMatch,
+    GrepOptions,
+    GrepSummary,
+    HashAlgorithm,
+    HashContent,
     ImportOptions,
     ImportResult,
+    InvalidNameError,
+    KindTotal,
+    LineEnding,
+    LinkEntry,
+    LinkLocation,
+    LinkRewriteReport,
+    MergeDuplicatesReport,
+    MoveOptions,
+    MoveResult,
+    NativeError,
+    NativeErrorCode,
+    NativeFileChange,
+    NoteStats,
+    OnConflict,
     OpenDialogOptions,
+    OrphanAttachment,
+    OrphanOptions,
+    OrphanReport,
     OSType,
+    PathStat,
     PlatformFeature,
     PlatformInfo,
     PlatformType,
     PromptOptions,
+    PruneHistoryOptions,
+    PruneHistoryReport,
+    ReadDirectoryOptions,
+    ReadFileOptions,
+    ReadFileResult,
+    RewrittenFile,
     SaveDialogOptions,
+    SearchIndexStats,
+    SearchMatchRange,
+    SearchResult,
+    SearchSnippet,
+    TemplateInfo,
+    TemplateOptions,
+    TextEncoding,
+    ThumbnailInfo,
+    TrashEntry,
+    TrashedPath,
+    WatchWorkspaceOptions,
+    WorkspaceFile,
+    WorkspaceStats,
+    WorkspaceStatsOptions,
+    WriteFileOptions,
 } from './types';
@@ -4,9 +4,74 @@
  * File system operations - REQUIRED on all platforms
  */
 
-import type { FileNode } from '../types';
+import type {
+    CopyOptions,
+    CreatedNote,
+    DeleteOptions,
+    DiffLine,
+    DirectoryPage,
+    DuplicateOptions,
+    DuplicateReport,
+    FileChunk,
+    FileHash,
+    FileMetadata,
+    FileNode,
+    FileOperation,
+    FileOperationsReport,
+    FileRange,
+    FileStreamSummary,
+    FileVersion,
+    GrepFileResult,
+    GrepOptions,
+    GrepSummary,
+    HashAlgorithm,
+    HashContent,
+    LinkEntry,
+    LinkRewriteReport,
+    MergeDuplicatesReport,
+    MoveOptions,
+    MoveResult,
+    NativeFileChange,
+    OrphanOptions,
+    OrphanReport,
+    PathStat,
+    PruneHistoryOptions,
+    PruneHistoryReport,
+    ReadDirectoryOptions,
+    ReadFileOptions,
+    ReadFileResult,
+    SearchIndexStats,
+    SearchResult,
+    TemplateInfo,
+    TemplateOptions,
+    ThumbnailInfo,
+    TrashEntry,
+    TrashedPath,
+    WatchWorkspaceOptions,
+    WorkspaceStats,
+    WorkspaceStatsOptions,
+    WriteFileOptions,
+} from '../types';
 
 export interface IFileSystem {
+    // ============================================================================
+    // Workspace Access
+    // ============================================================================
+
+    /**
+     * Allow file operations inside a workspace folder
+     * Optional: platforms without a path sandbox omit it
+     * @param path - Workspace root, picked earlier in the folder dialog
+     */
+    openWorkspace?(path: string): Promise<void>;
+
+    /**
+     * Stop allowing file operations inside a workspace folder
+     * Optional: platforms without a path sandbox omit it
+     * @param path - Workspace root
+     */
+    closeWorkspace?(path: string): Promise<void>;
+
     // ============================================================================
     // Read Operations
     // ============================================================================
@@ -16,31 +81,140 @@ export interface IFileSystem {
      */
     readFile(path: string): Promise<string>;
 
+    /**
+     * Read file contents along with the metadata to use as a write baseline
+     * Rejects with a FileTooLargeError above `options.maxSize`
+     */
+    readFileWithMetadata(path: string, options?: ReadFileOptions): Promise<ReadFileResult>;
+
     /**
      * Read file contents as binary (Uint8Array)
      */
     readFileBinary(path: string): Promise<Uint8Array>;
 
+    /**
+     * Decode a byte range of a text file, e.g. to page through a file too
+     * large to open
+     * Optional: platforms without ranged reads omit it
+     * @param path - File path
+     * @param offset - Byte offset to start at; moved forward to the next
+     * character boundary
+     * @param length - Bytes to read, at most 8 MiB
+     */
+    readFileRange?(path: string, offset: number, length: number): Promise<FileRange>;
+
+    /**
+     * Read a whole text file in chunks, e.g. for a read-only preview
+     * Optional: platforms without ranged reads omit it
+     * @param streamId - Caller-chosen id, used to cancel the stream
+     * @param path - File path
+     * @param onChunk - Called with each chunk in order
+     * @param chunkSize - Approximate chunk size in bytes (default 256 KiB)
+     * @returns Summary once the file was read or the stream was cancelled
+     */
+    streamFile?(
+        streamId: string,
+        path: string,
+        onChunk: (chunk: FileChunk) => void,
+        chunkSize?: number,
+    ): Promise<FileStreamSummary>;
+
+    /**
+     * Cancel a running stream
+     * @returns false if no stream with that id is running
+     */
+    cancelFileStream?(streamId: string): Promise<boolean>;
+
+    /**
+     * URL the webview can load a workspace file from directly, e.g. for
+     * img/video src, without reading it into memory first
+     * Optional: platforms without an asset protocol omit it
+     * @param path - Absolute path inside the open workspace
+     */
+    getAssetUrl?(path: string): string;
+
+    /**
+     * URL of a downscaled copy of an image, fitting in a `maxSize` square
+     * Optional: platforms without thumbnail support omit it
+     * @param path - Absolute path of a PNG, JPEG, GIF or WebP image
+     * @param maxSize - Largest width and height in pixels
+     */
+    getThumbnailUrl?(path: string, maxSize: number): string;
+
+    /**
+     * Generate (or reuse) the cached thumbnail of an image
+     * Optional: platforms without thumbnail support omit it
+     * @param path - Absolute path of a PNG, JPEG, GIF or WebP image
+     * @param maxSize - Largest width and height in pixels
+     */
+    getThumbnail?(path: string, maxSize: number): Promise<ThumbnailInfo>;
+
     /**
      * Read directory contents
      * @param path - Directory path
      * @param recursive - Whether to read subdirectories
+     * @param options - Ignore, hidden-file, extension and depth filters
+     */
+    readDirectory(path: string, recursive?: boolean, options?: ReadDirectoryOptions): Promise<FileNode[]>;
+
+    /**
+     * Read one page of a folder's direct children, for lazily loading large folders
+     * Optional: platforms without paginated listing omit it
+     * @param path - Directory path
+     * @param offset - Index of the first entry to return
+     * @param limit - Maximum number of entries to return
+     * @param options - Ignore, hidden-file and extension filters
+     */
+    readDirectoryPage?(
+        path: string,
+        offset: number,
+        limit: number,
+        options?: ReadDirectoryOptions,
+    ): Promise<DirectoryPage>;
+
+    /**
+     * Patterns from a workspace's `.gitignore` and `.inkdownignore`
+     * Optional: platforms without ignore-file support omit it
+     * @param workspaceRoot - Workspace root path
      */
-    readDirectory(path: string, recursive?: boolean): Promise<FileNode[]>;
+    readIgnorePatterns?(workspaceRoot: string): Promise<string[]>;
 
     /**
      * Check if path exists
      */
     exists(path: string): Promise<boolean>;
 
+    /**
+     * Details of a file or folder: times, read-only flag, symlink, kind and
+     * MIME type, and line/word counts and title for notes
+     * Optional: platforms without it omit it
+     */
+    stat?(path: string): Promise<PathStat>;
+
+    /**
+     * Hex-encoded hash of a file's bytes (default: SHA-256)
+     * Optional: platforms without native hashing omit it
+     */
+    hashFile?(path: string, algorithm?: HashAlgorithm, content?: HashContent): Promise<string>;
+
+    /**
+     * Hash many files in parallel, reusing cached hashes of unchanged files
+     * Optional: platforms without native hashing omit it
+     * @returns One result per path, in order
+     */
+    hashFiles?(paths: string[], algorithm?: HashAlgorithm, content?: HashContent): Promise<FileHash[]>;
+
     // ============================================================================
     // Write Operations
     // ============================================================================
 
     /**
      * Write string content to file
+     * @param options - Optional preconditions; rejects with a FileConflictError
+     * if the file changed on disk since it was read
+     * @returns Metadata of the written file
      */
-    writeFile(path: string, content: string): Promise<void>;
+    writeFile(path: string, content: string, options?: WriteFileOptions): Promise<FileMetadata>;
 
     /**
      * Write binary content to file
@@ -58,23 +232,282 @@ export interface IFileSystem {
 
     /**
      * Rename/move a file or directory
+     * @param options - Pass workspaceRoot and updateLinks to rewrite links to it
+     * @returns Notes whose links were rewritten
      */
-    rename(oldPath: string, newPath: string): Promise<void>;
+    rename(oldPath: string, newPath: string, options?: MoveOptions): Promise<LinkRewriteReport>;
 
     /**
      * Delete a file or directory
+     * @param options - Pass workspaceRoot to move the item to the workspace trash
+     */
+    delete(path: string, options?: DeleteOptions): Promise<void>;
+
+    /**
+     * Move a file or directory, into `destination` if that is a directory
+     * Falls back to copy and delete across file systems
+     * @param options - Pass workspaceRoot and updateLinks to rewrite links to
+     * it, onConflict to handle an existing destination
+     * @returns Where the item ended up and the notes whose links were rewritten
+     */
+    move(source: string, destination: string, options?: MoveOptions): Promise<MoveResult>;
+
+    /**
+     * Copy a file or directory, into `destination` if that is a directory
+     * Modification times are kept
+     * @param options - onConflict to handle an existing destination
+     * @returns Path of the copy, or null if it was skipped
+     */
+    copy(source: string, destination: string, options?: CopyOptions): Promise<string | null>;
+
+    /**
+     * Run several operations inside a workspace as a unit
+     * All operations are validated before any runs; if one fails while
+     * running, the ones already done are rolled back. Links are not rewritten.
+     * Optional: platforms without batch operations omit it
+     * @param workspaceRoot - Workspace root path; every path must be inside it
+     * @param operations - Operations in the order to run them
+     * @returns Whether the batch was applied, and a result per operation
+     */
+    applyFileOperations?(
+        workspaceRoot: string,
+        operations: FileOperation[],
+    ): Promise<FileOperationsReport>;
+
+    // ============================================================================
+    // Trash Operations
+    // ============================================================================
+
+    /**
+     * List items in the workspace trash, most recently deleted first
+     * @param workspaceRoot - Workspace root path
+     */
+    listTrash(workspaceRoot: string): Promise<TrashEntry[]>;
+
+    /**
+     * Restore an item from the trash to its original location
+     * @param workspaceRoot - Workspace root path
+     * @param id - Trash entry id
+     * @returns Path the item was restored to
+     */
+    restoreFromTrash(workspaceRoot: string, id: string): Promise<string>;
+
+    /**
+     * Permanently delete items from the trash
+     * @param workspaceRoot - Workspace root path
+     * @param options - Purge a single entry, or entries older than N days;
+     * empties the whole trash when omitted
+     * @returns Number of items purged
+     */
+    purgeTrash(
+        workspaceRoot: string,
+        options?: { id?: string; olderThanDays?: number },
+    ): Promise<number>;
+
+    /**
+     * Move many items to the workspace trash; an item that can't be moved
+     * doesn't stop the others
+     * Optional: platforms without bulk trash omit it
+     * @param workspaceRoot - Workspace root path
+     * @returns One result per path, in order
+     */
+    movePathsToTrash?(workspaceRoot: string, paths: string[]): Promise<TrashedPath[]>;
+
+    // ============================================================================
+    // Watch Operations
+    // ============================================================================
+
+    /**
+     * Watch a workspace root recursively for changes made outside the app
+     * Optional: platforms without a native watcher omit it
+     * @param path - Workspace root path
+     * @param callback - Called with each debounced batch of changes
+     * @param options - Patterns to skip on top of the workspace's ignore files
+     * @returns Function that stops watching
+     */
+    watchWorkspace?(
+        path: string,
+        callback: (changes: NativeFileChange[]) => void,
+        options?: WatchWorkspaceOptions,
+    ): Promise<() => Promise<void>>;
+
+    // ============================================================================
+    // Search Operations
+    // ============================================================================
+
+    /**
+     * Build or update the full-text index of a workspace
+     * Optional: platforms without a native index omit it
+     * @param workspaceRoot - Workspace root path
+     */
+    indexWorkspace?(workspaceRoot: string): Promise<SearchIndexStats>;
+
+    /**
+     * Search a workspace's full-text index
+     * Supports `"phrases"`, `prefix*`, `tag:`, `path:` and `title:` fields,
+     * and `-excluded` terms
+     * @param workspaceRoot - Workspace root path
+     * @param query - Search query
+     * @param limit - Maximum number of results (default 50)
+     */
+    searchWorkspace?(workspaceRoot: string, query: string, limit?: number): Promise<SearchResult[]>;
+
+    /**
+     * Literal or regex search over every file in a workspace
+     * Optional: platforms without native search omit it
+     * @param searchId - Caller-chosen id, used to cancel the search
+     * @param workspaceRoot - Workspace root path
+     * @param query - Text or pattern to search for
+     * @param options - Matching and ignore options
+     * @param onResults - Called with the matches of each file as they are found
+     * @returns Summary once the search finished or was cancelled
+     */
+    grepWorkspace?(
+        searchId: string,
+        workspaceRoot: string,
+        query: string,
+        options: GrepOptions,
+        onResults: (result: GrepFileResult) => void,
+    ): Promise<GrepSummary>;
+
+    /**
+     * Cancel a running grep search
+     * @returns false if no search with that id is running
+     */
+    cancelGrep?(searchId: string): Promise<boolean>;
+
+    /**
+     * Counts, sizes and word totals of every file in a workspace
+     * Optional: platforms without native statistics omit it
+     * @param workspaceRoot - Workspace root path
+     */
+    workspaceStats?(workspaceRoot: string, options?: WorkspaceStatsOptions): Promise<WorkspaceStats>;
+
+    /**
+     * Group files with identical content and, optionally, nearly identical notes
+     * Optional: platforms without native duplicate detection omit it
+     * @param workspaceRoot - Workspace root path
+     */
+    findDuplicates?(workspaceRoot: string, options?: DuplicateOptions): Promise<DuplicateReport>;
+
+    /**
+     * Keep one file and move its duplicates to the trash, pointing links to
+     * the duplicates at the kept file
+     * Optional: platforms without native duplicate detection omit it
+     * @param workspaceRoot - Workspace root path
+     */
+    mergeDuplicates?(workspaceRoot: string, keep: string, duplicates: string[]): Promise<MergeDuplicatesReport>;
+
+    /**
+     * Attachments that no note links to or embeds
+     * Optional: platforms without a native link index omit it
+     * @param workspaceRoot - Workspace root path
+     */
+    findOrphanAttachments?(workspaceRoot: string, options?: OrphanOptions): Promise<OrphanReport>;
+
+    // ============================================================================
+    // Link Operations
+    // ============================================================================
+
+    /**
+     * Links from a note to other files
+     * Optional: platforms without a native link index omit it
+     * @param workspaceRoot - Workspace root path
+     * @param path - Note path
+     */
+    getOutgoingLinks?(workspaceRoot: string, path: string): Promise<LinkEntry[]>;
+
+    /**
+     * Links from other notes to a file
+     * @param workspaceRoot - Workspace root path
+     * @param path - Linked file path
+     */
+    getBacklinks?(workspaceRoot: string, path: string): Promise<LinkEntry[]>;
+
+    /**
+     * Links anywhere in the workspace that don't resolve to a file
+     * @param workspaceRoot - Workspace root path
+     */
+    getUnresolvedLinks?(workspaceRoot: string): Promise<LinkEntry[]>;
+
+    // ============================================================================
+    // Template Operations
+    // ============================================================================
+
+    /**
+     * Templates in a folder and its subfolders, sorted by name
+     * Optional: platforms without native templates omit it
+     * @param folder - Absolute templates folder path
+     */
+    listTemplates?(folder: string): Promise<TemplateInfo[]>;
+
+    /**
+     * Questions of a template's `{{prompt:...}}` placeholders, to ask before
+     * creating a note; the answers are passed as variables named by the question
+     * @param template - Template path
+     */
+    templatePrompts?(template: string): Promise<string[]>;
+
+    /**
+     * Create a note from a template, expanding `{{date}}`, `{{time}}`,
+     * `{{title}}`, `{{cursor}}`, prompts, includes and variables in its
+     * content and in the target path
+     * @param workspaceRoot - Workspace root path
+     * @param template - Template path
+     * @param target - Note path, absolute or relative to the workspace root
+     * @param vars - Variables, including prompt answers
+     */
+    createFromTemplate?(
+        workspaceRoot: string,
+        template: string,
+        target: string,
+        vars?: Record<string, string>,
+        options?: TemplateOptions,
+    ): Promise<CreatedNote>;
+
+    // ============================================================================
+    // History Operations
+    // ============================================================================
+
+    /**
+     * Saved versions of a file, newest first
+     * Optional: platforms without version history omit it
+     * @param workspaceRoot - Workspace root path
+     * @param path - File path
+     */
+    listVersions?(workspaceRoot: string, path: string): Promise<FileVersion[]>;
+
+    /**
+     * Content of a saved version
+     * @param workspaceRoot - Workspace root path
+     * @param path - File path
+     * @param id - Version id from listVersions
+     */
+    getVersion?(workspaceRoot: string, path: string, id: string): Promise<string>;
+
+    /**
+     * Line diff from a saved version to the current file
+     * @param workspaceRoot - Workspace root path
+     * @param path - File path
+     * @param id - Version id from listVersions
      */
-    delete(path: string): Promise<void>;
+    diffVersion?(workspaceRoot: string, path: string, id: string): Promise<DiffLine[]>;
 
     /**
-     * Move a file or directory
+     * Replace a file with a saved version; the current content is saved as
+     * a version first
+     * @param workspaceRoot - Workspace root path
+     * @param path - File path
+     * @param id - Version id from listVersions
      */
-    move(source: string, destination: string): Promise<void>;
+    restoreVersion?(workspaceRoot: string, path: string, id: string): Promise<void>;
 
     /**
-     * Copy a file or directory
+     * Remove old versions by age and then by total size
+     * @param workspaceRoot - Workspace root path
+     * @param options - Age and size limits
      */
-    copy(source: string, destination: string): Promise<void>;
+    pruneHistory?(workspaceRoot: string, options?: PruneHistoryOptions): Promise<PruneHistoryReport>;
 
     // ============================================================================
     // Plugin File Operations
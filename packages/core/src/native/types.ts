@@ -15,6 +15,754 @@ export interface FileNode {
     children?: FileNode[];
     size?: number;
     modified?: number;
+    /** The entry is a symbolic link; the other fields describe its target */
+    isSymlink?: boolean;
+    /** Where the link points, as stored in the link */
+    symlinkTarget?: string;
+    /** Extra properties, listed with `includeDetails` */
+    details?: FileDetails;
+}
+
+/**
+ * Broad kind of a file or folder, from its extension
+ */
+export type FileKind = 'folder' | 'note' | 'image' | 'audio' | 'video' | 'pdf' | 'text' | 'font' | 'other';
+
+/**
+ * Counts and title of a note
+ */
+export interface NoteStats {
+    lineCount: number;
+    /** Words outside the frontmatter */
+    wordCount: number;
+    /** Frontmatter `title`, if set */
+    title: string | null;
+}
+
+/**
+ * Properties of a file or folder beyond those in FileNode
+ */
+export interface FileDetails {
+    /** Milliseconds since the Unix epoch; null where the file system doesn't record it */
+    created: number | null;
+    /** Milliseconds since the Unix epoch */
+    modified: number | null;
+    readOnly: boolean;
+    kind: FileKind;
+    /** From the extension; null for folders */
+    mimeType: string | null;
+    /** Only for notes up to 5 MiB */
+    note?: NoteStats;
+}
+
+/**
+ * A file or folder and its details
+ */
+export interface PathStat extends FileDetails {
+    name: string;
+    path: string;
+    isDirectory: boolean;
+    /** Size in bytes; null for folders */
+    size: number | null;
+    /** The item is a symbolic link; the other fields describe its target */
+    isSymlink: boolean;
+    /** Where the link points, as stored in the link */
+    symlinkTarget: string | null;
+    /** The link points at nothing */
+    isBrokenSymlink: boolean;
+}
+
+/**
+ * Filters for readDirectory and readDirectoryPage
+ */
+export interface ReadDirectoryOptions {
+    /** Root whose ignore files apply and that patterns are relative to (default: the directory read) */
+    workspaceRoot?: string;
+    /** Skip entries matched by `.gitignore` / `.inkdownignore` */
+    respectIgnoreFiles?: boolean;
+    /** Extra gitignore-style patterns to skip */
+    ignorePatterns?: string[];
+    /** Include dot-files and dot-folders (default: true) */
+    includeHidden?: boolean;
+    /** Only list files with these extensions; folders are always listed */
+    extensions?: string[];
+    /** Levels to descend when recursive; folders at the limit have no `children` */
+    maxDepth?: number;
+    /**
+     * Descend into symlinked folders when recursive (default: true).
+     * Links back to a folder already being read are returned without `children`.
+     */
+    followSymlinks?: boolean;
+    /** Add `details` to every entry; notes are read to count their lines and words */
+    includeDetails?: boolean;
+}
+
+/**
+ * One page of a folder listing
+ */
+export interface DirectoryPage {
+    entries: FileNode[];
+    /** Number of entries in the folder after filtering */
+    total: number;
+    hasMore: boolean;
+}
+
+/**
+ * A file system change reported by the native workspace watcher
+ */
+export interface NativeFileChange {
+    type: 'create' | 'modify' | 'delete' | 'rename';
+    path: string;
+    oldPath?: string;
+    isDirectory: boolean;
+    /** Milliseconds since the Unix epoch */
+    timestamp: number;
+}
+
+export interface WatchWorkspaceOptions {
+    /** Gitignore-style patterns to skip on top of the workspace's ignore files */
+    ignorePatterns?: string[];
+}
+
+/**
+ * On-disk state of a file, used as the baseline for conflict checks
+ */
+export interface FileMetadata {
+    size: number;
+    /** Milliseconds since the Unix epoch */
+    modified: number;
+    /** Hex-encoded SHA-256 of the file content */
+    hash: string;
+}
+
+/**
+ * Encoding a text file is stored in. Windows-1252 covers Latin-1 files
+ * and is the fallback for other 8-bit encodings.
+ */
+export type TextEncoding = 'utf-8' | 'utf-16le' | 'utf-16be' | 'windows-1252';
+
+/**
+ * Line ending style of a text file; `mixed` files are written back as given
+ */
+export type LineEnding = 'lf' | 'crlf' | 'cr' | 'mixed';
+
+export interface ReadFileResult {
+    /** Decoded content, without the byte order mark */
+    content: string;
+    metadata: FileMetadata;
+    encoding: TextEncoding;
+    /** Whether the file starts with a byte order mark */
+    bom: boolean;
+    lineEnding: LineEnding;
+}
+
+/**
+ * Options for reading a whole file
+ */
+export interface ReadFileOptions {
+    /**
+     * Largest file size in bytes to read (default 20 MiB); larger files are
+     * rejected with a FileTooLargeError
+     */
+    maxSize?: number;
+}
+
+/**
+ * Decoded text of a byte range of a file
+ */
+export interface FileRange {
+    content: string;
+    /**
+     * Byte offset of the first byte decoded; later than the requested
+     * offset if that fell inside a character or the byte order mark
+     */
+    offset: number;
+    /** Byte offset to read the next range from */
+    nextOffset: number;
+    totalSize: number;
+    encoding: TextEncoding;
+    /** Whether the range reaches the end of the file */
+    eof: boolean;
+}
+
+/**
+ * One chunk of a streamed file, in order
+ */
+export interface FileChunk {
+    streamId: string;
+    content: string;
+    /** Byte offset of the chunk in the file */
+    offset: number;
+    totalSize: number;
+}
+
+export interface FileStreamSummary {
+    bytesRead: number;
+    totalSize: number;
+    encoding: TextEncoding;
+    /** Stopped by `cancelFileStream` */
+    cancelled: boolean;
+}
+
+/**
+ * Hash algorithms of `hashFiles`
+ */
+export type HashAlgorithm = 'sha256' | 'blake3';
+
+/**
+ * What `hashFiles` hashes: the bytes on disk, or the text `readFile`
+ * decodes them to (without a byte order mark, in UTF-8)
+ */
+export type HashContent = 'bytes' | 'text';
+
+/**
+ * Hash of one file; files that couldn't be hashed have an `error`
+ */
+export interface FileHash {
+    path: string;
+    /** Hex-encoded hash of the bytes on disk or the decoded text */
+    hash: string | null;
+    size: number | null;
+    /** Milliseconds since the Unix epoch */
+    modified: number | null;
+    error: NativeError | null;
+}
+
+/**
+ * Downscaled copy of an image attachment
+ */
+export interface ThumbnailInfo {
+    /** Asset URL serving the thumbnail, as returned by `getThumbnailUrl` */
+    url: string;
+    width: number;
+    height: number;
+}
+
+/**
+ * Preconditions for a write. If the file on disk no longer matches,
+ * the write is rejected with a FileConflictError.
+ */
+export interface WriteFileOptions {
+    expectedModified?: number;
+    expectedHash?: string;
+    /** Defaults to the existing file's encoding, or UTF-8 for new files */
+    encoding?: TextEncoding;
+    /** Defaults to the existing file's byte order mark */
+    bom?: boolean;
+    /** Line breaks are converted to this style; defaults to the existing file's */
+    lineEnding?: LineEnding;
+}
+
+/**
+ * What went wrong in a native command. I/O failures are coded by the kind
+ * of the underlying OS error; the rest come from the commands' own checks.
+ * `io` covers anything else.
+ */
+export type NativeErrorCode =
+    | 'notFound'
+    | 'alreadyExists'
+    | 'permissionDenied'
+    | 'notADirectory'
+    | 'isADirectory'
+    | 'directoryNotEmpty'
+    | 'readOnlyFilesystem'
+    | 'storageFull'
+    | 'busy'
+    | 'invalidInput'
+    | 'accessDenied'
+    | 'invalidName'
+    | 'tooLarge'
+    | 'conflict'
+    | 'encoding'
+    | 'io';
+
+/**
+ * Rejection value of every native command
+ */
+export interface NativeError<Details = Record<string, unknown>> {
+    code: NativeErrorCode;
+    message: string;
+    /** The file the error is about, if any */
+    path: string | null;
+    /** Extra data for some codes; see the specific error types */
+    details: Details | null;
+}
+
+/**
+ * Rejection value of writeFile when the file changed since it was read
+ */
+export interface FileConflictError extends NativeError<{ current: FileMetadata | null }> {
+    code: 'conflict';
+    path: string;
+    /** `current` is the metadata now, or null if the file was deleted */
+    details: { current: FileMetadata | null };
+}
+
+/**
+ * Rejection value of writeFile when the content has characters the
+ * requested encoding can't represent
+ */
+export interface FileEncodingError extends NativeError {
+    code: 'encoding';
+    path: string;
+}
+
+/**
+ * Rejection value of `readFileWithMetadata` for a file above the size
+ * limit; read it with `readFileRange` or `streamFile` instead
+ */
+export interface FileTooLargeError extends NativeError<{ size: number; limit: number }> {
+    code: 'tooLarge';
+    path: string;
+    /** File size and the limit the read was refused at, in bytes */
+    details: { size: number; limit: number };
+}
+
+/**
+ * Rejection value of file commands for a path outside the open workspaces,
+ * the app's directories and the files picked in a dialog
+ */
+export interface AccessDeniedError extends NativeError {
+    code: 'accessDenied';
+    path: string;
+}
+
+/**
+ * Rejection value of theme and plugin commands for a name that would leave
+ * its directory, e.g. `../other`
+ */
+export interface InvalidNameError extends NativeError<{ name: string }> {
+    code: 'invalidName';
+    details: { name: string };
+}
+
+/**
+ * Options for deleting a file or directory
+ */
+export interface DeleteOptions {
+    /** Workspace root; when set, the item is moved to the workspace trash */
+    workspaceRoot?: string;
+    /** Skip the trash and delete permanently */
+    permanent?: boolean;
+}
+
+/**
+ * What to do when the destination of a move or copy already exists
+ * - `error`: fail
+ * - `overwrite`: replace the existing item; it goes to the workspace trash
+ *   when `workspaceRoot` is given
+ * - `skip`: do nothing
+ * - `autoRename`: use "name (copy).ext", "name (copy 2).ext"...
+ * - `mergeDirectories`: merge a folder into an existing folder; files on
+ *   both sides, and items that aren't both folders, are auto-renamed, and
+ *   links aren't updated
+ */
+export type OnConflict = 'error' | 'overwrite' | 'skip' | 'autoRename' | 'mergeDirectories';
+
+/**
+ * Options for renaming or moving a file or directory
+ */
+export interface MoveOptions {
+    /** Workspace root; required for `updateLinks` */
+    workspaceRoot?: string;
+    /** Rewrite links to the item across the workspace */
+    updateLinks?: boolean;
+    /** For moves only (default `error`) */
+    onConflict?: OnConflict;
+}
+
+/**
+ * Options for copying a file or directory
+ */
+export interface CopyOptions {
+    /** Workspace root; an item replaced by `overwrite` goes to its trash */
+    workspaceRoot?: string;
+    /** Default `autoRename` */
+    onConflict?: OnConflict;
+}
+
+/**
+ * One operation of `applyFileOperations`, tagged by `type`
+ * - `move` and `copy` put the item inside `to` if that is a directory
+ * - `copy` names the copy "name (copy)" if `to` is taken
+ * - `delete` moves the item to the trash unless `permanent` is set
+ */
+export type FileOperation =
+    | { type: 'create'; path: string; directory?: boolean }
+    | { type: 'write'; path: string; content: string }
+    | { type: 'rename'; from: string; to: string }
+    | { type: 'move'; from: string; to: string }
+    | { type: 'copy'; from: string; to: string }
+    | { type: 'delete'; path: string; permanent?: boolean };
+
+/**
+ * - `applied`: done and kept
+ * - `failed`: failed to validate or to run
+ * - `rolledBack`: done, then undone because another operation failed
+ * - `rollbackFailed`: done, but undoing it failed
+ * - `skipped`: not run because another operation failed
+ */
+export type FileOperationStatus = 'applied' | 'failed' | 'rolledBack' | 'rollbackFailed' | 'skipped';
+
+export interface FileOperationResult {
+    status: FileOperationStatus;
+    /** Where the created, written, moved or copied item is */
+    path: string | null;
+    error: NativeError | null;
+}
+
+export interface FileOperationsReport {
+    /** Whether every operation was applied */
+    committed: boolean;
+    /** One result per operation, in order */
+    results: FileOperationResult[];
+}
+
+/**
+ * A note whose links were rewritten by a rename or move
+ */
+export interface RewrittenFile {
+    /** Where the note is now */
+    path: string;
+    /** Content before the rewrite, for undo */
+    previousContent: string;
+    /** Number of links rewritten */
+    links: number;
+}
+
+export interface LinkRewriteReport {
+    files: RewrittenFile[];
+}
+
+/**
+ * Result of a move: where the item ended up, and the notes whose links
+ * were rewritten
+ */
+export interface MoveResult extends LinkRewriteReport {
+    /** null if the move was skipped because the destination exists */
+    path: string | null;
+}
+
+/**
+ * A note template, as listed from the templates folder
+ */
+export interface TemplateInfo {
+    /** Path relative to the templates folder, `/`-separated, without `.md` */
+    name: string;
+    /** Absolute template path */
+    path: string;
+}
+
+export interface TemplateOptions {
+    /** Offset of the local time zone from UTC, e.g. -300 for UTC-5 */
+    utcOffsetMinutes?: number;
+    /** Use "name 1.md", "name 2.md"... when the target exists instead of failing */
+    autoRename?: boolean;
+}
+
+/**
+ * A note created from a template
+ */
+export interface CreatedNote {
+    path: string;
+    /** Position of `{{cursor}}` in UTF-16 code units; null if the template has none */
+    cursor: number | null;
+}
+
+/**
+ * An item in the workspace trash
+ */
+export interface TrashEntry {
+    id: string;
+    name: string;
+    /** Absolute path the item will be restored to */
+    originalPath: string;
+    /** Milliseconds since the Unix epoch */
+    deletedAt: number;
+    isDirectory: boolean;
+}
+
+/**
+ * Outcome of moving one path to the trash in bulk
+ */
+export interface TrashedPath {
+    path: string;
+    /** Trash entry id; null if the path couldn't be moved */
+    id: string | null;
+    error: NativeError | null;
+}
+
+/**
+ * A saved version of a file in the workspace history
+ */
+export interface FileVersion {
+    id: string;
+    /** Milliseconds since the Unix epoch */
+    createdAt: number;
+    size: number;
+    /** Hex-encoded SHA-256 of the content */
+    hash: string;
+}
+
+/**
+ * One line of a diff from a version to the current file
+ */
+export interface DiffLine {
+    kind: 'equal' | 'insert' | 'delete';
+    /** 1-based line number in the version, absent for inserted lines */
+    oldLine: number | null;
+    /** 1-based line number in the current file, absent for deleted lines */
+    newLine: number | null;
+    text: string;
+}
+
+/**
+ * Limits for pruning the version history; unset limits use the defaults
+ * (30 days, 200 MB)
+ */
+export interface PruneHistoryOptions {
+    maxAgeDays?: number;
+    maxTotalBytes?: number;
+}
+
+export interface PruneHistoryReport {
+    removedVersions: number;
+    freedBytes: number;
+}
+
+export interface SearchMatchRange {
+    start: number;
+    end: number;
+}
+
+export interface SearchSnippet {
+    /** Excerpt of the file around the matches */
+    text: string;
+    /** Offset of `text` within the file content, in UTF-16 code units */
+    offset: number;
+    /** Match ranges within `text`, in UTF-16 code units */
+    highlights: SearchMatchRange[];
+}
+
+export interface SearchResult {
+    /** Absolute file path */
+    path: string;
+    title: string;
+    score: number;
+    tags: string[];
+    snippet?: SearchSnippet;
+}
+
+export interface SearchIndexStats {
+    /** Files (re)indexed by this run */
+    indexed: number;
+    /** Files dropped from the index because they no longer exist */
+    removed: number;
+    /** Notes currently in the workspace */
+    total: number;
+}
+
+export interface GrepOptions {
+    /** Treat the query as a regular expression instead of literal text */
+    regex?: boolean;
+    caseSensitive?: boolean;
+    wholeWord?: boolean;
+    /** Extra gitignore-style patterns on top of the workspace's ignore files */
+    ignorePatterns?: string[];
+    /** Stop once this many matching lines were found (default 10000) */
+    maxResults?: number;
+}
+
+export interface GrepMatch {
+    /** 1-based line number */
+    lineNumber: number;
+    /** The line, or a preview of it when it is very long */
+    text: string;
+    /** Match ranges within `text`, in UTF-16 code units */
+    ranges: SearchMatchRange[];
+}
+
+export interface GrepFileResult {
+    searchId: string;
+    /** Absolute file path */
+    path: string;
+    matches: GrepMatch[];
+}
+
+export interface GrepSummary {
+    filesSearched: number;
+    filesMatched: number;
+    matches: number;
+    /** Stopped by `cancelGrep` */
+    cancelled: boolean;
+    /** Stopped because `maxResults` was reached */
+    truncated: boolean;
+}
+
+export interface WorkspaceStatsOptions {
+    /** Extra gitignore-style patterns on top of the workspace's ignore files */
+    ignorePatterns?: string[];
+    /** Length of `largestFiles` and `recentFiles` (default 10, at most 100) */
+    listLength?: number;
+    /** Days in `wordsByDay`, ending today (default 30, 1 to 366) */
+    days?: number;
+    /** Offset of the local time zone from UTC, e.g. -300 for UTC-5 */
+    utcOffsetMinutes?: number;
+}
+
+export interface KindTotal {
+    kind: FileKind;
+    count: number;
+    bytes: number;
+}
+
+export interface WorkspaceFile {
+    /** Absolute file path */
+    path: string;
+    kind: FileKind;
+    size: number;
+    /** Milliseconds since the Unix epoch */
+    modified: number;
+}
+
+export interface DayWords {
+    /** Local date as `YYYY-MM-DD` */
+    date: string;
+    /** Words added to notes that day, from their version history */
+    words: number;
+    /** Notes written to that day */
+    notes: number;
+}
+
+/**
+ * Statistics of a whole workspace; words and characters are counted in
+ * notes, outside their frontmatter
+ */
+export interface WorkspaceStats {
+    noteCount: number;
+    /** Files that aren't notes */
+    attachmentCount: number;
+    totalBytes: number;
+    /** Most bytes first */
+    byKind: KindTotal[];
+    wordCount: number;
+    characterCount: number;
+    characterCountNoSpaces: number;
+    /** Largest files first */
+    largestFiles: WorkspaceFile[];
+    /** Most recently modified files first */
+    recentFiles: WorkspaceFile[];
+    /** One entry per day, oldest first */
+    wordsByDay: DayWords[];
+}
+
+export interface OrphanOptions {
+    /** Extra gitignore-style patterns on top of the workspace's ignore files */
+    ignorePatterns?: string[];
+    /** Only look inside this folder (absolute path), e.g. the attachments folder */
+    folder?: string;
+}
+
+export interface OrphanAttachment {
+    /** Absolute file path */
+    path: string;
+    kind: FileKind;
+    size: number;
+    /** Milliseconds since the Unix epoch */
+    modified: number;
+}
+
+/**
+ * Attachments no note links to or embeds; files only referenced outside
+ * note links (frontmatter, plugin data) show up too, so review before deleting
+ */
+export interface OrphanReport {
+    /** Largest first */
+    orphans: OrphanAttachment[];
+    totalBytes: number;
+    attachmentsScanned: number;
+}
+
+export interface DuplicateOptions {
+    /** Extra gitignore-style patterns on top of the workspace's ignore files */
+    ignorePatterns?: string[];
+    /** Also group notes that are nearly the same */
+    includeSimilar?: boolean;
+    /** Lowest similarity, from 0 to 1, for near-duplicate notes (default 0.8) */
+    similarityThreshold?: number;
+    /** Group empty files too */
+    includeEmpty?: boolean;
+}
+
+/**
+ * `exact`: byte-for-byte identical; `similar`: notes above the similarity threshold
+ */
+export type DuplicateKind = 'exact' | 'similar';
+
+export interface DuplicateFile {
+    /** Absolute file path */
+    path: string;
+    size: number;
+    /** Milliseconds since the Unix epoch */
+    modified: number;
+}
+
+export interface DuplicateGroup {
+    kind: DuplicateKind;
+    /** Hex-encoded SHA-256 of the content; null for similar notes */
+    hash: string | null;
+    /** 1 for exact duplicates; for similar notes the lowest similarity that joined two of them */
+    similarity: number;
+    /** Oldest first, so the first file is usually the original */
+    files: DuplicateFile[];
+}
+
+export interface DuplicateReport {
+    /** Exact groups, most wasted bytes first, then similar ones, most similar first */
+    groups: DuplicateGroup[];
+    filesScanned: number;
+    /** Bytes taken by all but one file of each exact group */
+    wastedBytes: number;
+}
+
+/**
+ * Result of merging duplicates: the removed files, and the notes whose
+ * links were pointed at the kept file
+ */
+export interface MergeDuplicatesReport extends LinkRewriteReport {
+    removed: {
+        path: string;
+        /** Trash entry id, for `restoreFromTrash` */
+        trashId: string;
+    }[];
+}
+
+export interface LinkLocation {
+    line: number;
+    /** Column in UTF-16 code units */
+    col: number;
+    /** Offset from the start of the file in UTF-16 code units */
+    offset: number;
+}
+
+/**
+ * A link found by the backend link index, shaped like `LinkCache`
+ */
+export interface LinkEntry {
+    /** Absolute path of the note containing the link */
+    sourcePath: string;
+    /** Absolute path the link resolves to, null if it doesn't resolve */
+    targetPath: string | null;
+    /** Link target as written, without subpath */
+    link: string;
+    /** The full link text */
+    original: string;
+    displayText?: string;
+    /** Heading or block reference after `#` */
+    subpath?: string;
+    kind: 'wikilink' | 'markdown';
+    embed: boolean;
+    position: { start: LinkLocation; end: LinkLocation };
 }
 
 export interface FileInfo {
@@ -1,7 +1,8 @@
 import type { App } from '../App';
 import { Events } from '../Events';
 import type { TAbstractFile, TFile } from '../managers/Workspace';
-import type { SelectiveSyncManager } from './SelectiveSyncManager';
+import type { NativeFileChange } from '../native';
+import { type SelectiveSyncManager, WORKSPACE_IGNORE_FILES } from './SelectiveSyncManager';
 import type { FileChangeEvent } from './types';
 
 /**
@@ -30,6 +31,7 @@ export interface FileChangeBatch {
  * - Batch processing of multiple changes
  * - Retry logic for transient read failures
  * - Pause/resume for programmatic file operations
+ * - Native disk watcher for changes made outside the app (git, other editors)
  *
  * Events:
  * - 'change': Single file change (for immediate needs)
@@ -43,6 +45,7 @@ export class FileWatcherService extends Events {
     private batchTimer: NodeJS.Timeout | null = null;
     private isActive = false;
     private isPaused = false;
+    private stopNativeWatch: (() => Promise<void>) | null = null;
 
     // Configuration
     private config: Required<FileWatcherConfig> = {
@@ -57,6 +60,7 @@ export class FileWatcherService extends Events {
     private boundHandleFileModify: (file: TFile) => void;
     private boundHandleFileDelete: (file: TAbstractFile) => void;
     private boundHandleFileRename: (file: TFile, oldPath: string) => void;
+    private boundHandleNativeChanges: (changes: NativeFileChange[]) => void;
 
     constructor(app: App, selectiveSync: SelectiveSyncManager, config?: FileWatcherConfig) {
         super();
@@ -73,6 +77,7 @@ export class FileWatcherService extends Events {
         this.boundHandleFileModify = this.handleFileModify.bind(this);
         this.boundHandleFileDelete = this.handleFileDelete.bind(this);
         this.boundHandleFileRename = this.handleFileRename.bind(this);
+        this.boundHandleNativeChanges = this.handleNativeChanges.bind(this);
     }
 
     /**
@@ -138,6 +143,17 @@ export class FileWatcherService extends Events {
         this.app.workspace.on('file-delete', this.boundHandleFileDelete);
         this.app.workspace.on('file-rename', this.boundHandleFileRename);
 
+        // Also watch the disk so external edits are picked up without polling
+        const workspacePath = this.app.fileSystemManager.getWorkspacePath();
+        if (workspacePath) {
+            await this.selectiveSync.reloadWorkspaceIgnoreRules();
+            this.stopNativeWatch = await this.app.fileSystemManager.watchWorkspace(
+                workspacePath,
+                this.boundHandleNativeChanges,
+                { ignorePatterns: this.selectiveSync.getIgnorePatterns() },
+            );
+        }
+
         console.log('[FileWatcherService] File watcher started');
     }
 
@@ -169,6 +185,14 @@ export class FileWatcherService extends Events {
         this.app.workspace.off('file-modify', this.boundHandleFileModify);
         this.app.workspace.off('file-delete', this.boundHandleFileDelete);
         this.app.workspace.off('file-rename', this.boundHandleFileRename);
+
+        if (this.stopNativeWatch) {
+            this.stopNativeWatch().catch((error) => {
+                console.error('[FileWatcherService] Error stopping native watcher:', error);
+            });
+            this.stopNativeWatch = null;
+        }
+
         this.offAll();
         console.log('[FileWatcherService] File watcher stopped');
     }
@@ -190,7 +214,7 @@ export class FileWatcherService extends Events {
     /**
      * Handle file creation event
      */
-    private async handleFileCreate(file: TFile): Promise<void> {
+    private async handleFileCreate(file: Pick<TFile, 'path'>): Promise<void> {
         const path = file.path;
 
         if (!this.shouldProcess(path)) return;
@@ -220,7 +244,7 @@ export class FileWatcherService extends Events {
     /**
      * Handle file modification event
      */
-    private async handleFileModify(file: TFile): Promise<void> {
+    private async handleFileModify(file: Pick<TFile, 'path'>): Promise<void> {
         const path = file.path;
 
         if (!this.shouldProcess(path)) return;
@@ -250,7 +274,7 @@ export class FileWatcherService extends Events {
     /**
      * Handle file deletion event
      */
-    private handleFileDelete(file: TAbstractFile): void {
+    private handleFileDelete(file: Pick<TAbstractFile, 'path'>): void {
         const path = file.path;
 
         console.log('[FileWatcherService] File delete detected:', path);
@@ -301,7 +325,7 @@ export class FileWatcherService extends Events {
     /**
      * Handle file rename event
      */
-    private handleFileRename(file: TFile, oldPath: string): void {
+    private handleFileRename(file: Pick<TFile, 'path'>, oldPath: string): void {
         const newPath = file.path;
 
         console.log('[FileWatcherService] File rename detected:', oldPath, '->', newPath);
@@ -351,6 +375,40 @@ export class FileWatcherService extends Events {
         this.trigger('change', event);
     }
 
+    /**
+     * Handle a batch of changes reported by the native disk watcher
+     */
+    private handleNativeChanges(changes: NativeFileChange[]): void {
+        const workspacePath = this.app.fileSystemManager.getWorkspacePath();
+        const ignoreFileChanged = changes.some((change) =>
+            WORKSPACE_IGNORE_FILES.some((name) => change.path === `${workspacePath}/${name}`),
+        );
+        if (ignoreFileChanged) {
+            this.selectiveSync.reloadWorkspaceIgnoreRules().catch((error) => {
+                console.error('[FileWatcherService] Error reloading ignore rules:', error);
+            });
+        }
+
+        for (const change of changes) {
+            const file = { path: change.path };
+
+            switch (change.type) {
+                case 'create':
+                    this.handleFileCreate(file);
+                    break;
+                case 'modify':
+                    this.handleFileModify(file);
+                    break;
+                case 'delete':
+                    this.handleFileDelete(file);
+                    break;
+                case 'rename':
+                    this.handleFileRename(file, change.oldPath ?? change.path);
+                    break;
+            }
+        }
+    }
+
     /**
      * Check if a path should be processed
      */
@@ -6,12 +6,69 @@ export interface SyncIgnoreConfig {
     ignoredPaths: Record<string, boolean>;
 }
 
+/** Ignore files read from the workspace root, shared with the native file listing */
+export const WORKSPACE_IGNORE_FILES = ['.gitignore', '.inkdownignore'];
+
+interface IgnoreRule {
+    /** minimatch glob for a workspace-relative path */
+    glob: string;
+    negated: boolean;
+    dirOnly: boolean;
+}
+
+/**
+ * Convert a gitignore-style line to a minimatch rule
+ */
+export function parseIgnoreRule(line: string): IgnoreRule | null {
+    let pattern = line.trimEnd();
+    if (!pattern || pattern.startsWith('#')) return null;
+
+    const negated = pattern.startsWith('!');
+    if (negated) pattern = pattern.slice(1);
+
+    const dirOnly = pattern.endsWith('/');
+    pattern = pattern.replace(/\/+$/, '');
+
+    // A slash anywhere but the end anchors the pattern to the root;
+    // otherwise it matches at any depth
+    const anchored = pattern.includes('/');
+    pattern = pattern.replace(/^\//, '');
+    if (!pattern) return null;
+
+    return { glob: anchored ? pattern : `**/${pattern}`, negated, dirOnly };
+}
+
+/**
+ * Whether a workspace-relative path is ignored by gitignore-style rules,
+ * either itself or through one of its parent folders
+ */
+export function matchesIgnoreRules(path: string, rules: IgnoreRule[]): boolean {
+    const segments = path.split('/').filter(Boolean);
+
+    return segments.some((_, index) => {
+        const ancestor = segments.slice(0, index + 1).join('/');
+        const isDir = index < segments.length - 1;
+        let ignored = false;
+
+        // Like git, the last matching rule wins
+        for (const rule of rules) {
+            if ((!rule.dirOnly || isDir) && minimatch(ancestor, rule.glob, { dot: true })) {
+                ignored = !rule.negated;
+            }
+        }
+
+        return ignored;
+    });
+}
+
 export class SelectiveSyncManager {
     private app: App;
     private config: SyncIgnoreConfig = {
         ignorePatterns: ['*.tmp', '.trash/**', 'node_modules/**'],
         ignoredPaths: {},
     };
+    /** Rules from the workspace's `.gitignore` and `.inkdownignore` */
+    private workspaceRules: IgnoreRule[] = [];
 
     constructor(app: App) {
         this.app = app;
@@ -25,6 +82,17 @@ export class SelectiveSyncManager {
                 ignoredPaths: syncConfig.ignoredPaths || {},
             };
         }
+        await this.reloadWorkspaceIgnoreRules();
+    }
+
+    /**
+     * Re-read the workspace's ignore files, e.g. after they changed on disk
+     */
+    async reloadWorkspaceIgnoreRules(): Promise<void> {
+        const patterns = await this.app.fileSystemManager.readIgnorePatterns();
+        this.workspaceRules = patterns
+            .map(parseIgnoreRule)
+            .filter((rule): rule is IgnoreRule => rule !== null);
     }
 
     shouldIgnore(path: string): boolean {
@@ -43,7 +111,14 @@ export class SelectiveSyncManager {
             }
         }
 
-        return false;
+        // Check the workspace's ignore files, which match relative to its root
+        const workspacePath = this.app.fileSystemManager.getWorkspacePath();
+        const relativePath =
+            workspacePath && path.startsWith(`${workspacePath}/`)
+                ? path.slice(workspacePath.length + 1)
+                : normalizedPath;
+
+        return matchesIgnoreRules(relativePath, this.workspaceRules);
     }
 
     async addIgnorePath(path: string): Promise<void> {
@@ -1,5 +1,6 @@
 import type { App } from '../App';
 import { Events } from '../Events';
+import type { TFile } from '../managers/Workspace';
 import { loggers } from '../utils/logger';
 import { ConflictResolver } from './ConflictResolver';
 import type { EncryptionManager } from './EncryptionManager';
@@ -1407,6 +1408,48 @@ export class SyncEngine extends Events {
     return this.arrayBufferToBase64(hashBuffer); // Use helper instead of manual map
   }
 
+  /**
+   * Hashes of files in the format of `calculateHash`, computed natively
+   * where the platform can so the content doesn't have to be read here.
+   * Native hashes are of the decoded text, the same content `calculateHash`
+   * is given, so notes with a byte order mark or in another encoding match.
+   */
+  private async hashFiles(files: TFile[]): Promise<Map<string, string>> {
+    const hashes = new Map<string, string>();
+
+    try {
+      const results = await this.app.fileSystemManager.hashFiles(
+        files.map((file) => file.path),
+        'sha256',
+        'text',
+      );
+      for (const result of results ?? []) {
+        if (result.hash) {
+          hashes.set(result.path, this.hexToBase64(result.hash));
+        }
+      }
+    } catch (error: any) {
+      this.logger.warn('Native hashing failed, hashing in the webview:', error);
+    }
+
+    for (const file of files) {
+      if (!hashes.has(file.path)) {
+        const content = await this.app.fileManager.read(file);
+        hashes.set(file.path, await this.calculateHash(content));
+      }
+    }
+
+    return hashes;
+  }
+
+  private hexToBase64(hex: string): string {
+    const bytes = new Uint8Array(hex.length / 2);
+    for (let i = 0; i < bytes.length; i++) {
+      bytes[i] = parseInt(hex.slice(i * 2, i * 2 + 2), 16);
+    }
+    return this.arrayBufferToBase64(bytes.buffer);
+  }
+
   private arrayBufferToBase64(buffer: ArrayBuffer): string {
     let binary = '';
     const bytes = new Uint8Array(buffer);
@@ -1633,13 +1676,13 @@ export class SyncEngine extends Events {
     let changesFound = 0;
 
     try {
-      const files = await this.app.workspace.getFiles(['md']);
+      const files = (await this.app.workspace.getFiles(['md'])).filter(
+        (file) => !this.selectiveSync.shouldIgnore(file.path),
+      );
+      const hashes = await this.hashFiles(files);
 
       for (const file of files) {
-        if (this.selectiveSync.shouldIgnore(file.path)) continue;
-
-        const content = await this.app.fileManager.read(file);
-        const currentHash = await this.calculateHash(content);
+        const currentHash = hashes.get(file.path)!;
         const storedHash = await this.localDatabase.getContentHash(file.path);
 
         if (storedHash && currentHash !== storedHash) {
@@ -1701,13 +1744,13 @@ export class SyncEngine extends Events {
 
       // Get local files
       const localFiles = await this.app.workspace.getFiles(['md']);
+      const syncedFiles = localFiles.filter((file) => !this.selectiveSync.shouldIgnore(file.path));
+      const hashes = await this.hashFiles(syncedFiles);
 
-      for (const file of localFiles) {
-        if (this.selectiveSync.shouldIgnore(file.path)) continue;
+      for (const file of syncedFiles) {
         total++;
 
-        const content = await this.app.fileManager.read(file);
-        const localHash = await this.calculateHash(content);
+        const localHash = hashes.get(file.path)!;
         const noteId = await this.localDatabase.getNoteIdByPath(file.path);
 
         if (noteId) {
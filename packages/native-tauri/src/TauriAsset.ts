@@ -4,12 +4,32 @@
  * Utilities for handling local file assets in Tauri
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+
+/** URI scheme the backend serves workspace files on */
+const ASSET_PROTOCOL = 'inkdown-asset';
 
 export class TauriAsset {
     /**
-     * Convert a local file path to a URL that can be used in img src
-     * For Tauri, we need to read the file and convert to data URL
+     * Convert a workspace file path to an `inkdown-asset://` URL that can be
+     * used in img/audio/video src. The backend streams the file with range
+     * and caching support, so nothing is base64-encoded.
+     */
+    static getAssetUrl(filePath: string): string {
+        return convertFileSrc(filePath, ASSET_PROTOCOL);
+    }
+
+    /**
+     * URL of a cached, downscaled copy of a PNG/JPEG/GIF/WebP image that fits
+     * in a `maxSize` square. Other files are served unchanged.
+     */
+    static getThumbnailUrl(filePath: string, maxSize: number): string {
+        return `${TauriAsset.getAssetUrl(filePath)}?thumbnail=${Math.round(maxSize)}`;
+    }
+
+    /**
+     * Convert a local file path to a data URL
+     * Reads the whole file through IPC; prefer getAssetUrl for workspace files
      */
     static async convertFileToDataUrl(filePath: string): Promise<string> {
         try {
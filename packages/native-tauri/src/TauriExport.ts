@@ -11,6 +11,7 @@ import type {
     IExport,
     ImportOptions,
     ImportResult,
+    ReadFileResult,
 } from '@inkdown/core/native';
 import { invoke } from '@tauri-apps/api/core';
 
@@ -65,7 +66,7 @@ export class TauriExport implements IExport {
             }
 
             // Read file content
-            const content = await invoke<string>('read_file', { path });
+            const { content } = await invoke<ReadFileResult>('read_file', { path });
 
             // Extract filename from path
             const name = path.split(/[\\/]/).pop() || 'file';
@@ -99,7 +100,7 @@ export class TauriExport implements IExport {
             // Read all files
             const results: ImportResult[] = [];
             for (const path of paths) {
-                const content = await invoke<string>('read_file', { path });
+                const { content } = await invoke<ReadFileResult>('read_file', { path });
                 const name = path.split(/[\\/]/).pop() || 'file';
                 results.push({ name, data: content, path });
             }
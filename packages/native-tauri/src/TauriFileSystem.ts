@@ -4,8 +4,64 @@
  * Tauri implementation of IFileSystem
  */
 
-import type { FileNode, IFileSystem } from '@inkdown/core/native';
+import type {
+    CopyOptions,
+    CreatedNote,
+    DeleteOptions,
+    DiffLine,
+    DirectoryPage,
+    DuplicateOptions,
+    DuplicateReport,
+    FileChunk,
+    FileDetails,
+    FileHash,
+    FileMetadata,
+    FileNode,
+    FileOperation,
+    FileOperationsReport,
+    FileRange,
+    FileStreamSummary,
+    FileVersion,
+    GrepFileResult,
+    GrepOptions,
+    GrepSummary,
+    HashAlgorithm,
+    HashContent,
+    IFileSystem,
+    LinkEntry,
+    LinkRewriteReport,
+    MergeDuplicatesReport,
+    MoveOptions,
+    MoveResult,
+    NativeFileChange,
+    OrphanOptions,
+    OrphanReport,
+    PathStat,
+    PruneHistoryOptions,
+    PruneHistoryReport,
+    ReadDirectoryOptions,
+    ReadFileOptions,
+    ReadFileResult,
+    SearchIndexStats,
+    SearchResult,
+    TemplateInfo,
+    TemplateOptions,
+    ThumbnailInfo,
+    TrashEntry,
+    TrashedPath,
+    WatchWorkspaceOptions,
+    WorkspaceStats,
+    WorkspaceStatsOptions,
+    WriteFileOptions,
+} from '@inkdown/core/native';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { TauriAsset } from './TauriAsset';
+
+interface TauriFileChangeBatch {
+    workspace: string;
+    changes: NativeFileChange[];
+}
 
 interface TauriFileNode {
     name: string;
@@ -14,6 +70,33 @@ interface TauriFileNode {
     children?: TauriFileNode[];
     size?: number;
     modified?: number;
+    is_symlink: boolean;
+    symlink_target?: string;
+    details?: FileDetails;
+}
+
+interface TauriDirectoryPage {
+    entries: TauriFileNode[];
+    total: number;
+    has_more: boolean;
+}
+
+interface TauriTrashEntry {
+    id: string;
+    name: string;
+    original_path: string;
+    deleted_at: number;
+    is_directory: boolean;
+}
+
+function convertTrashEntry(entry: TauriTrashEntry): TrashEntry {
+    return {
+        id: entry.id,
+        name: entry.name,
+        originalPath: entry.original_path,
+        deletedAt: entry.deleted_at,
+        isDirectory: entry.is_directory,
+    };
 }
 
 function convertFileNode(node: TauriFileNode): FileNode {
@@ -24,12 +107,20 @@ function convertFileNode(node: TauriFileNode): FileNode {
         children: node.children?.map(convertFileNode),
         size: node.size,
         modified: node.modified,
+        isSymlink: node.is_symlink,
+        symlinkTarget: node.symlink_target,
+        details: node.details,
     };
 }
 
 export class TauriFileSystem implements IFileSystem {
     async readFile(path: string): Promise<string> {
-        return invoke<string>('read_file', { path });
+        const result = await this.readFileWithMetadata(path);
+        return result.content;
+    }
+
+    async readFileWithMetadata(path: string, options?: ReadFileOptions): Promise<ReadFileResult> {
+        return invoke<ReadFileResult>('read_file', { path, maxSize: options?.maxSize });
     }
 
     async readFileBinary(path: string): Promise<Uint8Array> {
@@ -42,17 +133,113 @@ export class TauriFileSystem implements IFileSystem {
         return bytes;
     }
 
-    async readDirectory(path: string, recursive = false): Promise<FileNode[]> {
-        const nodes = await invoke<TauriFileNode[]>('read_directory', { path, recursive });
+    async readFileRange(path: string, offset: number, length: number): Promise<FileRange> {
+        return invoke<FileRange>('read_file_range', { path, offset, length });
+    }
+
+    async streamFile(
+        streamId: string,
+        path: string,
+        onChunk: (chunk: FileChunk) => void,
+        chunkSize?: number,
+    ): Promise<FileStreamSummary> {
+        const unlisten = await listen<FileChunk>('file-stream-chunk', (event) => {
+            if (event.payload.streamId === streamId) {
+                onChunk(event.payload);
+            }
+        });
+
+        try {
+            return await invoke<FileStreamSummary>('stream_file', { streamId, path, chunkSize });
+        } finally {
+            unlisten();
+        }
+    }
+
+    async cancelFileStream(streamId: string): Promise<boolean> {
+        return invoke<boolean>('cancel_file_stream', { streamId });
+    }
+
+    getAssetUrl(path: string): string {
+        return TauriAsset.getAssetUrl(path);
+    }
+
+    getThumbnailUrl(path: string, maxSize: number): string {
+        return TauriAsset.getThumbnailUrl(path, maxSize);
+    }
+
+    async getThumbnail(path: string, maxSize: number): Promise<ThumbnailInfo> {
+        const { width, height } = await invoke<{ width: number; height: number }>('get_thumbnail', {
+            path,
+            maxSize,
+        });
+        return { url: this.getThumbnailUrl(path, maxSize), width, height };
+    }
+
+    async readDirectory(
+        path: string,
+        recursive = false,
+        options?: ReadDirectoryOptions,
+    ): Promise<FileNode[]> {
+        const nodes = await invoke<TauriFileNode[]>('read_directory', { path, recursive, options });
         return nodes.map(convertFileNode);
     }
 
+    async readDirectoryPage(
+        path: string,
+        offset: number,
+        limit: number,
+        options?: ReadDirectoryOptions,
+    ): Promise<DirectoryPage> {
+        const page = await invoke<TauriDirectoryPage>('read_directory_page', {
+            path,
+            offset,
+            limit,
+            options,
+        });
+        return {
+            entries: page.entries.map(convertFileNode),
+            total: page.total,
+            hasMore: page.has_more,
+        };
+    }
+
+    async readIgnorePatterns(workspaceRoot: string): Promise<string[]> {
+        return invoke<string[]>('read_ignore_patterns', { workspaceRoot });
+    }
+
     async exists(path: string): Promise<boolean> {
         return invoke<boolean>('path_exists', { path });
     }
 
-    async writeFile(path: string, content: string): Promise<void> {
-        await invoke('write_file', { path, content });
+    async stat(path: string): Promise<PathStat> {
+        return invoke<PathStat>('stat_path', { path });
+    }
+
+    async hashFile(path: string, algorithm?: HashAlgorithm, content?: HashContent): Promise<string> {
+        return invoke<string>('hash_file', { path, algorithm, content });
+    }
+
+    async hashFiles(paths: string[], algorithm?: HashAlgorithm, content?: HashContent): Promise<FileHash[]> {
+        return invoke<FileHash[]>('hash_files', { paths, algorithm, content });
+    }
+
+    async writeFile(
+        path: string,
+        content: string,
+        options: WriteFileOptions = {},
+    ): Promise<FileMetadata> {
+        return invoke<FileMetadata>('write_file', {
+            path,
+            content,
+            expectedModified: options.expectedModified,
+            expectedHash: options.expectedHash,
+            format: {
+                encoding: options.encoding,
+                bom: options.bom,
+                lineEnding: options.lineEnding,
+            },
+        });
     }
 
     async writeFileBinary(path: string, data: Uint8Array): Promise<void> {
@@ -69,20 +256,259 @@ export class TauriFileSystem implements IFileSystem {
         await invoke('create_directory', { path });
     }
 
-    async rename(oldPath: string, newPath: string): Promise<void> {
-        await invoke('rename_path', { oldPath, newPath });
+    async rename(
+        oldPath: string,
+        newPath: string,
+        options: MoveOptions = {},
+    ): Promise<LinkRewriteReport> {
+        return invoke<LinkRewriteReport>('rename_path', {
+            oldPath,
+            newPath,
+            workspaceRoot: options.workspaceRoot,
+            updateLinks: options.updateLinks,
+        });
+    }
+
+    async delete(path: string, options: DeleteOptions = {}): Promise<void> {
+        await invoke('delete_path', {
+            path,
+            workspaceRoot: options.workspaceRoot,
+            permanent: options.permanent,
+        });
+    }
+
+    async move(
+        source: string,
+        destination: string,
+        options: MoveOptions = {},
+    ): Promise<MoveResult> {
+        return invoke<MoveResult>('move_path', {
+            source,
+            destination,
+            workspaceRoot: options.workspaceRoot,
+            updateLinks: options.updateLinks,
+            onConflict: options.onConflict,
+        });
+    }
+
+    async copy(source: string, destination: string, options: CopyOptions = {}): Promise<string | null> {
+        return invoke<string | null>('copy_file', {
+            source,
+            destination,
+            workspaceRoot: options.workspaceRoot,
+            onConflict: options.onConflict,
+        });
+    }
+
+    async applyFileOperations(
+        workspaceRoot: string,
+        operations: FileOperation[],
+    ): Promise<FileOperationsReport> {
+        return invoke<FileOperationsReport>('apply_file_operations', { workspaceRoot, operations });
+    }
+
+    // ============================================================================
+    // Workspace Access
+    // ============================================================================
+
+    async openWorkspace(path: string): Promise<void> {
+        return invoke('open_workspace', { path });
+    }
+
+    async closeWorkspace(path: string): Promise<void> {
+        return invoke('close_workspace', { path });
+    }
+
+    // ============================================================================
+    // Trash Operations
+    // ============================================================================
+
+    async listTrash(workspaceRoot: string): Promise<TrashEntry[]> {
+        const entries = await invoke<TauriTrashEntry[]>('list_trash', { workspaceRoot });
+        return entries.map(convertTrashEntry);
+    }
+
+    async restoreFromTrash(workspaceRoot: string, id: string): Promise<string> {
+        return invoke<string>('restore_from_trash', { workspaceRoot, id });
+    }
+
+    async purgeTrash(
+        workspaceRoot: string,
+        options: { id?: string; olderThanDays?: number } = {},
+    ): Promise<number> {
+        return invoke<number>('purge_trash', {
+            workspaceRoot,
+            id: options.id,
+            olderThanDays: options.olderThanDays,
+        });
+    }
+
+    async movePathsToTrash(workspaceRoot: string, paths: string[]): Promise<TrashedPath[]> {
+        return invoke<TrashedPath[]>('move_paths_to_trash', { workspaceRoot, paths });
+    }
+
+    // ============================================================================
+    // Watch Operations
+    // ============================================================================
+
+    async watchWorkspace(
+        path: string,
+        callback: (changes: NativeFileChange[]) => void,
+        options?: WatchWorkspaceOptions,
+    ): Promise<() => Promise<void>> {
+        const unlisten = await listen<TauriFileChangeBatch>('workspace-file-changes', (event) => {
+            if (event.payload.workspace === path) {
+                callback(event.payload.changes);
+            }
+        });
+
+        try {
+            await invoke('watch_workspace', { path, ignorePatterns: options?.ignorePatterns });
+        } catch (error) {
+            unlisten();
+            throw error;
+        }
+
+        return async () => {
+            unlisten();
+            await invoke('unwatch_workspace', { path });
+        };
+    }
+
+    // ============================================================================
+    // Search Operations
+    // ============================================================================
+
+    async indexWorkspace(workspaceRoot: string): Promise<SearchIndexStats> {
+        return invoke<SearchIndexStats>('index_workspace', { workspaceRoot });
+    }
+
+    async searchWorkspace(
+        workspaceRoot: string,
+        query: string,
+        limit?: number,
+    ): Promise<SearchResult[]> {
+        return invoke<SearchResult[]>('search_workspace', { workspaceRoot, query, limit });
+    }
+
+    async grepWorkspace(
+        searchId: string,
+        workspaceRoot: string,
+        query: string,
+        options: GrepOptions,
+        onResults: (result: GrepFileResult) => void,
+    ): Promise<GrepSummary> {
+        const unlisten = await listen<GrepFileResult>('workspace-grep-results', (event) => {
+            if (event.payload.searchId === searchId) {
+                onResults(event.payload);
+            }
+        });
+
+        try {
+            return await invoke<GrepSummary>('grep_workspace', {
+                searchId,
+                workspaceRoot,
+                query,
+                options,
+            });
+        } finally {
+            unlisten();
+        }
+    }
+
+    async cancelGrep(searchId: string): Promise<boolean> {
+        return invoke<boolean>('cancel_grep', { searchId });
+    }
+
+    async workspaceStats(workspaceRoot: string, options?: WorkspaceStatsOptions): Promise<WorkspaceStats> {
+        return invoke<WorkspaceStats>('workspace_stats', { workspaceRoot, options });
+    }
+
+    async findDuplicates(workspaceRoot: string, options?: DuplicateOptions): Promise<DuplicateReport> {
+        return invoke<DuplicateReport>('find_duplicates', { workspaceRoot, options });
+    }
+
+    async mergeDuplicates(
+        workspaceRoot: string,
+        keep: string,
+        duplicates: string[],
+    ): Promise<MergeDuplicatesReport> {
+        return invoke<MergeDuplicatesReport>('merge_duplicates', { workspaceRoot, keep, duplicates });
+    }
+
+    async findOrphanAttachments(workspaceRoot: string, options?: OrphanOptions): Promise<OrphanReport> {
+        return invoke<OrphanReport>('find_orphan_attachments', { workspaceRoot, options });
+    }
+
+    // ============================================================================
+    // Link Operations
+    // ============================================================================
+
+    async getOutgoingLinks(workspaceRoot: string, path: string): Promise<LinkEntry[]> {
+        return invoke<LinkEntry[]>('get_outgoing_links', { workspaceRoot, path });
+    }
+
+    async getBacklinks(workspaceRoot: string, path: string): Promise<LinkEntry[]> {
+        return invoke<LinkEntry[]>('get_backlinks', { workspaceRoot, path });
+    }
+
+    async getUnresolvedLinks(workspaceRoot: string): Promise<LinkEntry[]> {
+        return invoke<LinkEntry[]>('get_unresolved_links', { workspaceRoot });
+    }
+
+    // ============================================================================
+    // Template Operations
+    // ============================================================================
+
+    async listTemplates(folder: string): Promise<TemplateInfo[]> {
+        return invoke<TemplateInfo[]>('list_templates', { folder });
+    }
+
+    async templatePrompts(template: string): Promise<string[]> {
+        return invoke<string[]>('template_prompts', { template });
+    }
+
+    async createFromTemplate(
+        workspaceRoot: string,
+        template: string,
+        target: string,
+        vars?: Record<string, string>,
+        options?: TemplateOptions,
+    ): Promise<CreatedNote> {
+        return invoke<CreatedNote>('create_from_template', {
+            workspaceRoot,
+            template,
+            target,
+            vars,
+            options,
+        });
+    }
+
+    // ============================================================================
+    // History Operations
+    // ============================================================================
+
+    async listVersions(workspaceRoot: string, path: string): Promise<FileVersion[]> {
+        return invoke<FileVersion[]>('list_versions', { workspaceRoot, path });
+    }
+
+    async getVersion(workspaceRoot: string, path: string, id: string): Promise<string> {
+        return invoke<string>('get_version', { workspaceRoot, path, id });
     }
 
-    async delete(path: string): Promise<void> {
-        await invoke('delete_path', { path });
+    async diffVersion(workspaceRoot: string, path: string, id: string): Promise<DiffLine[]> {
+        return invoke<DiffLine[]>('diff_version', { workspaceRoot, path, id });
     }
 
-    async move(source: string, destination: string): Promise<void> {
-        await invoke('move_path', { source, destination });
+    async restoreVersion(workspaceRoot: string, path: string, id: string): Promise<void> {
+        return invoke('restore_version', { workspaceRoot, path, id });
     }
 
-    async copy(source: string, destination: string): Promise<void> {
-        await invoke('copy_file', { source, destination });
+    async pruneHistory(
+        workspaceRoot: string,
+        options?: PruneHistoryOptions,
+    ): Promise<PruneHistoryReport> {
+        return invoke<PruneHistoryReport>('prune_history', { workspaceRoot, options });
     }
 
     // ============================================================================
@@ -2,9 +2,12 @@ import type { Range } from '@codemirror/state';
 import type { EditorView } from '@codemirror/view';
 import { Decoration, WidgetType } from '@codemirror/view';
 import type { App } from '@inkdown/core';
-import { convertFileToDataUrl, resolveImagePath } from '../utils/imageLoader';
+import { loadImageUrl, resolveImagePath } from '../utils/imageLoader';
 import { shouldDecorate } from '../utils/selection';
 
+/** Largest width/height of the thumbnails shown inline, in pixels */
+const THUMBNAIL_SIZE = 1600;
+
 /**
  * Create decorations for images (![alt](url))
  * Hides the markdown syntax and displays the image
@@ -82,12 +85,12 @@ class ImageWidget extends WidgetType {
             container.style.padding = '8px';
 
             // Load image asynchronously via FileSystemManager
-            convertFileToDataUrl(this.app, resolvedPath)
-                .then((dataUrl) => {
+            loadImageUrl(this.app, resolvedPath, THUMBNAIL_SIZE)
+                .then((imageUrl) => {
                     console.log('[ImageWidget] Image loaded successfully');
                     // Clear loading text and create image
                     container.empty();
-                    this.createImage(container, dataUrl);
+                    this.createImage(container, imageUrl);
                 })
                 .catch((error) => {
                     console.error('[ImageWidget] Failed to load image:', error);
@@ -56,3 +56,14 @@ export async function convertFileToDataUrl(app: App, filePath: string): Promise<
         throw error;
     }
 }
+
+/**
+ * URL to show a local image from: a thumbnail fitting in `maxSize` or the
+ * asset URL when the platform can serve files directly, otherwise a data URL
+ */
+export async function loadImageUrl(app: App, filePath: string, maxSize?: number): Promise<string> {
+    const thumbnailUrl =
+        maxSize !== undefined ? app.fileSystemManager.getThumbnailUrl(filePath, maxSize) : null;
+    const url = thumbnailUrl ?? app.fileSystemManager.getAssetUrl(filePath);
+    return url ?? convertFileToDataUrl(app, filePath);
+}
@@ -12,6 +12,8 @@ import {
     ValidationError,
     NetworkError,
     isAppError,
+    isNativeError,
+    fromNativeError,
     toAppError,
 } from '@inkdown/core';
 
@@ -66,6 +68,12 @@ describe('AppError', () => {
             
             expect(error.code).toBe('FILE_NOT_FOUND');
         });
+
+        it('should allow optional metadata', () => {
+            const error = new FileSystemError('test', '/path', 'tooLarge', { size: 10, limit: 5 });
+            
+            expect(error.metadata).toEqual({ size: 10, limit: 5 });
+        });
     });
 
     describe('ConfigError', () => {
@@ -164,6 +172,20 @@ describe('Error utilities', () => {
             expect(result.code).toBe('UNKNOWN_ERROR');
         });
 
+        it('should convert native errors with a path to FileSystemError', () => {
+            const result = toAppError({
+                code: 'notFound',
+                message: 'Path does not exist: /notes/a.md',
+                path: '/notes/a.md',
+                details: null,
+            });
+            
+            expect(result).toBeInstanceOf(FileSystemError);
+            expect(result.code).toBe('notFound');
+            expect((result as FileSystemError).path).toBe('/notes/a.md');
+            expect(result.metadata).toBeUndefined();
+        });
+
         it('should convert unknown values to AppError', () => {
             const result = toAppError({ weird: 'object' });
             
@@ -171,4 +193,43 @@ describe('Error utilities', () => {
             expect(result.code).toBe('UNKNOWN_ERROR');
         });
     });
+
+    describe('isNativeError', () => {
+        it('should return true for native command rejections', () => {
+            expect(isNativeError({ code: 'io', message: 'failed', path: null, details: null })).toBe(true);
+        });
+
+        it('should return false for other values', () => {
+            expect(isNativeError('failed')).toBe(false);
+            expect(isNativeError(null)).toBe(false);
+            expect(isNativeError({ message: 'failed' })).toBe(false);
+        });
+    });
+
+    describe('fromNativeError', () => {
+        it('should keep details as metadata', () => {
+            const result = fromNativeError({
+                code: 'tooLarge',
+                message: 'File is too large to open',
+                path: '/notes/big.log',
+                details: { size: 30, limit: 20 },
+            });
+            
+            expect(result).toBeInstanceOf(FileSystemError);
+            expect(result.metadata).toEqual({ size: 30, limit: 20 });
+        });
+
+        it('should return a plain AppError without a path', () => {
+            const result = fromNativeError({
+                code: 'invalidName',
+                message: 'Invalid name: ../other',
+                path: null,
+                details: { name: '../other' },
+            });
+            
+            expect(result).not.toBeInstanceOf(FileSystemError);
+            expect(result.code).toBe('invalidName');
+            expect(result.metadata).toEqual({ name: '../other' });
+        });
+    });
 });
@@ -2,6 +2,7 @@ import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { CommandManager } from '@inkdown/core/managers/CommandManager';
 import type { App } from '@inkdown/core/App';
 import type { Command } from '@inkdown/core/types/plugin';
+import type { CreatedNote } from '@inkdown/core/native';
 
 describe('CommandManager', () => {
     let commandManager: CommandManager;
@@ -15,7 +16,7 @@ describe('CommandManager', () => {
     const mockGetActiveTab = vi.fn();
     const mockSaveFile = vi.fn<(path: string) => Promise<void>>();
     const mockOpenTab = vi.fn<(path: string) => Promise<void>>();
-    const mockCreateNewNote = vi.fn<() => Promise<string>>();
+    const mockCreateNewNote = vi.fn<() => Promise<CreatedNote | null>>();
 
     beforeEach(() => {
         vi.clearAllMocks();
@@ -0,0 +1,170 @@
+/**
+ * FilesConfigManager Unit Tests
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+// Answers the prompt modal submits, or null to cancel it
+let mockPromptAnswers: Record<string, string> | null = {};
+
+// Mock the prompt modal so template prompts resolve without a DOM
+vi.mock('@inkdown/core/components/Modal', () => ({
+    PromptModal: class {
+        private submit?: (answers: Record<string, string>) => void;
+        private cancel?: () => void;
+
+        setTitle() {
+            return this;
+        }
+
+        setQuestions() {
+            return this;
+        }
+
+        setSubmitText() {
+            return this;
+        }
+
+        onSubmit(callback: (answers: Record<string, string>) => void) {
+            this.submit = callback;
+            return this;
+        }
+
+        onCancel(callback: () => void) {
+            this.cancel = callback;
+            return this;
+        }
+
+        open() {
+            if (mockPromptAnswers) {
+                this.submit?.(mockPromptAnswers);
+            } else {
+                this.cancel?.();
+            }
+        }
+    },
+}));
+
+const { FilesConfigManager } = await import('@inkdown/core/managers/FilesConfigManager');
+
+function createMockApp(config: Record<string, unknown> | null = null) {
+    return {
+        configManager: {
+            loadConfig: vi.fn().mockResolvedValue(config),
+            saveConfig: vi.fn().mockResolvedValue(undefined),
+        },
+        fileSystemManager: {
+            getWorkspacePath: vi.fn().mockReturnValue('/workspace'),
+            joinPath: (...parts: string[]) => parts.join('/').replace(/\/+/g, '/'),
+            exists: vi.fn().mockResolvedValue(false),
+            createDirectory: vi.fn().mockResolvedValue(undefined),
+            writeFile: vi.fn().mockResolvedValue(undefined),
+            listTemplates: vi.fn().mockResolvedValue([]),
+            templatePrompts: vi.fn().mockResolvedValue([]),
+            createFromTemplate: vi.fn(),
+        },
+    };
+}
+
+describe('FilesConfigManager', () => {
+    let app: ReturnType<typeof createMockApp>;
+
+    beforeEach(() => {
+        vi.clearAllMocks();
+        mockPromptAnswers = {};
+    });
+
+    describe('createNewNote', () => {
+        it('should create an empty Untitled note without a template', async () => {
+            app = createMockApp();
+            const manager = new FilesConfigManager(app as any);
+
+            const note = await manager.createNewNote();
+
+            expect(note).toEqual({ path: '/workspace/Untitled.md', cursor: null });
+            expect(app.fileSystemManager.writeFile).toHaveBeenCalledWith('/workspace/Untitled.md', '');
+            expect(app.fileSystemManager.createFromTemplate).not.toHaveBeenCalled();
+        });
+
+        it('should create the note from the configured template', async () => {
+            app = createMockApp({
+                newNotesLocation: 'folder',
+                newNotesFolder: 'notes',
+                newNoteTemplate: 'Daily',
+            });
+            app.fileSystemManager.createFromTemplate.mockResolvedValue({
+                path: '/workspace/notes/Untitled.md',
+                cursor: 12,
+            });
+            const manager = new FilesConfigManager(app as any);
+
+            const note = await manager.createNewNote();
+
+            expect(note).toEqual({ path: '/workspace/notes/Untitled.md', cursor: 12 });
+            expect(app.fileSystemManager.createFromTemplate).toHaveBeenCalledWith(
+                '/workspace/Templates/Daily.md',
+                'notes/{{title}}',
+                {},
+                { autoRename: true },
+            );
+            expect(app.fileSystemManager.writeFile).not.toHaveBeenCalled();
+        });
+
+        it('should fall back to an empty note when templates are unavailable', async () => {
+            app = createMockApp({ newNoteTemplate: 'Daily' });
+            app.fileSystemManager.createFromTemplate.mockResolvedValue(null);
+            const manager = new FilesConfigManager(app as any);
+
+            const note = await manager.createNewNote();
+
+            expect(note).toEqual({ path: '/workspace/Untitled.md', cursor: null });
+            expect(app.fileSystemManager.writeFile).toHaveBeenCalledWith('/workspace/Untitled.md', '');
+        });
+
+        it('should pass prompt answers to the template', async () => {
+            app = createMockApp({ newNoteTemplate: 'Meeting' });
+            app.fileSystemManager.templatePrompts.mockResolvedValue(['Topic']);
+            app.fileSystemManager.createFromTemplate.mockResolvedValue({
+                path: '/workspace/Roadmap.md',
+                cursor: null,
+            });
+            mockPromptAnswers = { Topic: 'Roadmap' };
+            const manager = new FilesConfigManager(app as any);
+
+            const note = await manager.createNewNote();
+
+            expect(note).toEqual({ path: '/workspace/Roadmap.md', cursor: null });
+            expect(app.fileSystemManager.templatePrompts).toHaveBeenCalledWith('/workspace/Templates/Meeting.md');
+            expect(app.fileSystemManager.createFromTemplate).toHaveBeenCalledWith(
+                '/workspace/Templates/Meeting.md',
+                '{{title}}',
+                { Topic: 'Roadmap' },
+                { autoRename: true },
+            );
+        });
+
+        it('should return null when the template prompt is cancelled', async () => {
+            app = createMockApp({ newNoteTemplate: 'Meeting' });
+            app.fileSystemManager.templatePrompts.mockResolvedValue(['Topic']);
+            mockPromptAnswers = null;
+            const manager = new FilesConfigManager(app as any);
+
+            const note = await manager.createNewNote();
+
+            expect(note).toBeNull();
+            expect(app.fileSystemManager.createFromTemplate).not.toHaveBeenCalled();
+            expect(app.fileSystemManager.writeFile).not.toHaveBeenCalled();
+        });
+    });
+
+    describe('listTemplates', () => {
+        it('should list templates in the configured folder', async () => {
+            app = createMockApp({ templatesFolder: 'meta/templates' });
+            const manager = new FilesConfigManager(app as any);
+
+            await manager.listTemplates();
+
+            expect(app.fileSystemManager.listTemplates).toHaveBeenCalledWith('/workspace/meta/templates');
+        });
+    });
+});
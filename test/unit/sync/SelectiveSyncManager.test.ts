@@ -0,0 +1,130 @@
+/**
+ * SelectiveSyncManager Unit Tests
+ */
+
+import { describe, expect, it, vi } from 'vitest';
+import {
+    matchesIgnoreRules,
+    parseIgnoreRule,
+    SelectiveSyncManager,
+} from '../../../packages/core/src/sync/SelectiveSyncManager';
+
+function rules(...lines: string[]) {
+    return lines.map(parseIgnoreRule).filter((rule) => rule !== null);
+}
+
+function createManager(ignoreFilePatterns: string[], workspacePath = '/workspace') {
+    const app = {
+        configManager: {
+            loadConfig: vi.fn().mockResolvedValue(null),
+            saveConfig: vi.fn().mockResolvedValue(undefined),
+        },
+        fileSystemManager: {
+            readIgnorePatterns: vi.fn().mockResolvedValue(ignoreFilePatterns),
+            getWorkspacePath: vi.fn().mockReturnValue(workspacePath),
+        },
+    };
+    return { app, manager: new SelectiveSyncManager(app as any) };
+}
+
+describe('parseIgnoreRule', () => {
+    it('should skip blank lines and comments', () => {
+        expect(parseIgnoreRule('')).toBeNull();
+        expect(parseIgnoreRule('   ')).toBeNull();
+        expect(parseIgnoreRule('# build output')).toBeNull();
+    });
+
+    it('should match unanchored patterns at any depth', () => {
+        expect(parseIgnoreRule('*.log')).toEqual({ glob: '**/*.log', negated: false, dirOnly: false });
+    });
+
+    it('should anchor patterns containing a slash', () => {
+        expect(parseIgnoreRule('/build')?.glob).toBe('build');
+        expect(parseIgnoreRule('docs/drafts')?.glob).toBe('docs/drafts');
+    });
+
+    it('should parse negation and directory-only patterns', () => {
+        expect(parseIgnoreRule('!keep.log')).toEqual({
+            glob: '**/keep.log',
+            negated: true,
+            dirOnly: false,
+        });
+        expect(parseIgnoreRule('cache/')).toEqual({ glob: '**/cache', negated: false, dirOnly: true });
+    });
+});
+
+describe('matchesIgnoreRules', () => {
+    it('should ignore files matching a pattern at any depth', () => {
+        const ignore = rules('*.log');
+        expect(matchesIgnoreRules('debug.log', ignore)).toBe(true);
+        expect(matchesIgnoreRules('notes/debug.log', ignore)).toBe(true);
+        expect(matchesIgnoreRules('notes/debug.md', ignore)).toBe(false);
+    });
+
+    it('should ignore everything inside an ignored folder', () => {
+        const ignore = rules('node_modules/');
+        expect(matchesIgnoreRules('node_modules/pkg/readme.md', ignore)).toBe(true);
+        expect(matchesIgnoreRules('notes/node_modules/readme.md', ignore)).toBe(true);
+    });
+
+    it('should only match directory-only patterns against folders', () => {
+        const ignore = rules('cache/');
+        expect(matchesIgnoreRules('cache', ignore)).toBe(false);
+        expect(matchesIgnoreRules('cache/data.md', ignore)).toBe(true);
+    });
+
+    it('should only match anchored patterns at the root', () => {
+        const ignore = rules('/drafts');
+        expect(matchesIgnoreRules('drafts/idea.md', ignore)).toBe(true);
+        expect(matchesIgnoreRules('notes/drafts/idea.md', ignore)).toBe(false);
+    });
+
+    it('should let the last matching rule win', () => {
+        const ignore = rules('*.md', '!keep.md');
+        expect(matchesIgnoreRules('drop.md', ignore)).toBe(true);
+        expect(matchesIgnoreRules('keep.md', ignore)).toBe(false);
+    });
+
+    it('should match dot-files', () => {
+        expect(matchesIgnoreRules('.obsidian/workspace.json', rules('.obsidian/'))).toBe(true);
+    });
+});
+
+describe('SelectiveSyncManager', () => {
+    it('should apply the workspace ignore files after init', async () => {
+        const { manager } = createManager(['private/', '*.tmp.md']);
+        expect(manager.shouldIgnore('private/journal.md')).toBe(false);
+
+        await manager.init();
+
+        expect(manager.shouldIgnore('private/journal.md')).toBe(true);
+        expect(manager.shouldIgnore('notes/draft.tmp.md')).toBe(true);
+        expect(manager.shouldIgnore('notes/idea.md')).toBe(false);
+    });
+
+    it('should match absolute paths relative to the workspace', async () => {
+        const { manager } = createManager(['/private']);
+        await manager.init();
+
+        expect(manager.shouldIgnore('/workspace/private/journal.md')).toBe(true);
+        expect(manager.shouldIgnore('/workspace/notes/private/journal.md')).toBe(false);
+    });
+
+    it('should pick up changed ignore files on reload', async () => {
+        const { app, manager } = createManager([]);
+        await manager.init();
+        expect(manager.shouldIgnore('archive/old.md')).toBe(false);
+
+        app.fileSystemManager.readIgnorePatterns.mockResolvedValue(['archive/']);
+        await manager.reloadWorkspaceIgnoreRules();
+
+        expect(manager.shouldIgnore('archive/old.md')).toBe(true);
+    });
+
+    it('should keep the configured sync patterns', async () => {
+        const { manager } = createManager([]);
+        await manager.init();
+
+        expect(manager.shouldIgnore('.trash/old.md')).toBe(true);
+    });
+});